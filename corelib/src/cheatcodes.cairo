@@ -4,6 +4,8 @@ use clone::Clone;
 use integer::Into;
 use integer::TryInto;
 use option::OptionTrait;
+use serde::Serde;
+use starknet::account::Call;
 use starknet::testing::cheatcode;
 
 #[derive(Drop, Clone)]
@@ -12,7 +14,7 @@ struct PreparedContract {
     constructor_calldata: @Array::<felt252>,
 }
 
-#[derive(Drop, Clone)]
+#[derive(Drop, Clone, Serde)]
 struct RevertedTransaction {
     panic_data: Array::<felt252>,
 }
@@ -27,6 +29,13 @@ impl RevertedTransactionImpl of RevertedTransactionTrait {
     }
 }
 
+#[derive(Drop, Clone, Serde)]
+enum DeployError {
+    ClassNotDeclared: felt252,
+    ConstructorReverted: RevertedTransaction,
+    AlreadyDeployed: felt252,
+}
+
 fn declare(contract: felt252) -> Result::<felt252, felt252> {
     let span = cheatcode::<'declare'>(array![contract].span());
 
@@ -40,9 +49,33 @@ fn declare(contract: felt252) -> Result::<felt252, felt252> {
     }
 }
 
-fn deploy(prepared_contract: PreparedContract) -> Result::<felt252, RevertedTransaction> {
+fn deploy(prepared_contract: PreparedContract) -> Result::<felt252, DeployError> {
+    deploy_with(prepared_contract, Option::None(()), Option::None(()))
+}
+
+// Deploys the contract at the given address instead of the one calculated from its class hash and calldata
+fn deploy_at(
+    prepared_contract: PreparedContract, contract_address: felt252
+) -> Result::<felt252, DeployError> {
+    deploy_with(prepared_contract, Option::None(()), Option::Some(contract_address))
+}
+
+// Deploys the contract from the given address instead of the test account
+fn deploy_from(
+    prepared_contract: PreparedContract, deployer_address: felt252
+) -> Result::<felt252, DeployError> {
+    deploy_with(prepared_contract, Option::Some(deployer_address), Option::None(()))
+}
+
+fn deploy_with(
+    prepared_contract: PreparedContract,
+    deployer_address: Option<felt252>,
+    contract_address: Option<felt252>
+) -> Result::<felt252, DeployError> {
     let PreparedContract{class_hash, constructor_calldata } = prepared_contract;
     let mut inputs = array![class_hash];
+    deployer_address.serialize(ref inputs);
+    contract_address.serialize(ref inputs);
 
     let calldata_len_felt = constructor_calldata.len().into();
     inputs.append(calldata_len_felt);
@@ -57,27 +90,209 @@ fn deploy(prepared_contract: PreparedContract) -> Result::<felt252, RevertedTran
         i += 1;
     };
 
-    let outputs = cheatcode::<'deploy'>(inputs.span());
-    let exit_code = *outputs[0];
+    let mut outputs = cheatcode::<'deploy'>(inputs.span());
+    let exit_code = *outputs.pop_front().unwrap();
 
     if exit_code == 0 {
-        let result = *outputs[1];
-        Result::<felt252, RevertedTransaction>::Ok(result)
+        let result = *outputs[0];
+        Result::<felt252, DeployError>::Ok(result)
     } else {
-        let panic_data_len_felt = *outputs[1];
-        let panic_data_len = panic_data_len_felt.try_into().unwrap();
-        let mut panic_data = array![];
-
-        let offset = 2;
-        let mut i = offset;
-        loop {
-            if panic_data_len + offset == i {
-                break ();
-            }
-            panic_data.append(*outputs[i]);
-            i += 1;
-        };
-
-        Result::<felt252, RevertedTransaction>::Err(RevertedTransaction { panic_data })
+        let error = Serde::<DeployError>::deserialize(ref outputs)
+            .expect('Failed to decode deploy error');
+        Result::<felt252, DeployError>::Err(error)
     }
 }
+
+// Returns the address the contract would be deployed at by `deploy`, e.g. to set up cheats for its constructor
+fn precalculate_address(prepared_contract: @PreparedContract) -> felt252 {
+    let mut inputs = array![*prepared_contract.class_hash];
+    let constructor_calldata = *prepared_contract.constructor_calldata;
+    constructor_calldata.serialize(ref inputs);
+    let outputs = cheatcode::<'precalculate_address'>(inputs.span());
+    *outputs[0]
+}
+
+// Changes the caller address returned by `get_caller_address` in the contract, until `stop_prank` is called
+fn start_prank(contract_address: felt252, caller_address: felt252) {
+    cheatcode::<'start_prank'>(array![contract_address, caller_address].span());
+}
+
+fn stop_prank(contract_address: felt252) {
+    cheatcode::<'stop_prank'>(array![contract_address].span());
+}
+
+// Changes the block number returned by `get_block_number` in the contract, until `stop_roll` is called
+fn start_roll(contract_address: felt252, block_number: u64) {
+    cheatcode::<'start_roll'>(array![contract_address, block_number.into()].span());
+}
+
+fn stop_roll(contract_address: felt252) {
+    cheatcode::<'stop_roll'>(array![contract_address].span());
+}
+
+// Changes the block timestamp returned by `get_block_timestamp` in the contract, until `stop_warp` is called
+fn start_warp(contract_address: felt252, block_timestamp: u64) {
+    cheatcode::<'start_warp'>(array![contract_address, block_timestamp.into()].span());
+}
+
+fn stop_warp(contract_address: felt252) {
+    cheatcode::<'stop_warp'>(array![contract_address].span());
+}
+
+// Makes calls to the function of the contract return the data without executing the function,
+// until `stop_mock_call` is called
+fn mock_call(contract_address: felt252, function_name: felt252, ret_data: Array<felt252>) {
+    let mut inputs = array![contract_address, function_name];
+    ret_data.serialize(ref inputs);
+    cheatcode::<'mock_call'>(inputs.span());
+}
+
+fn stop_mock_call(contract_address: felt252, function_name: felt252) {
+    cheatcode::<'stop_mock_call'>(array![contract_address, function_name].span());
+}
+
+#[derive(Drop, Clone, Serde, PartialEq)]
+enum ReplaceBytecodeError {
+    ContractNotDeployed: (),
+    UndeclaredClassHash: (),
+}
+
+// Replaces the class of the deployed contract, like `replace_class_syscall` called by the contract itself
+fn replace_bytecode(
+    contract_address: felt252, new_class_hash: felt252
+) -> Result::<(), ReplaceBytecodeError> {
+    let mut outputs = cheatcode::<'replace_bytecode'>(array![contract_address, new_class_hash].span());
+    let exit_code = *outputs.pop_front().unwrap();
+
+    if exit_code == 0 {
+        Result::<(), ReplaceBytecodeError>::Ok(())
+    } else {
+        let error = Serde::<ReplaceBytecodeError>::deserialize(ref outputs)
+            .expect('Failed to decode error');
+        Result::<(), ReplaceBytecodeError>::Err(error)
+    }
+}
+
+// Returns the class hash of the contract deployed at the address, 0 if no contract is deployed there
+fn get_class_hash(contract_address: felt252) -> felt252 {
+    let outputs = cheatcode::<'get_class_hash'>(array![contract_address].span());
+    *outputs[0]
+}
+
+fn is_deployed(contract_address: felt252) -> bool {
+    let outputs = cheatcode::<'is_deployed'>(array![contract_address].span());
+    *outputs[0] == 1
+}
+
+fn get_nonce(contract_address: felt252) -> felt252 {
+    let outputs = cheatcode::<'get_nonce'>(array![contract_address].span());
+    *outputs[0]
+}
+
+// Sets the balance of the fee token of the given address
+fn deal(address: felt252, amount: u256) {
+    cheatcode::<'deal'>(array![address, amount.low.into(), amount.high.into()].span());
+}
+
+// Invoke transaction executing the calls with `__execute__` of the sender account
+#[derive(Drop, Serde)]
+struct InvokeTransaction {
+    sender_address: felt252,
+    calls: Array<Call>,
+}
+
+// Declare transaction of the contract with the given name sent from the sender account
+#[derive(Drop, Clone, Serde)]
+struct DeclareTransaction {
+    sender_address: felt252,
+    contract: felt252,
+}
+
+// Deploy account transaction deploying the account, which pays for its own deployment
+#[derive(Drop, Clone, Serde)]
+struct DeployAccountTransaction {
+    class_hash: felt252,
+    contract_address_salt: felt252,
+    constructor_calldata: Array<felt252>,
+}
+
+#[derive(Drop, Serde)]
+enum AccountTransaction {
+    Invoke: InvokeTransaction,
+    Declare: DeclareTransaction,
+    DeployAccount: DeployAccountTransaction,
+}
+
+#[derive(Drop, Clone, Serde)]
+enum TransactionError {
+    // `__validate__`, `__validate_declare__` or `__validate_deploy__` failed and the transaction was rejected
+    ValidationFailed: RevertedTransaction,
+    // Execution failed and the transaction was reverted, the panic data contains the revert reason as short strings
+    ExecutionReverted: RevertedTransaction,
+}
+
+// Returns the hash of the transaction to be signed, it depends on the current nonce of the sender
+fn transaction_hash(transaction: @AccountTransaction) -> felt252 {
+    let mut inputs = ArrayTrait::new();
+    transaction.serialize(ref inputs);
+    let outputs = cheatcode::<'transaction_hash'>(inputs.span());
+    *outputs[0]
+}
+
+// Executes the transaction with the signature, returns the return data of `__execute__` for invoke transactions,
+// the class hash for declare transactions and the account address for deploy account transactions
+fn execute_transaction(
+    transaction: AccountTransaction, signature: Array<felt252>
+) -> Result::<Array<felt252>, TransactionError> {
+    let mut inputs = ArrayTrait::new();
+    transaction.serialize(ref inputs);
+    signature.serialize(ref inputs);
+
+    let mut outputs = cheatcode::<'execute_transaction'>(inputs.span());
+    let exit_code = *outputs.pop_front().unwrap();
+
+    if exit_code == 0 {
+        let result = Serde::<Array<felt252>>::deserialize(ref outputs)
+            .expect('Failed to decode result');
+        Result::<Array<felt252>, TransactionError>::Ok(result)
+    } else {
+        let error = Serde::<TransactionError>::deserialize(ref outputs)
+            .expect('Failed to decode error');
+        Result::<Array<felt252>, TransactionError>::Err(error)
+    }
+}
+
+// Returns the address of the account deployed with a deploy account transaction
+fn deploy_account_address(
+    class_hash: felt252, contract_address_salt: felt252, constructor_calldata: @Array<felt252>
+) -> felt252 {
+    let mut inputs = array![class_hash, contract_address_salt];
+    constructor_calldata.serialize(ref inputs);
+    let outputs = cheatcode::<'deploy_account_address'>(inputs.span());
+    *outputs[0]
+}
+
+// Stark key pair used to sign transactions of accounts deployed in the test
+#[derive(Drop, Copy, Serde)]
+struct StarkKeyPair {
+    private_key: felt252,
+    public_key: felt252,
+}
+
+// Generates a random key pair
+fn generate_stark_keys() -> StarkKeyPair {
+    let mut outputs = cheatcode::<'generate_stark_keys'>(array![].span());
+    Serde::<StarkKeyPair>::deserialize(ref outputs).expect('Failed to decode key pair')
+}
+
+// Derives a key pair from the seed, the same seed always gives the same keys
+fn derive_stark_keys(seed: felt252) -> StarkKeyPair {
+    let mut outputs = cheatcode::<'derive_stark_keys'>(array![seed].span());
+    Serde::<StarkKeyPair>::deserialize(ref outputs).expect('Failed to decode key pair')
+}
+
+// Signs the message hash with the private key, returns the `(r, s)` signature
+fn sign_message_hash(private_key: felt252, message_hash: felt252) -> (felt252, felt252) {
+    let outputs = cheatcode::<'sign_message_hash'>(array![private_key, message_hash].span());
+    (*outputs[0], *outputs[1])
+}
@@ -1,6 +1,10 @@
 use array::ArrayTrait;
+use array::SpanTrait;
 use traits::Into;
 use option::Option;
+use result::Result;
+use serde::Serde;
+use starknet::ClassHashIntoFelt252;
 use starknet::ContractAddressIntoFelt252;
 use starknet::testing::cheatcode;
 
@@ -68,3 +72,249 @@ impl ArrayGenericPrintImpl of PrintTrait<Array<felt252>> {
         cheatcode::<'print'>(self.span());
     }
 }
+
+// Typed formatting, rendered by the `println` cheatcode.
+// Every value is encoded as its kind followed by its data.
+
+// value
+const FELT252: felt252 = 0;
+// value
+const BOOL: felt252 = 1;
+// value
+const UNSIGNED: felt252 = 2;
+// value, negative values wrap around the field prime
+const SIGNED: felt252 = 3;
+// low, high
+const U256: felt252 = 4;
+// value, rendered as hex
+const ADDRESS: felt252 = 5;
+// length, values
+const SEQUENCE: felt252 = 6;
+// length, values
+const TUPLE: felt252 = 7;
+// name, fields count, field name and value of each field
+const STRUCT: felt252 = 8;
+// name, variant name, value
+const ENUM: felt252 = 9;
+
+// Implemented for user structs and enums with `#[derive(Format)]`
+trait Format<T> {
+    fn fmt(self: @T, ref output: Array<felt252>);
+}
+
+// Prints the format string with every `{}` replaced by the next argument, like Rust `println!`.
+// The format string is split into short strings, arguments are written with `Format::fmt`.
+fn println(format: Array<felt252>, args: Array<felt252>) {
+    let mut inputs = ArrayTrait::new();
+    format.serialize(ref inputs);
+    let mut args = args;
+    loop {
+        match args.pop_front() {
+            Option::Some(value) => inputs.append(value),
+            Option::None(()) => {
+                break ();
+            },
+        };
+    };
+    cheatcode::<'println'>(inputs.span());
+}
+
+fn fmt_value(kind: felt252, value: felt252, ref output: Array<felt252>) {
+    output.append(kind);
+    output.append(value);
+}
+
+impl Felt252Format of Format<felt252> {
+    fn fmt(self: @felt252, ref output: Array<felt252>) {
+        fmt_value(FELT252, *self, ref output);
+    }
+}
+
+impl BoolFormat of Format<bool> {
+    fn fmt(self: @bool, ref output: Array<felt252>) {
+        fmt_value(BOOL, if *self {
+            1
+        } else {
+            0
+        }, ref output);
+    }
+}
+
+impl U8Format of Format<u8> {
+    fn fmt(self: @u8, ref output: Array<felt252>) {
+        fmt_value(UNSIGNED, (*self).into(), ref output);
+    }
+}
+
+impl U16Format of Format<u16> {
+    fn fmt(self: @u16, ref output: Array<felt252>) {
+        fmt_value(UNSIGNED, (*self).into(), ref output);
+    }
+}
+
+impl U32Format of Format<u32> {
+    fn fmt(self: @u32, ref output: Array<felt252>) {
+        fmt_value(UNSIGNED, (*self).into(), ref output);
+    }
+}
+
+impl U64Format of Format<u64> {
+    fn fmt(self: @u64, ref output: Array<felt252>) {
+        fmt_value(UNSIGNED, (*self).into(), ref output);
+    }
+}
+
+impl U128Format of Format<u128> {
+    fn fmt(self: @u128, ref output: Array<felt252>) {
+        fmt_value(UNSIGNED, (*self).into(), ref output);
+    }
+}
+
+impl U256Format of Format<u256> {
+    fn fmt(self: @u256, ref output: Array<felt252>) {
+        output.append(U256);
+        output.append((*self.low).into());
+        output.append((*self.high).into());
+    }
+}
+
+impl I8Format of Format<i8> {
+    fn fmt(self: @i8, ref output: Array<felt252>) {
+        fmt_value(SIGNED, (*self).into(), ref output);
+    }
+}
+
+impl I16Format of Format<i16> {
+    fn fmt(self: @i16, ref output: Array<felt252>) {
+        fmt_value(SIGNED, (*self).into(), ref output);
+    }
+}
+
+impl I32Format of Format<i32> {
+    fn fmt(self: @i32, ref output: Array<felt252>) {
+        fmt_value(SIGNED, (*self).into(), ref output);
+    }
+}
+
+impl I64Format of Format<i64> {
+    fn fmt(self: @i64, ref output: Array<felt252>) {
+        fmt_value(SIGNED, (*self).into(), ref output);
+    }
+}
+
+impl I128Format of Format<i128> {
+    fn fmt(self: @i128, ref output: Array<felt252>) {
+        fmt_value(SIGNED, (*self).into(), ref output);
+    }
+}
+
+impl ContractAddressFormat of Format<starknet::ContractAddress> {
+    fn fmt(self: @starknet::ContractAddress, ref output: Array<felt252>) {
+        fmt_value(ADDRESS, (*self).into(), ref output);
+    }
+}
+
+impl ClassHashFormat of Format<starknet::ClassHash> {
+    fn fmt(self: @starknet::ClassHash, ref output: Array<felt252>) {
+        fmt_value(ADDRESS, (*self).into(), ref output);
+    }
+}
+
+impl SpanFormat<T, impl TFormat: Format<T>> of Format<Span<T>> {
+    fn fmt(self: @Span<T>, ref output: Array<felt252>) {
+        let mut span = *self;
+        output.append(SEQUENCE);
+        output.append(span.len().into());
+        loop {
+            match span.pop_front() {
+                Option::Some(value) => TFormat::fmt(value, ref output),
+                Option::None(()) => {
+                    break ();
+                },
+            };
+        };
+    }
+}
+
+impl ArrayFormat<T, impl TFormat: Format<T>> of Format<Array<T>> {
+    fn fmt(self: @Array<T>, ref output: Array<felt252>) {
+        SpanFormat::<T, TFormat>::fmt(@self.span(), ref output);
+    }
+}
+
+impl TupleSize0Format of Format<()> {
+    fn fmt(self: @(), ref output: Array<felt252>) {
+        output.append(TUPLE);
+        output.append(0);
+    }
+}
+
+impl TupleSize1Format<E0, impl E0Format: Format<E0>> of Format<(E0, )> {
+    fn fmt(self: @(E0, ), ref output: Array<felt252>) {
+        let (e0, ) = self;
+        output.append(TUPLE);
+        output.append(1);
+        E0Format::fmt(e0, ref output);
+    }
+}
+
+impl TupleSize2Format<
+    E0, E1, impl E0Format: Format<E0>, impl E1Format: Format<E1>
+> of Format<(E0, E1)> {
+    fn fmt(self: @(E0, E1), ref output: Array<felt252>) {
+        let (e0, e1) = self;
+        output.append(TUPLE);
+        output.append(2);
+        E0Format::fmt(e0, ref output);
+        E1Format::fmt(e1, ref output);
+    }
+}
+
+impl TupleSize3Format<
+    E0, E1, E2, impl E0Format: Format<E0>, impl E1Format: Format<E1>, impl E2Format: Format<E2>
+> of Format<(E0, E1, E2)> {
+    fn fmt(self: @(E0, E1, E2), ref output: Array<felt252>) {
+        let (e0, e1, e2) = self;
+        output.append(TUPLE);
+        output.append(3);
+        E0Format::fmt(e0, ref output);
+        E1Format::fmt(e1, ref output);
+        E2Format::fmt(e2, ref output);
+    }
+}
+
+impl OptionFormat<T, impl TFormat: Format<T>> of Format<Option<T>> {
+    fn fmt(self: @Option<T>, ref output: Array<felt252>) {
+        output.append(ENUM);
+        output.append('Option');
+        match self {
+            Option::Some(value) => {
+                output.append('Some');
+                TFormat::fmt(value, ref output);
+            },
+            Option::None(_) => {
+                output.append('None');
+                TupleSize0Format::fmt(@(), ref output);
+            },
+        }
+    }
+}
+
+impl ResultFormat<
+    T, E, impl TFormat: Format<T>, impl EFormat: Format<E>
+> of Format<Result<T, E>> {
+    fn fmt(self: @Result<T, E>, ref output: Array<felt252>) {
+        output.append(ENUM);
+        output.append('Result');
+        match self {
+            Result::Ok(value) => {
+                output.append('Ok');
+                TFormat::fmt(value, ref output);
+            },
+            Result::Err(error) => {
+                output.append('Err');
+                EFormat::fmt(error, ref output);
+            },
+        }
+    }
+}
@@ -310,6 +310,28 @@ use starknet::System;
 mod cheatcodes;
 use cheatcodes::declare;
 use cheatcodes::deploy;
+use cheatcodes::deploy_at;
+use cheatcodes::deploy_from;
+use cheatcodes::precalculate_address;
+use cheatcodes::replace_bytecode;
+use cheatcodes::get_class_hash;
+use cheatcodes::is_deployed;
+use cheatcodes::get_nonce;
+use cheatcodes::start_prank;
+use cheatcodes::stop_prank;
+use cheatcodes::start_roll;
+use cheatcodes::stop_roll;
+use cheatcodes::start_warp;
+use cheatcodes::stop_warp;
+use cheatcodes::mock_call;
+use cheatcodes::stop_mock_call;
+use cheatcodes::deal;
+use cheatcodes::transaction_hash;
+use cheatcodes::execute_transaction;
+use cheatcodes::deploy_account_address;
+use cheatcodes::generate_stark_keys;
+use cheatcodes::derive_stark_keys;
+use cheatcodes::sign_message_hash;
 use cheatcodes::PreparedContract;
 
 mod forge_print;
@@ -5,7 +5,7 @@ use blockifier::{
     block_context::BlockContext,
     execution::{
         contract_class::{ContractClass, ContractClassV0},
-        execution_utils::felt_to_stark_felt,
+        execution_utils::{felt_to_stark_felt, stark_felt_to_felt},
     },
     state::cached_state::CachedState,
     transaction::objects::AccountTransactionContext,
@@ -48,7 +48,7 @@ pub fn build_block_context() -> BlockContext {
         block_timestamp: BlockTimestamp::default(),
         sequencer_address: ContractAddress(patricia_key!(TEST_SEQUENCER_ADDRESS)),
         fee_token_address: ContractAddress(patricia_key!(TEST_ERC20_CONTRACT_ADDRESS)),
-        vm_resource_fee_cost: HashMap::default(),
+        vm_resource_fee_cost: build_vm_resource_fee_cost(),
         gas_price: 100 * u128::pow(10, 9),
         invoke_tx_max_n_steps: 1_000_000,
         validate_max_n_steps: 1_000_000,
@@ -56,6 +56,20 @@ pub fn build_block_context() -> BlockContext {
     }
 }
 
+// L1 gas charged per unit of each VM resource, as on the mainnet
+fn build_vm_resource_fee_cost() -> HashMap<String, f64> {
+    HashMap::from([
+        ("n_steps".to_string(), 0.01),
+        ("pedersen_builtin".to_string(), 0.32),
+        ("range_check_builtin".to_string(), 0.16),
+        ("ecdsa_builtin".to_string(), 20.48),
+        ("bitwise_builtin".to_string(), 0.64),
+        ("poseidon_builtin".to_string(), 0.32),
+        ("output_builtin".to_string(), 0.0),
+        ("ec_op_builtin".to_string(), 10.24),
+    ])
+}
+
 #[must_use]
 pub fn build_transaction_context() -> AccountTransactionContext {
     AccountTransactionContext {
@@ -104,12 +118,13 @@ fn load_contract_class(
     ContractClassV0::try_from_json_string(&raw_contract_class).unwrap()
 }
 
-fn erc20_account_balance_key() -> StorageKey {
-    get_storage_var_address(
-        "ERC20_balances",
-        &[stark_felt!(TEST_ACCOUNT_CONTRACT_ADDRESS)],
-    )
-    .unwrap()
+// Storage keys of the low and high part of the `Uint256` balance of the address in the fee token
+#[must_use]
+pub fn erc20_balance_keys(address: ContractAddress) -> (StorageKey, StorageKey) {
+    let low_key = get_storage_var_address("ERC20_balances", &[*address.0.key()]).unwrap();
+    let high_key = stark_felt_to_felt(*low_key.0.key()) + Felt252::from(1);
+    let high_key = StorageKey(PatriciaKey::try_from(felt_to_stark_felt(&high_key)).unwrap());
+    (low_key, high_key)
 }
 
 // Creates a state with predeployed account and erc20 used to send transactions during tests.
@@ -146,7 +161,10 @@ pub fn build_testing_state(predeployed_contracts: &Utf8PathBuf) -> CachedState<D
         .expect("Failed to get permitted_minter storage address.");
     let storage_view = HashMap::from([
         (
-            (test_erc20_address, erc20_account_balance_key()),
+            (
+                test_erc20_address,
+                erc20_balance_keys(test_account_address).0,
+            ),
             stark_felt!(INITIAL_BALANCE),
         ),
         // Give the account mint permission.
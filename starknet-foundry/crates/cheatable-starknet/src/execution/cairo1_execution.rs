@@ -0,0 +1,88 @@
+use blockifier::execution::cairo1_execution::{
+    finalize_execution, initialize_execution_context, prepare_call_arguments, VmExecutionContext,
+};
+use blockifier::execution::contract_class::ContractClassV1;
+use blockifier::execution::entry_point::{
+    CallEntryPoint, CallInfo, EntryPointExecutionContext, EntryPointExecutionResult,
+    ExecutionResources,
+};
+use blockifier::execution::errors::VirtualMachineExecutionError;
+use blockifier::execution::execution_utils::Args;
+use blockifier::state::state_api::State;
+use cairo_vm::vm::runners::cairo_runner::{CairoArg, CairoRunner};
+use cairo_vm::vm::vm_core::VirtualMachine;
+
+use crate::execution::cheatable_syscall_handler::{CheatableSyscallHandler, CheatcodeHandler};
+use crate::state::CheatcodeState;
+
+/// Blockifier's `cairo1_execution::execute_entry_point_call` running the contract with `CheatableSyscallHandler`
+pub fn execute_entry_point_call_cairo1(
+    call: CallEntryPoint,
+    contract_class: &ContractClassV1,
+    state: &mut dyn State,
+    cheatcode_state: &mut CheatcodeState,
+    resources: &mut ExecutionResources,
+    context: &mut EntryPointExecutionContext,
+    cheatcode_handler: &mut dyn CheatcodeHandler,
+) -> EntryPointExecutionResult<CallInfo> {
+    let VmExecutionContext {
+        mut runner,
+        mut vm,
+        mut syscall_handler,
+        initial_syscall_ptr,
+        entry_point_pc,
+    } = initialize_execution_context(&call, contract_class, state, resources, context)?;
+
+    let args = prepare_call_arguments(
+        &syscall_handler.call,
+        &mut vm,
+        initial_syscall_ptr,
+        &mut syscall_handler.read_only_segments,
+    )?;
+    let n_total_args = args.len();
+
+    // Resources of the call are calculated as the difference from the resources before it
+    let previous_vm_resources = syscall_handler.resources.vm_resources.clone();
+
+    let mut cheatable_syscall_handler = CheatableSyscallHandler {
+        syscall_handler,
+        cheatcode_state,
+        cheatcode_handler,
+    };
+    run_entry_point(
+        &mut vm,
+        &mut runner,
+        &mut cheatable_syscall_handler,
+        entry_point_pc,
+        args,
+    )?;
+
+    Ok(finalize_execution(
+        vm,
+        runner,
+        cheatable_syscall_handler.syscall_handler,
+        previous_vm_resources,
+        n_total_args,
+    )?)
+}
+
+fn run_entry_point(
+    vm: &mut VirtualMachine,
+    runner: &mut CairoRunner,
+    hint_processor: &mut CheatableSyscallHandler,
+    entry_point_pc: usize,
+    args: Args,
+) -> Result<(), VirtualMachineExecutionError> {
+    let verify_secure = true;
+    let program_segment_size = None;
+    let args: Vec<&CairoArg> = args.iter().collect();
+    runner.run_from_entrypoint(
+        entry_point_pc,
+        &args,
+        verify_secure,
+        program_segment_size,
+        vm,
+        hint_processor,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,532 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use blockifier::abi::constants::{
+    CALL_CONTRACT_GAS_COST, DEPLOY_GAS_COST, GET_EXECUTION_INFO_GAS_COST, LIBRARY_CALL_GAS_COST,
+};
+use blockifier::execution::entry_point::{
+    CallEntryPoint, CallInfo, CallType, ConstructorContext, EntryPointExecutionResult,
+};
+use blockifier::execution::errors::EntryPointExecutionError;
+use blockifier::execution::execution_utils::{
+    felt_range_from_ptr, felt_to_stark_felt, stark_felt_to_felt,
+};
+use blockifier::execution::syscalls::hint_processor::{SyscallHintProcessor, OUT_OF_GAS_ERROR};
+use blockifier::execution::syscalls::SyscallSelector;
+use blockifier::state::state_api::State;
+use cairo_felt_blockifier::Felt252;
+use cairo_lang_casm::hints::{Hint, StarknetHint};
+use cairo_lang_casm::operand::{
+    BinOpOperand, CellRef, DerefOrImmediate, Operation, Register, ResOperand,
+};
+use cairo_vm::hint_processor::hint_processor_definition::{HintProcessor, HintReference};
+use cairo_vm::serde::deserialize_program::ApTracking;
+use cairo_vm::types::exec_scope::ExecutionScopes;
+use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
+use cairo_vm::vm::errors::hint_errors::HintError;
+use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
+use cairo_vm::vm::vm_core::VirtualMachine;
+use num_traits::{One, ToPrimitive};
+use starknet_api::core::{
+    calculate_contract_address, ClassHash, ContractAddress, EntryPointSelector, PatriciaKey,
+};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::{Calldata, ContractAddressSalt};
+
+use crate::execution::entry_point::{execute_call_entry_point, execute_deployment};
+use crate::state::{execute_reverting_on_failure, CheatcodeState};
+
+/// Executes the cheatcodes used by contracts, implemented by the runtime running the tests
+pub trait CheatcodeHandler {
+    /// Returns the cheatcode output, errors stop the execution of the contract
+    fn execute_cheatcode(
+        &mut self,
+        state: &mut dyn State,
+        cheatcode_state: &mut CheatcodeState,
+        selector: &str,
+        inputs: &[StarkFelt],
+    ) -> Result<Vec<StarkFelt>>;
+}
+
+/// Blockifier's syscall hint processor forwarding cheatcodes to the cheatcode handler.
+/// Calls, library calls and deployments are executed with it as well and the execution info
+/// of cheated contracts is replaced, so cheatcodes and cheats work at any call depth.
+pub struct CheatableSyscallHandler<'a> {
+    pub syscall_handler: SyscallHintProcessor<'a>,
+    pub cheatcode_state: &'a mut CheatcodeState,
+    pub cheatcode_handler: &'a mut dyn CheatcodeHandler,
+}
+
+impl HintProcessor for CheatableSyscallHandler<'_> {
+    fn execute_hint(
+        &mut self,
+        vm: &mut VirtualMachine,
+        exec_scopes: &mut ExecutionScopes,
+        hint_data: &Box<dyn Any>,
+        constants: &HashMap<String, Felt252>,
+    ) -> Result<(), HintError> {
+        match hint_data.downcast_ref::<Hint>() {
+            Some(Hint::Starknet(StarknetHint::Cheatcode {
+                selector,
+                input_start,
+                input_end,
+                output_start,
+                output_end,
+            })) => {
+                let selector = selector.value.to_bytes_be().1;
+                let selector = std::str::from_utf8(&selector).map_err(|_| {
+                    HintError::CustomHint("Failed to parse the cheatcode selector".to_string())
+                })?;
+                self.execute_cheatcode_hint(
+                    vm,
+                    selector,
+                    input_start,
+                    input_end,
+                    output_start,
+                    output_end,
+                )
+            }
+            Some(Hint::Starknet(StarknetHint::SystemCall { system })) => {
+                let syscall_ptr = relocatable_from_res_operand(vm, system)?;
+                match syscall_selector(vm, syscall_ptr) {
+                    Some(SyscallSelector::CallContract) => {
+                        self.execute_call_syscall(vm, syscall_ptr, CallType::Call)
+                    }
+                    Some(SyscallSelector::LibraryCall) => {
+                        self.execute_call_syscall(vm, syscall_ptr, CallType::Delegate)
+                    }
+                    Some(SyscallSelector::Deploy) => self.execute_deploy_syscall(vm, syscall_ptr),
+                    Some(SyscallSelector::GetExecutionInfo)
+                        if self
+                            .cheatcode_state
+                            .changes_execution_info(&self.syscall_handler.storage_address) =>
+                    {
+                        self.execute_get_execution_info_syscall(vm, syscall_ptr)
+                    }
+                    _ => self
+                        .syscall_handler
+                        .execute_hint(vm, exec_scopes, hint_data, constants),
+                }
+            }
+            _ => self
+                .syscall_handler
+                .execute_hint(vm, exec_scopes, hint_data, constants),
+        }
+    }
+
+    fn compile_hint(
+        &self,
+        hint_code: &str,
+        ap_tracking_data: &ApTracking,
+        reference_ids: &HashMap<String, usize>,
+        references: &HashMap<usize, HintReference>,
+    ) -> Result<Box<dyn Any>, VirtualMachineError> {
+        self.syscall_handler
+            .compile_hint(hint_code, ap_tracking_data, reference_ids, references)
+    }
+}
+
+impl CheatableSyscallHandler<'_> {
+    fn execute_cheatcode_hint(
+        &mut self,
+        vm: &mut VirtualMachine,
+        selector: &str,
+        input_start: &ResOperand,
+        input_end: &ResOperand,
+        output_start: &CellRef,
+        output_end: &CellRef,
+    ) -> Result<(), HintError> {
+        let input_start = relocatable_from_res_operand(vm, input_start)?;
+        let input_end = relocatable_from_res_operand(vm, input_end)?;
+        let inputs = felt_range_from_ptr(vm, input_start, (input_end - input_start)?)?;
+
+        let outputs = self
+            .cheatcode_handler
+            .execute_cheatcode(
+                self.syscall_handler.state,
+                self.cheatcode_state,
+                selector,
+                &inputs,
+            )
+            .map_err(|err| HintError::CustomHint(err.to_string()))?;
+
+        let (result_start, result_end) = write_felts(vm, &outputs)?;
+        vm.insert_value(cell_ref_to_relocatable(vm, output_start)?, result_start)?;
+        vm.insert_value(cell_ref_to_relocatable(vm, output_end)?, result_end)?;
+        Ok(())
+    }
+
+    /// Executes the call or library call with `execute_call_entry_point` instead of blockifier,
+    /// the syscall is laid out in memory as the request followed by the response
+    fn execute_call_syscall(
+        &mut self,
+        vm: &mut VirtualMachine,
+        syscall_ptr: Relocatable,
+        call_type: CallType,
+    ) -> Result<(), HintError> {
+        // Request: selector, gas, contract address or class hash, entry point selector,
+        // calldata start and end
+        let (selector, gas_cost) = match call_type {
+            CallType::Call => (SyscallSelector::CallContract, CALL_CONTRACT_GAS_COST),
+            CallType::Delegate => (SyscallSelector::LibraryCall, LIBRARY_CALL_GAS_COST),
+        };
+        let Some(gas) = self.charge_syscall(vm, syscall_ptr, selector, gas_cost, 6)? else {
+            return Ok(());
+        };
+        let target = felt_to_stark_felt(&vm.get_integer((syscall_ptr + 2)?)?);
+        let entry_point_selector = felt_to_stark_felt(&vm.get_integer((syscall_ptr + 3)?)?);
+        let calldata = calldata_from_request(vm, (syscall_ptr + 4)?)?;
+
+        let call = match call_type {
+            CallType::Call => {
+                let contract_address = contract_address_from_felt(target)?;
+                CallEntryPoint {
+                    class_hash: None,
+                    code_address: Some(contract_address),
+                    entry_point_type: EntryPointType::External,
+                    entry_point_selector: EntryPointSelector(entry_point_selector),
+                    calldata,
+                    storage_address: contract_address,
+                    caller_address: self.syscall_handler.storage_address,
+                    call_type,
+                    initial_gas: gas,
+                }
+            }
+            // Library calls are executed in the context of the calling contract
+            CallType::Delegate => CallEntryPoint {
+                class_hash: Some(ClassHash(target)),
+                code_address: None,
+                entry_point_type: EntryPointType::External,
+                entry_point_selector: EntryPointSelector(entry_point_selector),
+                calldata,
+                storage_address: self.syscall_handler.storage_address,
+                caller_address: self.syscall_handler.call.caller_address,
+                call_type,
+                initial_gas: gas,
+            },
+        };
+
+        let call_result = execute_reverting_on_failure(self.syscall_handler.state, |state| {
+            execute_call_entry_point(
+                call,
+                state,
+                self.cheatcode_state,
+                self.syscall_handler.resources,
+                self.syscall_handler.context,
+                self.cheatcode_handler,
+            )
+        });
+        let (gas_left, failed, retdata) = self.record_inner_call(call_result, gas)?;
+
+        // Response: gas left, failure flag, return data or panic data start and end
+        let (retdata_start, retdata_end) = write_felts(vm, &retdata)?;
+        let response_ptr = (syscall_ptr + 6)?;
+        vm.insert_value(response_ptr, Felt252::from(gas_left))?;
+        vm.insert_value((response_ptr + 1)?, Felt252::from(u8::from(failed)))?;
+        vm.insert_value((response_ptr + 2)?, retdata_start)?;
+        vm.insert_value((response_ptr + 3)?, retdata_end)?;
+
+        // Blockifier verifies the next syscall starts where the previous one ended
+        self.syscall_handler.syscall_ptr = (response_ptr + 4)?;
+        Ok(())
+    }
+
+    /// Deploys the contract with `execute_deployment`, so cheats are applied to its constructor
+    fn execute_deploy_syscall(
+        &mut self,
+        vm: &mut VirtualMachine,
+        syscall_ptr: Relocatable,
+    ) -> Result<(), HintError> {
+        // Request: selector, gas, class hash, salt, calldata start and end, deploy from zero flag
+        let Some(gas) =
+            self.charge_syscall(vm, syscall_ptr, SyscallSelector::Deploy, DEPLOY_GAS_COST, 7)?
+        else {
+            return Ok(());
+        };
+        let class_hash = ClassHash(felt_to_stark_felt(&vm.get_integer((syscall_ptr + 2)?)?));
+        let salt = ContractAddressSalt(felt_to_stark_felt(&vm.get_integer((syscall_ptr + 3)?)?));
+        let calldata = calldata_from_request(vm, (syscall_ptr + 4)?)?;
+        let deploy_from_zero = vm.get_integer((syscall_ptr + 6)?)?.is_one();
+
+        let deployer_address = self.syscall_handler.storage_address;
+        let contract_address = calculate_contract_address(
+            salt,
+            class_hash,
+            &calldata,
+            if deploy_from_zero {
+                ContractAddress::default()
+            } else {
+                deployer_address
+            },
+        )
+        .map_err(|err| HintError::CustomHint(err.to_string()))?;
+        let ctor_context = ConstructorContext {
+            class_hash,
+            code_address: Some(contract_address),
+            storage_address: contract_address,
+            caller_address: deployer_address,
+        };
+
+        let deploy_result = execute_reverting_on_failure(self.syscall_handler.state, |state| {
+            execute_deployment(
+                state,
+                self.cheatcode_state,
+                self.syscall_handler.resources,
+                self.syscall_handler.context,
+                ctor_context,
+                calldata,
+                gas,
+                self.cheatcode_handler,
+            )
+        });
+        let (gas_left, failed, retdata) = self.record_inner_call(deploy_result, gas)?;
+
+        // Response: gas left, failure flag, contract address if deployed,
+        // constructor return data or panic data start and end
+        let (retdata_start, retdata_end) = write_felts(vm, &retdata)?;
+        let mut response_ptr = (syscall_ptr + 7)?;
+        vm.insert_value(response_ptr, Felt252::from(gas_left))?;
+        vm.insert_value((response_ptr + 1)?, Felt252::from(u8::from(failed)))?;
+        response_ptr = (response_ptr + 2)?;
+        if !failed {
+            vm.insert_value(response_ptr, stark_felt_to_felt(*contract_address.0.key()))?;
+            response_ptr = (response_ptr + 1)?;
+        }
+        vm.insert_value(response_ptr, retdata_start)?;
+        vm.insert_value((response_ptr + 1)?, retdata_end)?;
+
+        self.syscall_handler.syscall_ptr = (response_ptr + 2)?;
+        Ok(())
+    }
+
+    /// Returns execution info with the cheated caller address, block number and block timestamp,
+    /// it is written to new memory segments since the ones allocated by blockifier can not be changed
+    fn execute_get_execution_info_syscall(
+        &mut self,
+        vm: &mut VirtualMachine,
+        syscall_ptr: Relocatable,
+    ) -> Result<(), HintError> {
+        // Request: selector, gas
+        let Some(gas) = self.charge_syscall(
+            vm,
+            syscall_ptr,
+            SyscallSelector::GetExecutionInfo,
+            GET_EXECUTION_INFO_GAS_COST,
+            2,
+        )?
+        else {
+            return Ok(());
+        };
+
+        let contract_address = self.syscall_handler.storage_address;
+        let block_context = &self.syscall_handler.context.block_context;
+        let block_number = self
+            .cheatcode_state
+            .rolled_contracts
+            .get(&contract_address)
+            .copied()
+            .unwrap_or(block_context.block_number);
+        let block_timestamp = self
+            .cheatcode_state
+            .warped_contracts
+            .get(&contract_address)
+            .copied()
+            .unwrap_or(block_context.block_timestamp);
+        let block_info_ptr = write_data(
+            vm,
+            &[
+                Felt252::from(block_number.0).into(),
+                Felt252::from(block_timestamp.0).into(),
+                stark_felt_to_felt(*block_context.sequencer_address.0.key()).into(),
+            ],
+        )?;
+
+        let tx_context = &self.syscall_handler.context.account_tx_context;
+        let (signature_start, signature_end) = write_felts(vm, &tx_context.signature.0)?;
+        let tx_info_ptr = write_data(
+            vm,
+            &[
+                stark_felt_to_felt(tx_context.version.0).into(),
+                stark_felt_to_felt(*tx_context.sender_address.0.key()).into(),
+                Felt252::from(tx_context.max_fee.0).into(),
+                signature_start.into(),
+                signature_end.into(),
+                stark_felt_to_felt(tx_context.transaction_hash.0).into(),
+                Felt252::from_bytes_be(block_context.chain_id.0.as_bytes()).into(),
+                stark_felt_to_felt(tx_context.nonce.0).into(),
+            ],
+        )?;
+
+        let caller_address = self
+            .cheatcode_state
+            .pranked_contracts
+            .get(&contract_address)
+            .copied()
+            .unwrap_or(self.syscall_handler.call.caller_address);
+        let execution_info_ptr = write_data(
+            vm,
+            &[
+                block_info_ptr.into(),
+                tx_info_ptr.into(),
+                stark_felt_to_felt(*caller_address.0.key()).into(),
+                stark_felt_to_felt(*contract_address.0.key()).into(),
+                stark_felt_to_felt(self.syscall_handler.call.entry_point_selector.0).into(),
+            ],
+        )?;
+
+        // Response: gas left, failure flag, execution info
+        let response_ptr = (syscall_ptr + 2)?;
+        vm.insert_value(response_ptr, Felt252::from(gas))?;
+        vm.insert_value((response_ptr + 1)?, Felt252::from(0))?;
+        vm.insert_value((response_ptr + 2)?, execution_info_ptr)?;
+
+        self.syscall_handler.syscall_ptr = (response_ptr + 3)?;
+        Ok(())
+    }
+
+    /// Counts the syscall and charges its base gas like blockifier's `execute_syscall`,
+    /// returns the gas left or `None` if there was not enough gas and the failure was written
+    fn charge_syscall(
+        &mut self,
+        vm: &mut VirtualMachine,
+        syscall_ptr: Relocatable,
+        selector: SyscallSelector,
+        gas_cost: u64,
+        request_size: usize,
+    ) -> Result<Option<u64>, HintError> {
+        *self
+            .syscall_handler
+            .resources
+            .syscall_counter
+            .entry(selector)
+            .or_default() += 1;
+
+        let gas = gas_from_request(vm, syscall_ptr)?;
+        if gas >= gas_cost {
+            return Ok(Some(gas - gas_cost));
+        }
+
+        // Response: gas left, failure flag, error data start and end
+        let out_of_gas_error = StarkFelt::try_from(OUT_OF_GAS_ERROR)
+            .map_err(|err| HintError::CustomHint(err.to_string()))?;
+        let (error_start, error_end) = write_felts(vm, &[out_of_gas_error])?;
+        let response_ptr = (syscall_ptr + request_size)?;
+        vm.insert_value(response_ptr, Felt252::from(gas))?;
+        vm.insert_value((response_ptr + 1)?, Felt252::from(1))?;
+        vm.insert_value((response_ptr + 2)?, error_start)?;
+        vm.insert_value((response_ptr + 3)?, error_end)?;
+
+        self.syscall_handler.syscall_ptr = (response_ptr + 4)?;
+        Ok(None)
+    }
+
+    /// Records the executed call, returning the gas left, failure flag and return or panic data
+    fn record_inner_call(
+        &mut self,
+        call_result: EntryPointExecutionResult<CallInfo>,
+        gas: u64,
+    ) -> Result<(u64, bool, Vec<StarkFelt>), HintError> {
+        match call_result {
+            Ok(call_info) => {
+                let gas_left = gas.saturating_sub(call_info.execution.gas_consumed);
+                let failed = call_info.execution.failed;
+                let retdata = call_info.execution.retdata.0.clone();
+                self.syscall_handler.inner_calls.push(call_info);
+                Ok((gas_left, failed, retdata))
+            }
+            Err(EntryPointExecutionError::ExecutionFailed { error_data }) => {
+                Ok((gas, true, error_data))
+            }
+            Err(err) => Err(HintError::CustomHint(err.to_string())),
+        }
+    }
+}
+
+/// Returns `None` for invalid selectors, which are reported by blockifier
+fn syscall_selector(vm: &VirtualMachine, syscall_ptr: Relocatable) -> Option<SyscallSelector> {
+    let selector = vm.get_integer(syscall_ptr).ok()?;
+    SyscallSelector::try_from(felt_to_stark_felt(&selector)).ok()
+}
+
+fn gas_from_request(vm: &VirtualMachine, syscall_ptr: Relocatable) -> Result<u64, HintError> {
+    vm.get_integer((syscall_ptr + 1)?)?
+        .to_u64()
+        .ok_or_else(|| HintError::CustomHint("Gas does not fit in u64".to_string()))
+}
+
+/// Reads the calldata from its start and end pointers written at the address
+fn calldata_from_request(vm: &VirtualMachine, ptr: Relocatable) -> Result<Calldata, HintError> {
+    let calldata_start = vm.get_relocatable(ptr)?;
+    let calldata_end = vm.get_relocatable((ptr + 1)?)?;
+    let calldata = felt_range_from_ptr(vm, calldata_start, (calldata_end - calldata_start)?)?;
+    Ok(Calldata(Arc::new(calldata)))
+}
+
+fn contract_address_from_felt(felt: StarkFelt) -> Result<ContractAddress, HintError> {
+    Ok(ContractAddress(
+        PatriciaKey::try_from(felt).map_err(|err| HintError::CustomHint(err.to_string()))?,
+    ))
+}
+
+/// Writes the data to a new memory segment, returning its start
+fn write_data(
+    vm: &mut VirtualMachine,
+    data: &[MaybeRelocatable],
+) -> Result<Relocatable, HintError> {
+    let start = vm.add_memory_segment();
+    vm.load_data(start, &data.to_vec())?;
+    Ok(start)
+}
+
+/// Writes the felts to a new memory segment, returning its start and end
+fn write_felts(
+    vm: &mut VirtualMachine,
+    felts: &[StarkFelt],
+) -> Result<(Relocatable, Relocatable), HintError> {
+    let data: Vec<MaybeRelocatable> = felts
+        .iter()
+        .map(|felt| MaybeRelocatable::from(stark_felt_to_felt(*felt)))
+        .collect();
+    let start = write_data(vm, &data)?;
+    Ok((start, (start + data.len())?))
+}
+
+fn cell_ref_to_relocatable(
+    vm: &VirtualMachine,
+    cell_ref: &CellRef,
+) -> Result<Relocatable, HintError> {
+    let base = match cell_ref.register {
+        Register::AP => vm.get_ap(),
+        Register::FP => vm.get_fp(),
+    };
+    Ok((base + i32::from(cell_ref.offset))?)
+}
+
+fn relocatable_from_res_operand(
+    vm: &VirtualMachine,
+    operand: &ResOperand,
+) -> Result<Relocatable, HintError> {
+    match operand {
+        ResOperand::Deref(cell_ref) => {
+            Ok(vm.get_relocatable(cell_ref_to_relocatable(vm, cell_ref)?)?)
+        }
+        ResOperand::BinOp(BinOpOperand {
+            op: Operation::Add,
+            a,
+            b: DerefOrImmediate::Immediate(offset),
+        }) => {
+            let base = vm.get_relocatable(cell_ref_to_relocatable(vm, a)?)?;
+            let offset = offset
+                .value
+                .to_usize()
+                .ok_or_else(|| HintError::CustomHint(format!("Invalid offset: {offset:?}")))?;
+            Ok((base + offset)?)
+        }
+        _ => Err(HintError::CustomHint(format!(
+            "Failed to extract a pointer from {operand:?}"
+        ))),
+    }
+}
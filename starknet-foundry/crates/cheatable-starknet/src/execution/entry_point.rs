@@ -0,0 +1,139 @@
+use blockifier::abi::abi_utils::selector_from_name;
+use blockifier::abi::constants::CONSTRUCTOR_ENTRY_POINT_NAME;
+use blockifier::execution::contract_class::ContractClass;
+use blockifier::execution::entry_point::{
+    CallEntryPoint, CallExecution, CallInfo, CallType, ConstructorContext,
+    EntryPointExecutionContext, EntryPointExecutionResult, ExecutionResources, Retdata,
+};
+use blockifier::execution::errors::{EntryPointExecutionError, PreExecutionError};
+use blockifier::state::errors::StateError;
+use blockifier::state::state_api::State;
+use starknet_api::core::ClassHash;
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::transaction::Calldata;
+
+use crate::execution::cairo1_execution::execute_entry_point_call_cairo1;
+use crate::execution::cheatable_syscall_handler::CheatcodeHandler;
+use crate::state::CheatcodeState;
+
+/// Executes the call like `CallEntryPoint::execute` does, but Cairo 1 contracts are run with
+/// `CheatableSyscallHandler`, so the cheatcodes they use are forwarded to the cheatcode handler
+/// and the cheats are applied to them. Mocked functions return the mocked data without being executed.
+pub fn execute_call_entry_point(
+    mut call: CallEntryPoint,
+    state: &mut dyn State,
+    cheatcode_state: &mut CheatcodeState,
+    resources: &mut ExecutionResources,
+    context: &mut EntryPointExecutionContext,
+    cheatcode_handler: &mut dyn CheatcodeHandler,
+) -> EntryPointExecutionResult<CallInfo> {
+    if call.call_type == CallType::Call && call.entry_point_type == EntryPointType::External {
+        if let Some(retdata) = cheatcode_state
+            .mocked_functions
+            .get(&(call.storage_address, call.entry_point_selector))
+        {
+            return Ok(CallInfo {
+                execution: CallExecution {
+                    retdata: Retdata(retdata.clone()),
+                    ..Default::default()
+                },
+                call,
+                ..Default::default()
+            });
+        }
+    }
+
+    let storage_class_hash = state.get_class_hash_at(call.storage_address)?;
+    if storage_class_hash == ClassHash::default() {
+        return Err(PreExecutionError::UninitializedStorageAddress(call.storage_address).into());
+    }
+    let class_hash = call.class_hash.unwrap_or(storage_class_hash);
+
+    match state.get_compiled_contract_class(&class_hash)? {
+        ContractClass::V1(contract_class) => {
+            // Checked like in `CallEntryPoint::execute`, which does it for Cairo 0 contracts
+            context.current_recursion_depth += 1;
+            if context.current_recursion_depth > context.block_context.max_recursion_depth {
+                context.current_recursion_depth -= 1;
+                return Err(EntryPointExecutionError::RecursionDepthExceeded);
+            }
+
+            call.class_hash = Some(class_hash);
+            let result = execute_entry_point_call_cairo1(
+                call,
+                &contract_class,
+                state,
+                cheatcode_state,
+                resources,
+                context,
+                cheatcode_handler,
+            );
+            context.current_recursion_depth -= 1;
+            result
+        }
+        // Cheatcodes are not available in Cairo 0 contracts and cheats are not applied to them
+        ContractClass::V0(_) => call.execute(state, resources, context),
+    }
+}
+
+/// Blockifier's `execute_deployment`, running the constructor with `execute_call_entry_point`
+#[allow(clippy::too_many_arguments)]
+pub fn execute_deployment(
+    state: &mut dyn State,
+    cheatcode_state: &mut CheatcodeState,
+    resources: &mut ExecutionResources,
+    context: &mut EntryPointExecutionContext,
+    ctor_context: ConstructorContext,
+    constructor_calldata: Calldata,
+    remaining_gas: u64,
+    cheatcode_handler: &mut dyn CheatcodeHandler,
+) -> EntryPointExecutionResult<CallInfo> {
+    if state.get_class_hash_at(ctor_context.storage_address)? != ClassHash::default() {
+        return Err(StateError::UnavailableContractAddress(ctor_context.storage_address).into());
+    }
+    state.set_class_hash_at(ctor_context.storage_address, ctor_context.class_hash)?;
+
+    let contract_class = state.get_compiled_contract_class(&ctor_context.class_hash)?;
+    let Some(constructor_selector) = contract_class.constructor_selector() else {
+        if !constructor_calldata.0.is_empty() {
+            return Err(EntryPointExecutionError::InvalidExecutionInput {
+                input_descriptor: "constructor_calldata".to_string(),
+                info: "Cannot pass calldata to a contract with no constructor.".to_string(),
+            });
+        }
+        return Ok(CallInfo {
+            call: CallEntryPoint {
+                class_hash: Some(ctor_context.class_hash),
+                code_address: ctor_context.code_address,
+                entry_point_type: EntryPointType::Constructor,
+                entry_point_selector: selector_from_name(CONSTRUCTOR_ENTRY_POINT_NAME),
+                calldata: Calldata::default(),
+                storage_address: ctor_context.storage_address,
+                caller_address: ctor_context.caller_address,
+                call_type: CallType::Call,
+                initial_gas: remaining_gas,
+            },
+            ..Default::default()
+        });
+    };
+
+    let constructor_call = CallEntryPoint {
+        class_hash: None,
+        code_address: ctor_context.code_address,
+        entry_point_type: EntryPointType::Constructor,
+        entry_point_selector: constructor_selector,
+        calldata: constructor_calldata,
+        storage_address: ctor_context.storage_address,
+        caller_address: ctor_context.caller_address,
+        call_type: CallType::Call,
+        initial_gas: remaining_gas,
+    };
+    execute_call_entry_point(
+        constructor_call,
+        state,
+        cheatcode_state,
+        resources,
+        context,
+        cheatcode_handler,
+    )
+}
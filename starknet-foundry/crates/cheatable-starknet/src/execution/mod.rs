@@ -0,0 +1,3 @@
+pub mod cairo1_execution;
+pub mod cheatable_syscall_handler;
+pub mod entry_point;
@@ -1,2 +1,3 @@
 pub mod constants;
+pub mod execution;
 pub mod state;
@@ -1,15 +1,19 @@
 use std::collections::HashMap;
 
 use blockifier::{
-    execution::contract_class::ContractClass,
+    execution::{
+        contract_class::ContractClass,
+        entry_point::{CallInfo, EntryPointExecutionResult},
+    },
     state::{
-        cached_state::ContractStorageKey,
+        cached_state::{CachedState, ContractStorageKey},
         errors::StateError,
-        state_api::{StateReader, StateResult},
+        state_api::{State, StateReader, StateResult},
     },
 };
 use starknet_api::{
-    core::{ClassHash, CompiledClassHash, ContractAddress, Nonce},
+    block::{BlockNumber, BlockTimestamp},
+    core::{ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, Nonce},
     hash::StarkFelt,
     state::StorageKey,
 };
@@ -80,3 +84,88 @@ impl StateReader for DictStateReader {
         Ok(compiled_class_hash)
     }
 }
+
+/// Reads the underlying state without changing it, so a `CachedState` on top of it can be discarded
+struct StateRef<'a>(&'a mut dyn State);
+
+impl StateReader for StateRef<'_> {
+    fn get_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        self.0.get_storage_at(contract_address, key)
+    }
+
+    fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.0.get_nonce_at(contract_address)
+    }
+
+    fn get_compiled_contract_class(
+        &mut self,
+        class_hash: &ClassHash,
+    ) -> StateResult<ContractClass> {
+        self.0.get_compiled_contract_class(class_hash)
+    }
+
+    fn get_class_hash_at(&mut self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.0.get_class_hash_at(contract_address)
+    }
+
+    fn get_compiled_class_hash(&mut self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.0.get_compiled_class_hash(class_hash)
+    }
+}
+
+/// Executes the call on its own state, changes are written to the `state` only if the call succeeds,
+/// so failed calls are reverted like on Starknet
+pub fn execute_reverting_on_failure(
+    state: &mut dyn State,
+    execute: impl FnOnce(&mut dyn State) -> EntryPointExecutionResult<CallInfo>,
+) -> EntryPointExecutionResult<CallInfo> {
+    let (result, state_diff) = {
+        let mut call_state = CachedState::new(StateRef(state));
+        let result = execute(&mut call_state);
+        (result, call_state.to_state_diff())
+    };
+    if !matches!(&result, Ok(call_info) if !call_info.execution.failed) {
+        return result;
+    }
+
+    // Calls and deployments do not change nonces or declare classes
+    for (contract_address, class_hash) in state_diff.address_to_class_hash {
+        state.set_class_hash_at(contract_address, class_hash)?;
+    }
+    for (contract_address, storage_updates) in state_diff.storage_updates {
+        for (key, value) in storage_updates {
+            state.set_storage_at(contract_address, key, value);
+        }
+    }
+    for (class_hash, compiled_class_hash) in state_diff.class_hash_to_compiled_class_hash {
+        state.set_compiled_class_hash(class_hash, compiled_class_hash)?;
+    }
+    result
+}
+
+/// Cheats set by the test, applied to the contracts at any call depth
+#[derive(Debug, Default)]
+pub struct CheatcodeState {
+    /// Caller addresses returned by `get_caller_address` in the pranked contracts
+    pub pranked_contracts: HashMap<ContractAddress, ContractAddress>,
+    /// Block numbers returned by `get_block_number` in the rolled contracts
+    pub rolled_contracts: HashMap<ContractAddress, BlockNumber>,
+    /// Block timestamps returned by `get_block_timestamp` in the warped contracts
+    pub warped_contracts: HashMap<ContractAddress, BlockTimestamp>,
+    /// Return data of the mocked functions, which are not executed when called
+    pub mocked_functions: HashMap<(ContractAddress, EntryPointSelector), Vec<StarkFelt>>,
+}
+
+impl CheatcodeState {
+    /// Whether the execution info seen by the contract differs from the one provided by blockifier
+    #[must_use]
+    pub fn changes_execution_info(&self, contract_address: &ContractAddress) -> bool {
+        self.pranked_contracts.contains_key(contract_address)
+            || self.rolled_contracts.contains_key(contract_address)
+            || self.warped_contracts.contains_key(contract_address)
+    }
+}
@@ -0,0 +1,199 @@
+use anyhow::{anyhow, Context, Result};
+use cairo_felt::Felt252;
+use num_traits::ToPrimitive;
+use starknet::core::crypto::compute_hash_on_elements;
+use starknet::core::types::FieldElement;
+use starknet::core::utils::cairo_short_string_to_felt;
+
+/// Transaction sent from an account deployed in the test, serialized by `cheatcodes::AccountTransaction`
+#[derive(Debug, PartialEq)]
+pub(crate) enum AccountTransactionInputs {
+    Invoke {
+        sender_address: Felt252,
+        /// Calldata of `__execute__`, the serialized calls
+        calldata: Vec<Felt252>,
+    },
+    Declare {
+        sender_address: Felt252,
+        contract: Felt252,
+    },
+    DeployAccount {
+        class_hash: Felt252,
+        contract_address_salt: Felt252,
+        constructor_calldata: Vec<Felt252>,
+    },
+}
+
+impl AccountTransactionInputs {
+    pub(crate) fn parse(inputs: &mut impl Iterator<Item = Felt252>) -> Result<Self> {
+        let variant = next_usize(inputs).context("Invalid account transaction variant")?;
+        match variant {
+            0 => {
+                let sender_address = inputs.next().context("Missing sender address")?;
+                let calldata = read_calls(inputs)?;
+                Ok(Self::Invoke {
+                    sender_address,
+                    calldata,
+                })
+            }
+            1 => Ok(Self::Declare {
+                sender_address: inputs.next().context("Missing sender address")?,
+                contract: inputs.next().context("Missing contract name")?,
+            }),
+            2 => Ok(Self::DeployAccount {
+                class_hash: inputs.next().context("Missing class hash")?,
+                contract_address_salt: inputs.next().context("Missing contract address salt")?,
+                constructor_calldata: read_array(inputs)?,
+            }),
+            _ => Err(anyhow!("Unknown account transaction variant: {variant}")),
+        }
+    }
+}
+
+fn next_usize(inputs: &mut impl Iterator<Item = Felt252>) -> Result<usize> {
+    inputs
+        .next()
+        .and_then(|value| value.to_usize())
+        .context("Failed to read usize")
+}
+
+/// Reads `Array::<felt252>` serialized with Cairo `Serde`: length followed by the elements
+pub(crate) fn read_array(inputs: &mut impl Iterator<Item = Felt252>) -> Result<Vec<Felt252>> {
+    let length = next_usize(inputs).context("Invalid array length")?;
+    let array: Vec<Felt252> = inputs.take(length).collect();
+    if array.len() != length {
+        return Err(anyhow!("Missing array elements"));
+    }
+    Ok(array)
+}
+
+/// Reads `Array::<Call>` serialized with Cairo `Serde`, returning all of its felts
+fn read_calls(inputs: &mut impl Iterator<Item = Felt252>) -> Result<Vec<Felt252>> {
+    let calls_count = next_usize(inputs).context("Invalid number of calls")?;
+    let mut calldata = vec![Felt252::from(calls_count)];
+    for _ in 0..calls_count {
+        calldata.push(inputs.next().context("Missing call address")?);
+        calldata.push(inputs.next().context("Missing call selector")?);
+        let call_calldata = read_array(inputs).context("Invalid call calldata")?;
+        calldata.push(Felt252::from(call_calldata.len()));
+        calldata.extend(call_calldata);
+    }
+    Ok(calldata)
+}
+
+/// Fields signed with every transaction
+pub(crate) struct TransactionHashFields<'a> {
+    /// `invoke`, `declare` or `deploy_account`
+    pub prefix: &'a str,
+    pub version: u8,
+    /// Sender of the transaction or the address of the deployed account
+    pub contract_address: &'a Felt252,
+    /// Calldata, class hash or the class hash, salt and constructor calldata of the deployed account
+    pub data: &'a [Felt252],
+    pub max_fee: u128,
+    pub chain_id: &'a str,
+    pub nonce: &'a Felt252,
+    /// Compiled class hash of the declared class
+    pub additional_data: &'a [Felt252],
+}
+
+/// Calculates the transaction hash the way the Starknet OS does, see
+/// <https://docs.starknet.io/documentation/architecture_and_concepts/Network_Architecture/transactions/>
+pub(crate) fn calculate_transaction_hash(fields: &TransactionHashFields) -> Result<Felt252> {
+    let data = fields
+        .data
+        .iter()
+        .map(field_element_from_felt)
+        .collect::<Result<Vec<_>>>()?;
+    let mut elements = vec![
+        cairo_short_string_to_felt(fields.prefix)?,
+        FieldElement::from(u64::from(fields.version)),
+        field_element_from_felt(fields.contract_address)?,
+        FieldElement::ZERO,
+        compute_hash_on_elements(&data),
+        field_element_from_felt(&Felt252::from(fields.max_fee))?,
+        cairo_short_string_to_felt(fields.chain_id)?,
+        field_element_from_felt(fields.nonce)?,
+    ];
+    for value in fields.additional_data {
+        elements.push(field_element_from_felt(value)?);
+    }
+    Ok(felt_from_field_element(&compute_hash_on_elements(
+        &elements,
+    )))
+}
+
+pub(crate) fn field_element_from_felt(felt: &Felt252) -> Result<FieldElement> {
+    FieldElement::from_bytes_be(&felt.to_be_bytes())
+        .map_err(|_| anyhow!("Failed to convert {felt} to a field element"))
+}
+
+pub(crate) fn felt_from_field_element(field_element: &FieldElement) -> Felt252 {
+    Felt252::from_bytes_be(&field_element.to_bytes_be())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn felts(values: &[u64]) -> Vec<Felt252> {
+        values.iter().map(|value| Felt252::from(*value)).collect()
+    }
+
+    #[test]
+    fn parsing_account_transactions() {
+        assert_eq!(
+            AccountTransactionInputs::parse(
+                &mut felts(&[0, 10, 2, 20, 30, 1, 5, 21, 31, 0]).into_iter()
+            )
+            .unwrap(),
+            AccountTransactionInputs::Invoke {
+                sender_address: Felt252::from(10),
+                calldata: felts(&[2, 20, 30, 1, 5, 21, 31, 0]),
+            }
+        );
+        assert_eq!(
+            AccountTransactionInputs::parse(&mut felts(&[1, 10, 123]).into_iter()).unwrap(),
+            AccountTransactionInputs::Declare {
+                sender_address: Felt252::from(10),
+                contract: Felt252::from(123),
+            }
+        );
+        assert_eq!(
+            AccountTransactionInputs::parse(&mut felts(&[2, 123, 7, 1, 42]).into_iter()).unwrap(),
+            AccountTransactionInputs::DeployAccount {
+                class_hash: Felt252::from(123),
+                contract_address_salt: Felt252::from(7),
+                constructor_calldata: felts(&[42]),
+            }
+        );
+        assert!(
+            AccountTransactionInputs::parse(&mut felts(&[0, 10, 1, 20, 30, 2, 5]).into_iter())
+                .is_err()
+        );
+        assert!(AccountTransactionInputs::parse(&mut felts(&[3]).into_iter()).is_err());
+    }
+
+    #[test]
+    fn calculating_declare_transaction_hash() {
+        let hash = calculate_transaction_hash(&TransactionHashFields {
+            prefix: "declare",
+            version: 2,
+            contract_address: &Felt252::from(0x101),
+            data: &felts(&[0x123]),
+            max_fee: 10,
+            chain_id: "SN_GOERLI",
+            nonce: &Felt252::from(3),
+            additional_data: &felts(&[0x456]),
+        })
+        .unwrap();
+
+        // Calculated with an implementation of the Pedersen hash independent of `starknet-rs`
+        let expected_hash = Felt252::parse_bytes(
+            b"2465795175c59c763e8cf355e4fb0506fe64d69afe6fcc5b47a3d0071a6f3d3",
+            16,
+        )
+        .unwrap();
+        assert_eq!(hash, expected_hash);
+    }
+}
@@ -0,0 +1,67 @@
+use anyhow::{anyhow, Result};
+use cairo_lang_filesystem::cfg::Cfg;
+
+/// Key of the cfg items enabling Scarb features, matched by `#[cfg(feature: 'name')]`
+const FEATURE_KEY: &str = "feature";
+
+/// Parses cfg items enabled in the compiled tests in addition to `test`.
+/// Items are given as `name` or `key=value`, every feature is enabled as `feature=<name>`.
+pub fn cfg_items_from_args(cfg: &[String], features: &[String]) -> Result<Vec<Cfg>> {
+    let mut cfg_items = cfg
+        .iter()
+        .map(|item| parse_cfg_item(item))
+        .collect::<Result<Vec<_>>>()?;
+    for feature in features {
+        if feature.is_empty() {
+            return Err(anyhow!("Feature name can not be empty"));
+        }
+        cfg_items.push(Cfg::kv(FEATURE_KEY, feature.as_str()));
+    }
+    Ok(cfg_items)
+}
+
+fn parse_cfg_item(item: &str) -> Result<Cfg> {
+    let cfg = match item.split_once('=') {
+        Some((key, value)) => {
+            if value.is_empty() {
+                return Err(anyhow!("Invalid cfg item = {item}, value can not be empty"));
+            }
+            Cfg::kv(key.trim(), value.trim())
+        }
+        None => Cfg::name(item.trim()),
+    };
+    if cfg.key.is_empty() {
+        return Err(anyhow!("Invalid cfg item = {item}, name can not be empty"));
+    }
+    Ok(cfg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_cfg_items_and_features() {
+        let cfg_items = cfg_items_from_args(
+            &["fuzzing".to_string(), "network=testnet".to_string()],
+            &["mock_oracle".to_string()],
+        )
+        .unwrap();
+
+        assert_eq!(
+            cfg_items,
+            vec![
+                Cfg::name("fuzzing"),
+                Cfg::kv("network", "testnet"),
+                Cfg::kv("feature", "mock_oracle"),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_cfg_items_fail() {
+        assert!(cfg_items_from_args(&["=value".to_string()], &[]).is_err());
+        assert!(cfg_items_from_args(&["key=".to_string()], &[]).is_err());
+        assert!(cfg_items_from_args(&[], &[String::new()]).is_err());
+    }
+}
@@ -3,21 +3,34 @@ use std::collections::HashMap;
 use std::io;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
+use crate::account_transactions::{
+    calculate_transaction_hash, read_array, AccountTransactionInputs, TransactionHashFields,
+};
+use crate::execution_trace::ContractCall;
+use crate::fees::{self, Balance};
+use crate::formatting;
 use crate::scarb::StarknetContractArtifacts;
+use crate::signing::{derive_stark_keys, generate_stark_keys, sign_message_hash};
 use anyhow::{anyhow, Context, Result};
 use blockifier::abi::abi_utils::selector_from_name;
 use blockifier::execution::contract_class::{
     ContractClass as BlockifierContractClass, ContractClassV1,
 };
 use blockifier::execution::entry_point::{
-    CallEntryPoint, CallInfo, CallType, EntryPointExecutionContext, ExecutionResources,
+    CallEntryPoint, CallInfo, CallType, ConstructorContext, EntryPointExecutionContext,
+    ExecutionResources,
 };
 use blockifier::execution::errors::EntryPointExecutionError;
-use blockifier::state::cached_state::CachedState;
+use blockifier::state::cached_state::{CachedState, TransactionalState};
 use blockifier::state::errors::StateError;
-use blockifier::state::state_api::StateReader;
+use blockifier::state::state_api::{State, StateReader};
 use blockifier::transaction::account_transaction::AccountTransaction;
+use blockifier::transaction::errors::TransactionExecutionError;
+use blockifier::transaction::objects::ResourcesMapping;
+use blockifier::transaction::transaction_types::TransactionType;
+use blockifier::transaction::transaction_utils::calculate_tx_resources;
 use blockifier::transaction::transactions::{DeclareTransaction, ExecutableTransaction};
 use cairo_felt::Felt252;
 use cairo_vm::hint_processor::hint_processor_definition::HintProcessorLogic;
@@ -29,23 +42,30 @@ use cairo_vm::vm::errors::memory_errors::MemoryError;
 use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
 use cairo_vm::vm::vm_core::VirtualMachine;
 use cheatable_starknet::constants::{
-    build_block_context, build_declare_transaction, build_invoke_transaction,
-    build_transaction_context, TEST_ACCOUNT_CONTRACT_ADDRESS,
+    build_block_context, build_declare_transaction, build_transaction_context, MAX_FEE,
+    TEST_ACCOUNT_CONTRACT_ADDRESS,
 };
-use cheatable_starknet::state::DictStateReader;
+use cheatable_starknet::execution::cheatable_syscall_handler::CheatcodeHandler;
+use cheatable_starknet::execution::entry_point::{execute_call_entry_point, execute_deployment};
+use cheatable_starknet::state::{CheatcodeState, DictStateReader};
 use num_traits::{Num, ToPrimitive};
-use regex::Regex;
 use serde::Deserialize;
-use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, PatriciaKey};
+use starknet_api::block::{BlockNumber, BlockTimestamp};
+use starknet_api::core::{
+    calculate_contract_address, ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector,
+    Nonce, PatriciaKey,
+};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::transaction::{
-    Calldata, ContractAddressSalt, InvokeTransaction, InvokeTransactionV1,
+    Calldata, ContractAddressSalt, DeclareTransactionV2, DeployAccountTransaction, Fee,
+    InvokeTransaction, InvokeTransactionV1, TransactionHash, TransactionSignature,
+    TransactionVersion,
 };
-use starknet_api::{patricia_key, stark_felt, StarknetApiError};
+use starknet_api::{patricia_key, StarknetApiError};
 use thiserror::Error;
 
-use crate::vm_memory::write_cheatcode_panic;
+use crate::vm_memory::write_cheatcode_error;
 use cairo_lang_casm::hints::{Hint, StarknetHint};
 use cairo_lang_casm::operand::{CellRef, ResOperand};
 use cairo_lang_runner::casm_run::{extract_relocatable, vm_get_range, MemBuffer};
@@ -64,14 +84,135 @@ pub struct CairoHintProcessor<'a> {
     pub original_cairo_hint_processor: OriginalCairoHintProcessor<'a>,
     pub blockifier_state: CachedState<DictStateReader>,
     pub contracts: &'a HashMap<String, StarknetContractArtifacts>,
+    pub contracts_execution: ContractsExecution,
+    /// Wall-clock time after which the test is stopped
+    pub deadline: Option<Instant>,
+    pub timed_out: bool,
+}
+
+/// Checking the clock on every step would slow down the execution noticeably
+const DEADLINE_CHECK_INTERVAL: usize = 1000;
+
+/// Contracts declared and calls executed by blockifier during a test
+#[derive(Debug, Default)]
+pub struct ContractsExecution {
+    declared_contracts: HashMap<ClassHash, String>,
+    /// Calls with the number of steps the test executed before making them
+    call_infos: Vec<(usize, CallInfo)>,
+    /// Cheats applied to the contracts called from the test
+    cheatcode_state: CheatcodeState,
+    steps: usize,
+    charge_fees: bool,
+    fee_charged: u128,
+}
+
+impl ContractsExecution {
+    pub(crate) fn new(charge_fees: bool) -> Self {
+        Self {
+            charge_fees,
+            ..Default::default()
+        }
+    }
+
+    /// Total fee charged for declarations, deployments and calls, if fees are charged
+    pub(crate) fn fee_charged(&self) -> Option<u128> {
+        self.charge_fees.then_some(self.fee_charged)
+    }
+
+    fn record_call(&mut self, call_info: CallInfo) {
+        self.call_infos.push((self.steps, call_info));
+    }
+
+    fn charge_fee(
+        &mut self,
+        blockifier_state: &mut impl State,
+        actual_resources: &ResourcesMapping,
+    ) -> Result<()> {
+        if self.charge_fees {
+            self.fee_charged += fees::charge_fee(blockifier_state, actual_resources)?;
+        }
+        Ok(())
+    }
+
+    /// Charges the fee of an invoke transaction making the call, before its state changes are committed
+    fn charge_invoke_fee(
+        &mut self,
+        transactional_state: &mut TransactionalState<'_, CachedState<DictStateReader>>,
+        resources: ExecutionResources,
+        call_info: &CallInfo,
+    ) -> Result<()> {
+        if !self.charge_fees {
+            return Ok(());
+        }
+        let actual_resources = calculate_tx_resources(
+            resources,
+            &[call_info],
+            TransactionType::InvokeFunction,
+            transactional_state,
+            None,
+        )
+        .map_err(|err| anyhow!("Failed to calculate transaction resources: {err}"))?;
+        self.charge_fee(transactional_state, &actual_resources)
+    }
+}
+
+impl CairoHintProcessor<'_> {
+    /// Contract calls made by the test, with the number of steps executed before each of them
+    pub(crate) fn executed_contract_calls(&mut self) -> Result<Vec<(usize, ContractCall)>> {
+        let mut contract_calls = vec![];
+        for (step, call_info) in &self.contracts_execution.call_infos {
+            let contract_call = contract_call_from_call_info(
+                call_info,
+                &self.contracts_execution.declared_contracts,
+                &mut self.blockifier_state,
+            )?;
+            contract_calls.push((*step, contract_call));
+        }
+        Ok(contract_calls)
+    }
+}
+
+fn contract_call_from_call_info(
+    call_info: &CallInfo,
+    declared_contracts: &HashMap<ClassHash, String>,
+    blockifier_state: &mut CachedState<DictStateReader>,
+) -> Result<ContractCall> {
+    let class_hash = match call_info.call.class_hash {
+        Some(class_hash) => class_hash,
+        None => blockifier_state
+            .get_class_hash_at(call_info.call.storage_address)
+            .context("Failed to get class hash of the called contract")?,
+    };
+    let inner_calls = call_info
+        .inner_calls
+        .iter()
+        .map(|inner_call| {
+            contract_call_from_call_info(inner_call, declared_contracts, blockifier_state)
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(ContractCall {
+        contract_name: declared_contracts.get(&class_hash).cloned(),
+        entry_point_selector: Felt252::from_bytes_be(call_info.call.entry_point_selector.0.bytes()),
+        steps: call_info.vm_resources.n_steps,
+        inner_calls,
+    })
 }
 
 impl ResourceTracker for CairoHintProcessor<'_> {
     fn consumed(&self) -> bool {
-        self.original_cairo_hint_processor.run_resources.consumed()
+        self.timed_out || self.original_cairo_hint_processor.run_resources.consumed()
     }
 
     fn consume_step(&mut self) {
+        self.contracts_execution.steps += 1;
+        if let Some(deadline) = self.deadline {
+            if self.contracts_execution.steps % DEADLINE_CHECK_INTERVAL == 0
+                && Instant::now() >= deadline
+            {
+                self.timed_out = true;
+            }
+        }
         self.original_cairo_hint_processor
             .run_resources
             .consume_step();
@@ -117,10 +258,17 @@ impl HintProcessorLogic for CairoHintProcessor<'_> {
                 output_start,
                 output_end,
                 self.contracts,
+                &mut self.contracts_execution,
             );
         }
         if let Some(Hint::Starknet(StarknetHint::SystemCall { system })) = maybe_extended_hint {
-            return execute_syscall(system, vm, &mut self.blockifier_state);
+            return execute_syscall(
+                system,
+                vm,
+                &mut self.blockifier_state,
+                &mut self.contracts_execution,
+            )
+            .map_err(Into::into);
         }
         self.original_cairo_hint_processor
             .execute_hint(vm, exec_scopes, hint_data, constants)
@@ -167,37 +315,43 @@ fn execute_syscall(
     system: &ResOperand,
     vm: &mut VirtualMachine,
     blockifier_state: &mut CachedState<DictStateReader>,
-) -> Result<(), HintError> {
+    contracts_execution: &mut ContractsExecution,
+) -> Result<(), EnhancedHintError> {
     let (cell, offset) = extract_buffer(system);
     let system_ptr = get_ptr(vm, cell, &offset)?;
 
     let mut buffer = MemBuffer::new(vm, system_ptr);
 
-    let selector = buffer.next_felt252().unwrap().to_bytes_be();
-    let gas_counter = buffer.next_usize().unwrap();
-    let contract_address = buffer.next_felt252().unwrap().into_owned();
-    let entry_point_selector = buffer.next_felt252().unwrap().into_owned();
+    let selector = buffer.next_felt252()?.to_bytes_be();
+    let gas_counter = buffer.next_usize()?;
+    let contract_address = buffer.next_felt252()?.into_owned();
+    let entry_point_selector = buffer.next_felt252()?.into_owned();
 
-    let calldata = buffer.next_arr().unwrap();
+    let calldata = buffer.next_arr()?;
 
-    assert_eq!(std::str::from_utf8(&selector).unwrap(), "CallContract");
+    let selector =
+        std::str::from_utf8(&selector).context("Failed to parse the syscall selector")?;
+    if selector != "CallContract" {
+        return Err(anyhow!("Unsupported syscall selector: {selector}").into());
+    }
     let call_result = call_contract(
         &contract_address,
         &entry_point_selector,
         &calldata,
         blockifier_state,
+        contracts_execution,
     )
-    .unwrap_or_else(|err| panic!("Transaction execution error: {err}"));
+    .map_err(|err| anyhow!("Transaction execution error: {err}"))?;
 
     let (result, exit_code) = match call_result {
         CallContractOutput::Success { ret_data } => (ret_data, 0),
         CallContractOutput::Panic { panic_data } => (panic_data, 1),
     };
 
-    buffer.write(gas_counter).unwrap();
-    buffer.write(Felt252::from(exit_code)).unwrap();
+    buffer.write(gas_counter)?;
+    buffer.write(Felt252::from(exit_code))?;
 
-    buffer.write_arr(result.iter()).unwrap();
+    buffer.write_arr(result.iter())?;
 
     Ok(())
 }
@@ -213,6 +367,7 @@ fn call_contract(
     entry_point_selector: &Felt252,
     calldata: &[Felt252],
     blockifier_state: &mut CachedState<DictStateReader>,
+    contracts_execution: &mut ContractsExecution,
 ) -> Result<CallContractOutput> {
     let contract_address = ContractAddress(PatriciaKey::try_from(StarkFelt::new(
         contract_address.to_be_bytes(),
@@ -248,30 +403,53 @@ fn call_contract(
         block_context.invoke_tx_max_n_steps,
     );
 
-    let exec_result = entry_point.execute(blockifier_state, &mut resources, &mut context);
-    if let Ok(call_info) = exec_result {
-        let raw_return_data = &call_info.execution.retdata.0;
-
-        let return_data = raw_return_data
-            .iter()
-            .map(|data| Felt252::from_bytes_be(data.bytes()))
-            .collect();
-
-        Ok(CallContractOutput::Success {
-            ret_data: return_data,
-        })
-    } else if let Err(EntryPointExecutionError::ExecutionFailed { error_data }) = exec_result {
-        let err_data = error_data
-            .iter()
-            .map(|data| Felt252::from_bytes_be(data.bytes()))
-            .collect();
+    let mut transactional_state = CachedState::create_transactional(blockifier_state);
+    let exec_result = execute_call_entry_point(
+        entry_point,
+        &mut transactional_state,
+        &mut contracts_execution.cheatcode_state,
+        &mut resources,
+        &mut context,
+        &mut ContractCheatcodes,
+    );
+    // Changes made by a failed call are reverted, like on Starknet
+    let output = match exec_result {
+        Ok(call_info) if !call_info.execution.failed => {
+            contracts_execution.charge_invoke_fee(
+                &mut transactional_state,
+                resources,
+                &call_info,
+            )?;
+            transactional_state.commit();
+            let ret_data = felts_from_stark_felts(&call_info.execution.retdata.0);
+            contracts_execution.record_call(call_info);
+            CallContractOutput::Success { ret_data }
+        }
+        Ok(call_info) => {
+            transactional_state.abort();
+            CallContractOutput::Panic {
+                panic_data: felts_from_stark_felts(&call_info.execution.retdata.0),
+            }
+        }
+        Err(EntryPointExecutionError::ExecutionFailed { error_data }) => {
+            transactional_state.abort();
+            CallContractOutput::Panic {
+                panic_data: felts_from_stark_felts(&error_data),
+            }
+        }
+        Err(err) => {
+            transactional_state.abort();
+            return Err(anyhow!("Unparseable result: {err:?}"));
+        }
+    };
+    Ok(output)
+}
 
-        Ok(CallContractOutput::Panic {
-            panic_data: err_data,
-        })
-    } else {
-        panic!("Unparseable result: {exec_result:?}");
-    }
+fn felts_from_stark_felts(felts: &[StarkFelt]) -> Vec<Felt252> {
+    felts
+        .iter()
+        .map(|felt| Felt252::from_bytes_be(felt.bytes()))
+        .collect()
 }
 
 // All errors that can be thrown from the hint executor have to be added here,
@@ -316,6 +494,7 @@ fn execute_cheatcode_hint(
     output_start: &CellRef,
     output_end: &CellRef,
     contracts: &HashMap<String, StarknetContractArtifacts>,
+    contracts_execution: &mut ContractsExecution,
 ) -> Result<(), HintError> {
     // Parse the selector.
     let selector = &selector.value.to_bytes_be().1;
@@ -339,11 +518,17 @@ fn execute_cheatcode_hint(
         output_start,
         output_end,
         contracts,
+        contracts_execution,
     )
     .map_err(Into::into)
 }
 
-#[allow(unused, clippy::too_many_lines, clippy::trivially_copy_pass_by_ref)]
+#[allow(
+    unused,
+    clippy::too_many_lines,
+    clippy::trivially_copy_pass_by_ref,
+    clippy::too_many_arguments
+)]
 fn match_cheatcode_by_selector(
     vm: &mut VirtualMachine,
     blockifier_state: &mut CachedState<DictStateReader>,
@@ -352,26 +537,49 @@ fn match_cheatcode_by_selector(
     output_start: &CellRef,
     output_end: &CellRef,
     contracts: &HashMap<String, StarknetContractArtifacts>,
+    contracts_execution: &mut ContractsExecution,
 ) -> Result<(), EnhancedHintError> {
     let mut buffer = MemBuffer::new_segment(vm);
     let result_start = buffer.ptr;
 
     match selector {
-        "prepare" => todo!(),
-        "start_roll" => todo!(),
-        "stop_roll" => todo!(),
-        "start_warp" => todo!(),
-        "stop_warp" => todo!(),
-        "start_prank" => todo!(),
-        "stop_prank" => todo!(),
-        "mock_call" => todo!(),
-        "declare" => declare(&mut buffer, blockifier_state, &inputs, contracts),
-        "deploy" => deploy(&mut buffer, blockifier_state, &inputs),
-        "print" => {
-            print(inputs);
+        "declare" => declare(
+            &mut buffer,
+            blockifier_state,
+            &inputs,
+            contracts,
+            contracts_execution,
+        ),
+        "deploy" => deploy(&mut buffer, blockifier_state, &inputs, contracts_execution),
+        "transaction_hash" => {
+            let transaction = AccountTransactionInputs::parse(&mut inputs.iter().cloned())?;
+            let built_transaction = build_account_transaction(
+                blockifier_state,
+                transaction,
+                vec![],
+                contracts,
+                contracts_execution.charge_fees,
+            )?;
+            buffer.write(built_transaction.transaction_hash)?;
+            Ok(())
+        }
+        "execute_transaction" => execute_transaction(
+            &mut buffer,
+            blockifier_state,
+            &inputs,
+            contracts,
+            contracts_execution,
+        ),
+        _ => {
+            let outputs = execute_state_cheatcode(
+                blockifier_state,
+                &mut contracts_execution.cheatcode_state,
+                selector,
+                &inputs,
+            )?;
+            buffer.write_data(outputs.iter())?;
             Ok(())
         }
-        _ => Err(anyhow!("Unknown cheatcode selector: {selector}")).map_err(Into::into),
     }?;
 
     let result_end = buffer.ptr;
@@ -381,9 +589,176 @@ fn match_cheatcode_by_selector(
     Ok(())
 }
 
-fn print(inputs: Vec<Felt252>) {
+/// Cheatcodes which need only the state and the cheats, so they are available both in tests
+/// and in contracts called from them
+#[allow(clippy::too_many_lines)]
+fn execute_state_cheatcode(
+    state: &mut dyn State,
+    cheatcode_state: &mut CheatcodeState,
+    selector: &str,
+    inputs: &[Felt252],
+) -> Result<Vec<Felt252>, EnhancedHintError> {
+    match selector {
+        "start_prank" => {
+            let (contract_address, caller_address) = cheat_inputs(selector, inputs)?;
+            cheatcode_state.pranked_contracts.insert(
+                contract_address,
+                contract_address_from_felt(&caller_address)?,
+            );
+            Ok(vec![])
+        }
+        "stop_prank" => {
+            cheatcode_state
+                .pranked_contracts
+                .remove(&address_input(inputs)?);
+            Ok(vec![])
+        }
+        "start_roll" => {
+            let (contract_address, block_number) = cheat_inputs(selector, inputs)?;
+            let block_number = block_number
+                .to_u64()
+                .context("Block number does not fit in u64")?;
+            cheatcode_state
+                .rolled_contracts
+                .insert(contract_address, BlockNumber(block_number));
+            Ok(vec![])
+        }
+        "stop_roll" => {
+            cheatcode_state
+                .rolled_contracts
+                .remove(&address_input(inputs)?);
+            Ok(vec![])
+        }
+        "start_warp" => {
+            let (contract_address, block_timestamp) = cheat_inputs(selector, inputs)?;
+            let block_timestamp = block_timestamp
+                .to_u64()
+                .context("Block timestamp does not fit in u64")?;
+            cheatcode_state
+                .warped_contracts
+                .insert(contract_address, BlockTimestamp(block_timestamp));
+            Ok(vec![])
+        }
+        "stop_warp" => {
+            cheatcode_state
+                .warped_contracts
+                .remove(&address_input(inputs)?);
+            Ok(vec![])
+        }
+        "mock_call" => {
+            let mut inputs = inputs.iter().cloned();
+            let function = mocked_function(&mut inputs)?;
+            let ret_data = read_array(&mut inputs).context("Invalid mocked return data")?;
+            cheatcode_state
+                .mocked_functions
+                .insert(function, stark_felts(&ret_data)?);
+            Ok(vec![])
+        }
+        "stop_mock_call" => {
+            let function = mocked_function(&mut inputs.iter().cloned())?;
+            cheatcode_state.mocked_functions.remove(&function);
+            Ok(vec![])
+        }
+        "precalculate_address" => {
+            let mut inputs = inputs.iter().cloned();
+            let class_hash = inputs.next().context("Missing class hash")?;
+            let constructor_calldata = read_array(&mut inputs)?;
+            let contract_address = calculate_contract_address(
+                ContractAddressSalt::default(),
+                ClassHash(StarkFelt::new(class_hash.to_be_bytes())?),
+                &Calldata(Arc::new(stark_felts(&constructor_calldata)?)),
+                ContractAddress(patricia_key!(TEST_ACCOUNT_CONTRACT_ADDRESS)),
+            )?;
+            Ok(vec![Felt252::from_bytes_be(
+                contract_address.0.key().bytes(),
+            )])
+        }
+        "replace_bytecode" => replace_bytecode(state, inputs),
+        "get_class_hash" => {
+            let class_hash = state.get_class_hash_at(address_input(inputs)?)?;
+            Ok(vec![Felt252::from_bytes_be(class_hash.0.bytes())])
+        }
+        "is_deployed" => {
+            let class_hash = state.get_class_hash_at(address_input(inputs)?)?;
+            Ok(vec![Felt252::from(u8::from(
+                class_hash != ClassHash::default(),
+            ))])
+        }
+        "get_nonce" => {
+            let nonce = state.get_nonce_at(address_input(inputs)?)?;
+            Ok(vec![Felt252::from_bytes_be(nonce.0.bytes())])
+        }
+        "deal" => {
+            deal(state, inputs)?;
+            Ok(vec![])
+        }
+        "deploy_account_address" => {
+            let mut inputs = inputs.iter().cloned();
+            let class_hash = inputs.next().context("Missing class hash")?;
+            let contract_address_salt = inputs.next().context("Missing contract address salt")?;
+            let constructor_calldata = read_array(&mut inputs)?;
+            let (contract_address, _) =
+                deploy_account_address(&class_hash, &contract_address_salt, &constructor_calldata)?;
+            Ok(vec![Felt252::from_bytes_be(
+                contract_address.0.key().bytes(),
+            )])
+        }
+        "generate_stark_keys" => Ok(generate_stark_keys().to_felts().to_vec()),
+        "derive_stark_keys" => {
+            let [seed] = inputs else {
+                return Err(anyhow!("Expected a single seed, got: {inputs:?}").into());
+            };
+            Ok(derive_stark_keys(seed)?.to_felts().to_vec())
+        }
+        "sign_message_hash" => {
+            let [private_key, message_hash] = inputs else {
+                return Err(anyhow!("Invalid sign_message_hash inputs: {inputs:?}").into());
+            };
+            let (r, s) = sign_message_hash(private_key, message_hash)?;
+            Ok(vec![r, s])
+        }
+        "print" => {
+            print(inputs);
+            Ok(vec![])
+        }
+        "println" => {
+            println!("{}", formatting::format(inputs)?);
+            Ok(vec![])
+        }
+        _ => Err(anyhow!("Unknown cheatcode selector: {selector}")).map_err(Into::into),
+    }
+}
+
+/// Cheatcodes used by contracts called from the test, which are executed with `CheatableSyscallHandler`
+struct ContractCheatcodes;
+
+impl CheatcodeHandler for ContractCheatcodes {
+    fn execute_cheatcode(
+        &mut self,
+        state: &mut dyn State,
+        cheatcode_state: &mut CheatcodeState,
+        selector: &str,
+        inputs: &[StarkFelt],
+    ) -> Result<Vec<StarkFelt>> {
+        if matches!(
+            selector,
+            "declare" | "deploy" | "transaction_hash" | "execute_transaction"
+        ) {
+            return Err(anyhow!("Cheatcode {selector} can only be used in tests"));
+        }
+        let inputs: Vec<Felt252> = inputs
+            .iter()
+            .map(|felt| Felt252::from_bytes_be(felt.bytes()))
+            .collect();
+        let outputs = execute_state_cheatcode(state, cheatcode_state, selector, &inputs)
+            .map_err(|err| anyhow!("{err}"))?;
+        Ok(stark_felts(&outputs)?)
+    }
+}
+
+fn print(inputs: &[Felt252]) {
     for value in inputs {
-        if let Some(short_string) = as_cairo_short_string(&value) {
+        if let Some(short_string) = as_cairo_short_string(value) {
             println!("original value: [{value}], converted to a string: [{short_string}]",);
         } else {
             println!("original value: [{value}]");
@@ -396,27 +771,17 @@ fn declare(
     blockifier_state: &mut CachedState<DictStateReader>,
     inputs: &[Felt252],
     contracts: &HashMap<String, StarknetContractArtifacts>,
+    contracts_execution: &mut ContractsExecution,
 ) -> Result<(), EnhancedHintError> {
-    let contract_value = inputs[0].clone();
+    let contract_value = inputs
+        .first()
+        .cloned()
+        .ok_or_else(|| anyhow!("Missing contract name"))?;
 
     let contract_value_as_short_str = as_cairo_short_string(&contract_value)
         .context("Converting contract name to short string failed")?;
-    let contract_artifact = contracts.get(&contract_value_as_short_str).ok_or_else(|| {
-        anyhow!("Failed to get contract artifact for name = {contract_value_as_short_str}. Make sure starknet target is correctly defined in Scarb.toml file.")
-    })?;
-    let sierra_contract_class: ContractClass = serde_json::from_str(&contract_artifact.sierra)
-        .with_context(|| format!("File to parse json from artifact = {contract_artifact:?}"))?;
-
-    let casm_contract_class = CasmContractClass::from_contract_class(sierra_contract_class, true)
-        .context("Sierra to casm failed")?;
-    let casm_serialized = serde_json::to_string_pretty(&casm_contract_class)
-        .context("Failed to serialize contract to casm")?;
-
-    let contract_class = ContractClassV1::try_from_json_string(&casm_serialized)
-        .context("Failed to read contract class from json")?;
-    let contract_class = BlockifierContractClass::V1(contract_class);
-
-    let class_hash = get_class_hash(casm_serialized.as_str())?;
+    let (class_hash, _, contract_class) =
+        contract_class_from_artifacts(&contract_value_as_short_str, contracts)?;
 
     let nonce = blockifier_state
         .get_nonce_at(ContractAddress(patricia_key!(
@@ -433,26 +798,54 @@ fn declare(
         starknet_api::transaction::DeclareTransaction::V2(declare_tx),
         contract_class,
     )
-    .unwrap_or_else(|err| panic!("Unable to build transaction {err:?}"));
+    .map_err(|err| anyhow!("Unable to build declare transaction: {err}"))?;
 
     let account_tx = AccountTransaction::Declare(tx);
     let block_context = build_block_context();
-    let _tx_result = account_tx
+    let tx_info = account_tx
         .execute(blockifier_state, &block_context)
         .context("Failed to execute declare transaction")?;
+    contracts_execution.charge_fee(blockifier_state, &tx_info.actual_resources)?;
+    contracts_execution
+        .declared_contracts
+        .insert(class_hash, contract_value_as_short_str);
     // result_segment.
-    let felt_class_hash = felt252_from_hex_string(&class_hash.to_string()).unwrap();
+    let felt_class_hash = felt252_from_hex_string(&class_hash.to_string())?;
 
-    buffer
-        .write(Felt252::from(0))
-        .expect("Failed to insert error code");
-    buffer
-        .write(felt_class_hash)
-        .expect("Failed to insert declared contract class hash");
+    buffer.write(Felt252::from(0))?;
+    buffer.write(felt_class_hash)?;
 
     Ok(())
 }
 
+/// Compiles the contract with the given name to casm, returning its class hash, compiled class hash and class
+fn contract_class_from_artifacts(
+    contract_name: &str,
+    contracts: &HashMap<String, StarknetContractArtifacts>,
+) -> Result<(ClassHash, CompiledClassHash, BlockifierContractClass)> {
+    let contract_artifact = contracts.get(contract_name).ok_or_else(|| {
+        anyhow!("Failed to get contract artifact for name = {contract_name}. Make sure starknet target is correctly defined in Scarb.toml file.")
+    })?;
+    let sierra_contract_class: ContractClass = serde_json::from_str(&contract_artifact.sierra)
+        .with_context(|| format!("File to parse json from artifact = {contract_artifact:?}"))?;
+
+    let casm_contract_class = CasmContractClass::from_contract_class(sierra_contract_class, true)
+        .context("Sierra to casm failed")?;
+    let casm_serialized = serde_json::to_string_pretty(&casm_contract_class)
+        .context("Failed to serialize contract to casm")?;
+
+    let contract_class = ContractClassV1::try_from_json_string(&casm_serialized)
+        .context("Failed to read contract class from json")?;
+    let class_hash = get_class_hash(casm_serialized.as_str())?;
+    let compiled_class_hash = get_compiled_class_hash(casm_serialized.as_str())?;
+
+    Ok((
+        class_hash,
+        compiled_class_hash,
+        BlockifierContractClass::V1(contract_class),
+    ))
+}
+
 fn get_class_hash(casm_contract: &str) -> Result<ClassHash> {
     let compiled_class = serde_json::from_str::<CompiledClass>(casm_contract)?;
     let class_hash = compiled_class.class_hash()?;
@@ -460,130 +853,612 @@ fn get_class_hash(casm_contract: &str) -> Result<ClassHash> {
     Ok(ClassHash(class_hash))
 }
 
+/// Hash of the casm class, signed in declare transactions
+fn get_compiled_class_hash(casm_contract: &str) -> Result<CompiledClassHash> {
+    let compiled_class = serde_json::from_str::<CompiledClass>(casm_contract)?;
+    let compiled_class_hash = compiled_class.class_hash()?;
+    let compiled_class_hash = StarkFelt::new(compiled_class_hash.to_bytes_be())?;
+    Ok(CompiledClassHash(compiled_class_hash))
+}
+
+/// Errors of the deploy cheatcode, returned to the test as `cheatcodes::DeployError`
+#[derive(Debug, PartialEq)]
+enum DeployError {
+    ClassNotDeclared { class_hash: Felt252 },
+    ConstructorReverted { panic_data: Vec<Felt252> },
+    AlreadyDeployed { contract_address: Felt252 },
+}
+
+impl DeployError {
+    /// Serializes the error the way Cairo `Serde` does: variant index followed by its data
+    fn to_felts(&self) -> Vec<Felt252> {
+        match self {
+            DeployError::ClassNotDeclared { class_hash } => {
+                vec![Felt252::from(0), class_hash.clone()]
+            }
+            DeployError::ConstructorReverted { panic_data } => {
+                let mut felts = vec![Felt252::from(1), Felt252::from(panic_data.len())];
+                felts.extend(panic_data.iter().cloned());
+                felts
+            }
+            DeployError::AlreadyDeployed { contract_address } => {
+                vec![Felt252::from(2), contract_address.clone()]
+            }
+        }
+    }
+}
+
+/// Inputs of the deploy cheatcode, serialized by `cheatcodes::deploy_with` from the corelib
+#[derive(Debug, PartialEq)]
+struct DeployInputs {
+    class_hash: Felt252,
+    /// Address of the test account is used if not provided
+    deployer_address: Option<Felt252>,
+    /// Address is calculated from the class hash, calldata and deployer if not provided
+    contract_address: Option<Felt252>,
+    calldata: Vec<Felt252>,
+}
+
+impl DeployInputs {
+    fn parse(inputs: &[Felt252]) -> Result<Self> {
+        let mut inputs = inputs.iter().cloned();
+        let class_hash = inputs.next().context("Missing class hash")?;
+        let deployer_address = read_option(&mut inputs).context("Invalid deployer address")?;
+        let contract_address = read_option(&mut inputs).context("Invalid contract address")?;
+        let calldata_length = inputs
+            .next()
+            .and_then(|length| length.to_usize())
+            .context("Failed to convert calldata length to usize")?;
+        let calldata: Vec<Felt252> = inputs.take(calldata_length).collect();
+        if calldata.len() != calldata_length {
+            return Err(anyhow!("Missing constructor calldata"));
+        }
+
+        Ok(Self {
+            class_hash,
+            deployer_address,
+            contract_address,
+            calldata,
+        })
+    }
+}
+
+/// Reads `Option::<felt252>` serialized with Cairo `Serde`: `[0, value]` for `Some` and `[1]` for `None`
+fn read_option(inputs: &mut impl Iterator<Item = Felt252>) -> Result<Option<Felt252>> {
+    let variant = inputs.next().context("Missing option variant")?;
+    if variant == Felt252::from(0) {
+        Ok(Some(inputs.next().context("Missing option value")?))
+    } else if variant == Felt252::from(1) {
+        Ok(None)
+    } else {
+        Err(anyhow!("Unknown option variant: {variant}"))
+    }
+}
+
+fn contract_address_from_felt(address: &Felt252) -> Result<ContractAddress> {
+    Ok(ContractAddress::try_from(StarkFelt::new(
+        address.to_be_bytes(),
+    )?)?)
+}
+
 fn deploy(
     buffer: &mut MemBuffer,
     blockifier_state: &mut CachedState<DictStateReader>,
     inputs: &[Felt252],
+    contracts_execution: &mut ContractsExecution,
 ) -> Result<(), EnhancedHintError> {
-    // TODO(#1991) deploy should fail if contract address provided doesn't match calculated
-    //  or not accept this address as argument at all.
-    let class_hash = inputs[0].clone();
+    let DeployInputs {
+        class_hash,
+        deployer_address,
+        contract_address,
+        calldata,
+    } = DeployInputs::parse(inputs)?;
 
-    let calldata_length = inputs[1].to_usize().unwrap();
-    let mut calldata = vec![];
-    for felt in inputs.iter().skip(2).take(calldata_length) {
-        calldata.push(felt.clone());
+    let deployer_address = match deployer_address {
+        Some(deployer_address) => contract_address_from_felt(&deployer_address)?,
+        None => ContractAddress(patricia_key!(TEST_ACCOUNT_CONTRACT_ADDRESS)),
+    };
+    let class_hash = ClassHash(StarkFelt::new(class_hash.to_be_bytes())?);
+
+    let contract_class = match blockifier_state.get_compiled_contract_class(&class_hash) {
+        Ok(contract_class) => contract_class,
+        Err(StateError::UndeclaredClassHash(_)) => {
+            let error = DeployError::ClassNotDeclared {
+                class_hash: Felt252::from_bytes_be(class_hash.0.bytes()),
+            };
+            write_cheatcode_error(buffer, &error.to_felts())?;
+            return Ok(());
+        }
+        Err(err) => return Err(err.into()),
+    };
+    if contract_class.constructor_selector().is_none() && !calldata.is_empty() {
+        let error = DeployError::ConstructorReverted {
+            panic_data: vec![felt_from_short_string("No constructor in contract")],
+        };
+        write_cheatcode_error(buffer, &error.to_felts())?;
+        return Ok(());
     }
 
-    // Deploy a contract using syscall deploy.
-    let account_address = ContractAddress(patricia_key!(TEST_ACCOUNT_CONTRACT_ADDRESS));
-    let block_context = build_block_context();
-    let entry_point_selector = selector_from_name("deploy_contract");
-    let salt = ContractAddressSalt::default();
-    let class_hash = ClassHash(StarkFelt::new(class_hash.to_be_bytes()).unwrap());
-
-    let contract_class = blockifier_state.get_compiled_contract_class(&class_hash)?;
-    if contract_class.constructor_selector().is_none() && !calldata.is_empty() {
-        write_cheatcode_panic(
-            buffer,
-            vec![felt_from_short_string("No constructor in contract")].as_slice(),
-        );
+    let calldata = Calldata(Arc::new(
+        calldata
+            .iter()
+            .map(|data| StarkFelt::new(data.to_be_bytes()))
+            .collect::<Result<Vec<_>, _>>()?,
+    ));
+    let contract_address = match contract_address {
+        Some(contract_address) => contract_address_from_felt(&contract_address)?,
+        None => calculate_contract_address(
+            ContractAddressSalt::default(),
+            class_hash,
+            &calldata,
+            deployer_address,
+        )?,
+    };
+    let contract_address_felt = Felt252::from_bytes_be(contract_address.0.key().bytes());
+    if blockifier_state.get_class_hash_at(contract_address)? != ClassHash::default() {
+        let error = DeployError::AlreadyDeployed {
+            contract_address: contract_address_felt,
+        };
+        write_cheatcode_error(buffer, &error.to_felts())?;
         return Ok(());
     }
 
-    let execute_calldata = create_execute_calldata(
-        &calldata,
-        &class_hash,
-        &account_address,
-        &entry_point_selector,
-        &salt,
+    let block_context = build_block_context();
+    let mut context = EntryPointExecutionContext::new(
+        block_context.clone(),
+        build_transaction_context(),
+        block_context.invoke_tx_max_n_steps,
     );
+    let ctor_context = ConstructorContext {
+        class_hash,
+        code_address: Some(contract_address),
+        storage_address: contract_address,
+        caller_address: deployer_address,
+    };
+    let mut resources = ExecutionResources::default();
 
-    let nonce = blockifier_state
-        .get_nonce_at(account_address)
-        .context("Failed to get nonce")?;
-    let tx = build_invoke_transaction(execute_calldata, account_address);
-    let account_tx =
-        AccountTransaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1 { nonce, ..tx }));
-
-    let tx_info = account_tx
-        .execute(blockifier_state, &block_context)
-        .unwrap_or_else(|e| panic!("Unparseable transaction error: {e:?}"));
-
-    if let Some(CallInfo { execution, .. }) = tx_info.execute_call_info {
-        let contract_address = execution
-            .retdata
-            .0
-            .get(0)
-            .expect("Failed to get contract_address from return_data");
-        let contract_address = Felt252::from_bytes_be(contract_address.bytes());
-
-        buffer
-            .write(Felt252::from(0))
-            .expect("Failed to insert error code");
-        buffer
-            .write(contract_address)
-            .expect("Failed to insert deployed contract address");
-    } else {
-        let revert_error = tx_info
-            .revert_error
-            .expect("Unparseable tx info, {tx_info:?}");
-        let extracted_panic_data = try_extract_panic_data(&revert_error)
-            .expect("Unparseable error message, {revert_error}");
+    // Changes made by a reverted constructor must not be visible to the test
+    let mut transactional_state = CachedState::create_transactional(blockifier_state);
+    let exec_result = execute_deployment(
+        &mut transactional_state,
+        &mut contracts_execution.cheatcode_state,
+        &mut resources,
+        &mut context,
+        ctor_context,
+        calldata,
+        u64::MAX,
+        &mut ContractCheatcodes,
+    );
 
-        write_cheatcode_panic(buffer, extracted_panic_data.as_slice());
+    match exec_result {
+        Ok(call_info) if !call_info.execution.failed => {
+            contracts_execution.charge_invoke_fee(
+                &mut transactional_state,
+                resources,
+                &call_info,
+            )?;
+            transactional_state.commit();
+            buffer.write(Felt252::from(0))?;
+            buffer.write(contract_address_felt)?;
+            contracts_execution.record_call(call_info);
+        }
+        Ok(call_info) => {
+            transactional_state.abort();
+            let error = DeployError::ConstructorReverted {
+                panic_data: felts_from_stark_felts(&call_info.execution.retdata.0),
+            };
+            write_cheatcode_error(buffer, &error.to_felts())?;
+        }
+        Err(EntryPointExecutionError::ExecutionFailed { error_data }) => {
+            transactional_state.abort();
+            let error = DeployError::ConstructorReverted {
+                panic_data: felts_from_stark_felts(&error_data),
+            };
+            write_cheatcode_error(buffer, &error.to_felts())?;
+        }
+        Err(err) => {
+            transactional_state.abort();
+            return Err(anyhow!("Failed to deploy contract: {err}").into());
+        }
     }
     Ok(())
 }
 
-fn felt_from_short_string(short_str: &str) -> Felt252 {
-    return Felt252::from_bytes_be(short_str.as_bytes());
+/// Errors of the replace bytecode cheatcode, returned to the test as `cheatcodes::ReplaceBytecodeError`
+#[derive(Debug, PartialEq)]
+enum ReplaceBytecodeError {
+    ContractNotDeployed,
+    UndeclaredClassHash,
 }
 
-fn try_extract_panic_data(err: &str) -> Option<Vec<Felt252>> {
-    let re = Regex::new(r#"(?m)^Got an exception while executing a hint: Custom Hint Error: Execution failed\. Failure reason: "(.*)"\.$"#)
-        .expect("Could not create panic_data matching regex");
+impl ReplaceBytecodeError {
+    /// Serializes the error the way Cairo `Serde` does: variant index
+    fn to_felts(&self) -> Vec<Felt252> {
+        match self {
+            ReplaceBytecodeError::ContractNotDeployed => vec![Felt252::from(0)],
+            ReplaceBytecodeError::UndeclaredClassHash => vec![Felt252::from(1)],
+        }
+    }
 
-    if let Some(captures) = re.captures(err) {
-        if let Some(panic_data_match) = captures.get(1) {
-            if panic_data_match.as_str().is_empty() {
-                return Some(vec![]);
-            }
-            let panic_data_felts: Vec<Felt252> = panic_data_match
-                .as_str()
-                .split(", ")
-                .map(felt_from_short_string)
-                .collect();
+    /// Output of the failed cheatcode: error code followed by the serialized error
+    fn to_cheatcode_output(&self) -> Vec<Felt252> {
+        let mut output = vec![Felt252::from(1)];
+        output.extend(self.to_felts());
+        output
+    }
+}
 
-            return Some(panic_data_felts);
+fn replace_bytecode(
+    state: &mut dyn State,
+    inputs: &[Felt252],
+) -> Result<Vec<Felt252>, EnhancedHintError> {
+    let [contract_address, class_hash] = inputs else {
+        return Err(anyhow!("Invalid replace_bytecode inputs: {inputs:?}").into());
+    };
+    let contract_address = contract_address_from_felt(contract_address)?;
+    let class_hash = ClassHash(StarkFelt::new(class_hash.to_be_bytes())?);
+
+    if state.get_class_hash_at(contract_address)? == ClassHash::default() {
+        return Ok(ReplaceBytecodeError::ContractNotDeployed.to_cheatcode_output());
+    }
+    match state.get_compiled_contract_class(&class_hash) {
+        Ok(_) => {}
+        Err(StateError::UndeclaredClassHash(_)) => {
+            return Ok(ReplaceBytecodeError::UndeclaredClassHash.to_cheatcode_output());
         }
+        Err(err) => return Err(err.into()),
     }
-    None
+
+    state.set_class_hash_at(contract_address, class_hash)?;
+    Ok(vec![Felt252::from(0)])
 }
 
-// Should this function panic?
-fn create_execute_calldata(
-    calldata: &[Felt252],
-    class_hash: &ClassHash,
-    account_address: &ContractAddress,
-    entry_point_selector: &EntryPointSelector,
-    salt: &ContractAddressSalt,
-) -> Calldata {
-    let calldata_len = u128::try_from(calldata.len()).unwrap();
-    let mut execute_calldata = vec![
-        *account_address.0.key(),      // Contract address.
-        entry_point_selector.0,        // EP selector.
-        stark_felt!(calldata_len + 3), // Calldata length.
-        class_hash.0,                  // Calldata: class_hash.
-        salt.0,                        // Contract_address_salt.
-        stark_felt!(calldata_len),     // Constructor calldata length.
-    ];
-    let mut calldata: Vec<StarkFelt> = calldata
+/// Sets the fee token balance of the address, the amount is a `u256` serialized as its low and high parts
+fn deal(state: &mut dyn State, inputs: &[Felt252]) -> Result<(), EnhancedHintError> {
+    let [address, low, high] = inputs else {
+        return Err(anyhow!("Invalid deal inputs: {inputs:?}").into());
+    };
+    let balance = Balance {
+        low: low
+            .to_u128()
+            .context("Low part of the amount does not fit in u128")?,
+        high: high
+            .to_u128()
+            .context("High part of the amount does not fit in u128")?,
+    };
+    fees::set_fee_token_balance(state, contract_address_from_felt(address)?, balance);
+    Ok(())
+}
+
+/// Errors of the execute transaction cheatcode, returned to the test as `cheatcodes::TransactionError`
+#[derive(Debug, PartialEq)]
+enum TransactionError {
+    ValidationFailed { panic_data: Vec<Felt252> },
+    ExecutionReverted { panic_data: Vec<Felt252> },
+}
+
+impl TransactionError {
+    /// Serializes the error the way Cairo `Serde` does: variant index followed by its data
+    fn to_felts(&self) -> Vec<Felt252> {
+        let (variant, panic_data) = match self {
+            TransactionError::ValidationFailed { panic_data } => (0, panic_data),
+            TransactionError::ExecutionReverted { panic_data } => (1, panic_data),
+        };
+        let mut felts = vec![Felt252::from(variant), Felt252::from(panic_data.len())];
+        felts.extend(panic_data.iter().cloned());
+        felts
+    }
+}
+
+/// What the execute transaction cheatcode returns to the test after a successful execution
+enum AccountTransactionOutput {
+    /// Return data of `__execute__`
+    ExecuteResult,
+    Declared {
+        class_hash: ClassHash,
+        contract_name: String,
+    },
+    DeployedAccount {
+        contract_address: ContractAddress,
+    },
+}
+
+/// Account transaction ready to be executed by blockifier
+struct BuiltAccountTransaction {
+    transaction_hash: Felt252,
+    transaction: AccountTransaction,
+    output: AccountTransactionOutput,
+}
+
+fn stark_felts(felts: &[Felt252]) -> Result<Vec<StarkFelt>, StarknetApiError> {
+    felts
         .iter()
-        .map(|data| StarkFelt::new(data.to_be_bytes()).unwrap())
-        .collect();
-    execute_calldata.append(&mut calldata);
-    Calldata(execute_calldata.into())
+        .map(|felt| StarkFelt::new(felt.to_be_bytes()))
+        .collect()
+}
+
+/// Address of an account deployed with a deploy account transaction, together with its constructor calldata
+fn deploy_account_address(
+    class_hash: &Felt252,
+    contract_address_salt: &Felt252,
+    constructor_calldata: &[Felt252],
+) -> Result<(ContractAddress, Calldata)> {
+    let constructor_calldata = Calldata(Arc::new(stark_felts(constructor_calldata)?));
+    let contract_address = calculate_contract_address(
+        ContractAddressSalt(StarkFelt::new(contract_address_salt.to_be_bytes())?),
+        ClassHash(StarkFelt::new(class_hash.to_be_bytes())?),
+        &constructor_calldata,
+        ContractAddress::default(),
+    )?;
+    Ok((contract_address, constructor_calldata))
+}
+
+/// Builds the transaction with its hash, which depends on the current nonce of the sender
+fn build_account_transaction(
+    blockifier_state: &mut CachedState<DictStateReader>,
+    transaction: AccountTransactionInputs,
+    signature: Vec<Felt252>,
+    contracts: &HashMap<String, StarknetContractArtifacts>,
+    charge_fees: bool,
+) -> Result<BuiltAccountTransaction> {
+    // Blockifier charges the fee of transactions with a non-zero max fee from the sender
+    let max_fee = if charge_fees { MAX_FEE } else { 0 };
+    let chain_id = build_block_context().chain_id.0;
+    let signature = TransactionSignature(stark_felts(&signature)?);
+
+    match transaction {
+        AccountTransactionInputs::Invoke {
+            sender_address,
+            calldata,
+        } => {
+            let nonce =
+                blockifier_state.get_nonce_at(contract_address_from_felt(&sender_address)?)?;
+            let transaction_hash = calculate_transaction_hash(&TransactionHashFields {
+                prefix: "invoke",
+                version: 1,
+                contract_address: &sender_address,
+                data: &calldata,
+                max_fee,
+                chain_id: &chain_id,
+                nonce: &Felt252::from_bytes_be(nonce.0.bytes()),
+                additional_data: &[],
+            })?;
+            let tx = InvokeTransactionV1 {
+                transaction_hash: TransactionHash(StarkFelt::new(transaction_hash.to_be_bytes())?),
+                max_fee: Fee(max_fee),
+                signature,
+                nonce,
+                sender_address: contract_address_from_felt(&sender_address)?,
+                calldata: Calldata(Arc::new(stark_felts(&calldata)?)),
+            };
+            Ok(BuiltAccountTransaction {
+                transaction_hash,
+                transaction: AccountTransaction::Invoke(InvokeTransaction::V1(tx)),
+                output: AccountTransactionOutput::ExecuteResult,
+            })
+        }
+        AccountTransactionInputs::Declare {
+            sender_address,
+            contract,
+        } => {
+            let contract_name = as_cairo_short_string(&contract)
+                .context("Converting contract name to short string failed")?;
+            let (class_hash, compiled_class_hash, contract_class) =
+                contract_class_from_artifacts(&contract_name, contracts)?;
+            let nonce =
+                blockifier_state.get_nonce_at(contract_address_from_felt(&sender_address)?)?;
+            let transaction_hash = calculate_transaction_hash(&TransactionHashFields {
+                prefix: "declare",
+                version: 2,
+                contract_address: &sender_address,
+                data: &[Felt252::from_bytes_be(class_hash.0.bytes())],
+                max_fee,
+                chain_id: &chain_id,
+                nonce: &Felt252::from_bytes_be(nonce.0.bytes()),
+                additional_data: &[Felt252::from_bytes_be(compiled_class_hash.0.bytes())],
+            })?;
+            let tx = DeclareTransactionV2 {
+                transaction_hash: TransactionHash(StarkFelt::new(transaction_hash.to_be_bytes())?),
+                max_fee: Fee(max_fee),
+                signature,
+                nonce,
+                class_hash,
+                compiled_class_hash,
+                sender_address: contract_address_from_felt(&sender_address)?,
+            };
+            let tx = DeclareTransaction::new(
+                starknet_api::transaction::DeclareTransaction::V2(tx),
+                contract_class,
+            )
+            .map_err(|err| anyhow!("Unable to build declare transaction: {err}"))?;
+            Ok(BuiltAccountTransaction {
+                transaction_hash,
+                transaction: AccountTransaction::Declare(tx),
+                output: AccountTransactionOutput::Declared {
+                    class_hash,
+                    contract_name,
+                },
+            })
+        }
+        AccountTransactionInputs::DeployAccount {
+            class_hash,
+            contract_address_salt,
+            constructor_calldata,
+        } => {
+            let (contract_address, calldata) =
+                deploy_account_address(&class_hash, &contract_address_salt, &constructor_calldata)?;
+            let nonce = Nonce::default();
+            let mut data = vec![class_hash.clone(), contract_address_salt.clone()];
+            data.extend(constructor_calldata);
+            let transaction_hash = calculate_transaction_hash(&TransactionHashFields {
+                prefix: "deploy_account",
+                version: 1,
+                contract_address: &Felt252::from_bytes_be(contract_address.0.key().bytes()),
+                data: &data,
+                max_fee,
+                chain_id: &chain_id,
+                nonce: &Felt252::from_bytes_be(nonce.0.bytes()),
+                additional_data: &[],
+            })?;
+            let tx = DeployAccountTransaction {
+                transaction_hash: TransactionHash(StarkFelt::new(transaction_hash.to_be_bytes())?),
+                max_fee: Fee(max_fee),
+                version: TransactionVersion(StarkFelt::from(1_u8)),
+                signature,
+                nonce,
+                class_hash: ClassHash(StarkFelt::new(class_hash.to_be_bytes())?),
+                contract_address_salt: ContractAddressSalt(StarkFelt::new(
+                    contract_address_salt.to_be_bytes(),
+                )?),
+                constructor_calldata: calldata,
+            };
+            Ok(BuiltAccountTransaction {
+                transaction_hash,
+                transaction: AccountTransaction::DeployAccount(tx),
+                output: AccountTransactionOutput::DeployedAccount { contract_address },
+            })
+        }
+    }
+}
+
+/// Splits the message into short strings, the way Cairo stores longer strings in felts
+fn short_strings_from_message(message: &str) -> Vec<Felt252> {
+    message
+        .as_bytes()
+        .chunks(31)
+        .map(Felt252::from_bytes_be)
+        .collect()
+}
+
+fn execute_transaction(
+    buffer: &mut MemBuffer,
+    blockifier_state: &mut CachedState<DictStateReader>,
+    inputs: &[Felt252],
+    contracts: &HashMap<String, StarknetContractArtifacts>,
+    contracts_execution: &mut ContractsExecution,
+) -> Result<(), EnhancedHintError> {
+    let mut inputs = inputs.iter().cloned();
+    let transaction = AccountTransactionInputs::parse(&mut inputs)?;
+    let signature = read_array(&mut inputs).context("Invalid signature")?;
+    let BuiltAccountTransaction {
+        transaction,
+        output,
+        ..
+    } = build_account_transaction(
+        blockifier_state,
+        transaction,
+        signature,
+        contracts,
+        contracts_execution.charge_fees,
+    )?;
+
+    let block_context = build_block_context();
+    let tx_info = match transaction.execute(blockifier_state, &block_context) {
+        Ok(tx_info) => tx_info,
+        Err(TransactionExecutionError::ValidateTransactionError(error)) => {
+            let panic_data = match error {
+                EntryPointExecutionError::ExecutionFailed { error_data } => error_data
+                    .iter()
+                    .map(|data| Felt252::from_bytes_be(data.bytes()))
+                    .collect(),
+                error => short_strings_from_message(&error.to_string()),
+            };
+            write_cheatcode_error(
+                buffer,
+                &TransactionError::ValidationFailed { panic_data }.to_felts(),
+            )?;
+            return Ok(());
+        }
+        Err(error) => return Err(anyhow!("Failed to execute transaction: {error}").into()),
+    };
+
+    if contracts_execution.charge_fees {
+        contracts_execution.fee_charged += tx_info.actual_fee.0;
+    }
+    for call_info in [
+        tx_info.validate_call_info,
+        tx_info.execute_call_info.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        contracts_execution.record_call(call_info);
+    }
+    if let Some(revert_error) = tx_info.revert_error {
+        let panic_data = short_strings_from_message(&revert_error);
+        write_cheatcode_error(
+            buffer,
+            &TransactionError::ExecutionReverted { panic_data }.to_felts(),
+        )?;
+        return Ok(());
+    }
+
+    let result = match output {
+        AccountTransactionOutput::ExecuteResult => tx_info
+            .execute_call_info
+            .map(|call_info| {
+                call_info
+                    .execution
+                    .retdata
+                    .0
+                    .iter()
+                    .map(|data| Felt252::from_bytes_be(data.bytes()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        AccountTransactionOutput::Declared {
+            class_hash,
+            contract_name,
+        } => {
+            contracts_execution
+                .declared_contracts
+                .insert(class_hash, contract_name);
+            vec![Felt252::from_bytes_be(class_hash.0.bytes())]
+        }
+        AccountTransactionOutput::DeployedAccount { contract_address } => {
+            vec![Felt252::from_bytes_be(contract_address.0.key().bytes())]
+        }
+    };
+    buffer.write(Felt252::from(0))?;
+    buffer.write(Felt252::from(result.len()))?;
+    buffer.write_data(result.iter())?;
+    Ok(())
+}
+
+/// Contract address and the value it is cheated with, passed as the inputs of the start cheatcodes
+fn cheat_inputs(selector: &str, inputs: &[Felt252]) -> Result<(ContractAddress, Felt252)> {
+    let [contract_address, value] = inputs else {
+        return Err(anyhow!("Invalid {selector} inputs: {inputs:?}"));
+    };
+    Ok((contract_address_from_felt(contract_address)?, value.clone()))
+}
+
+/// Contract address and the selector of the function, which is passed by its name as a short string
+fn mocked_function(
+    inputs: &mut impl Iterator<Item = Felt252>,
+) -> Result<(ContractAddress, EntryPointSelector)> {
+    let contract_address = inputs.next().context("Missing contract address")?;
+    let function_name = inputs.next().context("Missing function name")?;
+    let function_name = as_cairo_short_string(&function_name)
+        .context("Converting function name to short string failed")?;
+    Ok((
+        contract_address_from_felt(&contract_address)?,
+        selector_from_name(&function_name),
+    ))
+}
+
+/// Address passed as the only input of a cheatcode
+fn address_input(inputs: &[Felt252]) -> Result<ContractAddress> {
+    let [address] = inputs else {
+        return Err(anyhow!(
+            "Expected a single contract address, got: {inputs:?}"
+        ));
+    };
+    contract_address_from_felt(address)
+}
+
+fn felt_from_short_string(short_str: &str) -> Felt252 {
+    return Felt252::from_bytes_be(short_str.as_bytes());
 }
 
 fn felt252_from_hex_string(value: &str) -> Result<Felt252> {
@@ -596,6 +1471,7 @@ fn felt252_from_hex_string(value: &str) -> Result<Felt252> {
 mod test {
     use assert_fs::fixture::PathCopy;
     use cairo_felt::Felt252;
+    use starknet_api::stark_felt;
     use std::process::Command;
 
     use super::*;
@@ -624,73 +1500,59 @@ mod test {
     }
 
     #[test]
-    fn execute_calldata() {
-        let calldata = create_execute_calldata(
-            &[Felt252::from(100), Felt252::from(200)],
-            &ClassHash(StarkFelt::from(123_u32)),
-            &ContractAddress::try_from(StarkFelt::from(111_u32)).unwrap(),
-            &EntryPointSelector(StarkFelt::from(222_u32)),
-            &ContractAddressSalt(StarkFelt::from(333_u32)),
-        );
+    fn serializing_deploy_errors() {
         assert_eq!(
-            calldata,
-            Calldata(Arc::new(vec![
-                StarkFelt::from(111_u32),
-                StarkFelt::from(222_u32),
-                StarkFelt::from(5_u32),
-                StarkFelt::from(123_u32),
-                StarkFelt::from(333_u32),
-                StarkFelt::from(2_u32),
-                StarkFelt::from(100_u32),
-                StarkFelt::from(200_u32),
-            ]))
+            DeployError::ClassNotDeclared {
+                class_hash: Felt252::from(123)
+            }
+            .to_felts(),
+            vec![Felt252::from(0), Felt252::from(123)]
         );
-    }
-
-    #[test]
-    fn execute_calldata_no_entrypoint_calldata() {
-        let calldata = create_execute_calldata(
-            &[],
-            &ClassHash(StarkFelt::from(123_u32)),
-            &ContractAddress::try_from(StarkFelt::from(111_u32)).unwrap(),
-            &EntryPointSelector(StarkFelt::from(222_u32)),
-            &ContractAddressSalt(StarkFelt::from(333_u32)),
+        assert_eq!(
+            DeployError::ConstructorReverted {
+                panic_data: vec![Felt252::from(5), Felt252::from(6)]
+            }
+            .to_felts(),
+            vec![
+                Felt252::from(1),
+                Felt252::from(2),
+                Felt252::from(5),
+                Felt252::from(6)
+            ]
         );
         assert_eq!(
-            calldata,
-            Calldata(Arc::new(vec![
-                StarkFelt::from(111_u32),
-                StarkFelt::from(222_u32),
-                StarkFelt::from(3_u32),
-                StarkFelt::from(123_u32),
-                StarkFelt::from(333_u32),
-                StarkFelt::from(0_u32),
-            ]))
+            DeployError::AlreadyDeployed {
+                contract_address: Felt252::from(456)
+            }
+            .to_felts(),
+            vec![Felt252::from(2), Felt252::from(456)]
         );
     }
 
     #[test]
-    fn string_extracting_panic_data() {
-        let cases: [(&str, Option<Vec<Felt252>>); 4] = [
-            (
-                "Beginning of trace\nGot an exception while executing a hint: Custom Hint Error: Execution failed. Failure reason: \"PANIK, DAYTA\".\n
-                 End of trace", 
-                Some(vec![Felt252::from(344_693_033_291_u64), Felt252::from(293_154_149_441_u64)])
-            ),
-            (
-                "Got an exception while executing a hint: Custom Hint Error: Execution failed. Failure reason: \"AYY, LMAO\".", 
-                Some(vec![Felt252::from(4_282_713_u64), Felt252::from(1_280_131_407_u64)])
-            ),
-            (
-                "Got an exception while executing a hint: Custom Hint Error: Execution failed. Failure reason: \"\".", 
-                Some(vec![])
-            ),
-            ("Custom Hint Error: Invalid trace: \"PANIC, DATA\"", None)
-        ];
+    fn parsing_deploy_inputs() {
+        let felts = |values: &[u64]| values.iter().map(|v| Felt252::from(*v)).collect::<Vec<_>>();
 
-        for (str, expected) in cases {
-            assert_eq!(try_extract_panic_data(str), expected);
-        }
+        assert_eq!(
+            DeployInputs::parse(&felts(&[123, 1, 1, 2, 5, 6])).unwrap(),
+            DeployInputs {
+                class_hash: Felt252::from(123),
+                deployer_address: None,
+                contract_address: None,
+                calldata: felts(&[5, 6]),
+            }
+        );
+        assert_eq!(
+            DeployInputs::parse(&felts(&[123, 0, 10, 0, 20, 0])).unwrap(),
+            DeployInputs {
+                class_hash: Felt252::from(123),
+                deployer_address: Some(Felt252::from(10)),
+                contract_address: Some(Felt252::from(20)),
+                calldata: vec![],
+            }
+        );
+        assert!(DeployInputs::parse(&felts(&[123, 1, 1, 3, 5])).is_err());
+        assert!(DeployInputs::parse(&felts(&[123, 2, 1, 0])).is_err());
     }
 
     #[test]
@@ -746,4 +1608,33 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn compiled_class_hash_correct() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+            .unwrap();
+
+        Command::new("scarb")
+            .current_dir(&temp)
+            .arg("build")
+            .output()
+            .unwrap();
+
+        // expected_compiled_class_hash computed with `compute_casm_class_hash` of starknet.py
+        let casm_contract_definition = std::fs::read_to_string(
+            temp.path()
+                .join("target/dev/simple_package_HelloStarknet.casm.json"),
+        )
+        .unwrap();
+        let expected_compiled_class_hash =
+            "0x192485856ebf42c113825d359a948dacaf526d62bf6c2aa231beffed0fddc3f";
+
+        let actual_compiled_class_hash =
+            get_compiled_class_hash(casm_contract_definition.as_str()).unwrap();
+        assert_eq!(
+            actual_compiled_class_hash,
+            CompiledClassHash(stark_felt!(expected_compiled_class_hash))
+        );
+    }
 }
@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::fs;
+
+use anyhow::{Context, Result};
+use cairo_lang_sierra::program::Program;
+use cairo_lang_sierra_to_casm::compiler::CairoProgram;
+use camino::{Utf8Path, Utf8PathBuf};
+use test_collector::{FunctionLocation, FunctionLocations};
+
+use crate::execution_trace::{ContractCall, ContractEntryPoints, ExecutedCode, ProgramFunctions};
+use crate::scarb::StarknetContractArtifacts;
+
+const COVERAGE_REPORT_PATH: &str = "coverage/lcov.info";
+
+#[derive(Debug, PartialEq, Clone)]
+struct FunctionCoverage {
+    location: FunctionLocation,
+    hits: usize,
+}
+
+/// Number of times the functions defined in the tested sources were called, keyed by their full path
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Coverage(BTreeMap<String, FunctionCoverage>);
+
+impl Coverage {
+    /// Creates coverage of the functions with the given locations, none of them called yet
+    #[must_use]
+    #[allow(clippy::implicit_hasher)]
+    pub fn new(function_locations: &FunctionLocations) -> Self {
+        Self(
+            function_locations
+                .iter()
+                .map(|(name, location)| {
+                    (
+                        name.clone(),
+                        FunctionCoverage {
+                            location: location.clone(),
+                            hits: 0,
+                        },
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    pub fn merge(&mut self, other: &Coverage) {
+        for (name, function) in &other.0 {
+            self.0
+                .entry(name.clone())
+                .and_modify(|existing| existing.hits += function.hits)
+                .or_insert_with(|| function.clone());
+        }
+    }
+
+    /// Functions without a known location (e.g. from corelib) are not recorded
+    fn record_hit(&mut self, function_name: &str) {
+        if let Some(function) = self.0.get_mut(&strip_generic_args(function_name)) {
+            function.hits += 1;
+        }
+    }
+
+    /// Entry points of contracts are wrappers generated by the Starknet plugin
+    /// (e.g. `package::Contract::__external::function`), hits are recorded for the wrapped function
+    fn record_entry_point_hit(&mut self, wrapper_name: &str) {
+        let wrapper_name = strip_generic_args(wrapper_name);
+        let Some((wrapper_module, function_name)) = wrapper_name.rsplit_once("::") else {
+            return;
+        };
+        let contract_module = wrapper_module
+            .rsplit_once("::")
+            .map_or(wrapper_module, |(contract_module, _)| contract_module);
+        let prefix = format!("{contract_module}::");
+        let suffix = format!("::{function_name}");
+
+        if let Some(function) = self
+            .0
+            .iter_mut()
+            .find(|(name, _)| name.starts_with(&prefix) && name.ends_with(&suffix))
+            .map(|(_, function)| function)
+        {
+            function.hits += 1;
+        }
+    }
+
+    /// Renders the coverage in the LCOV tracefile format
+    ///
+    /// Functions of the package are named differently depending on the compiled test root
+    /// (e.g. `src::function` and `package::function`), they are reported once per location.
+    #[must_use]
+    pub fn to_lcov(&self) -> String {
+        let mut functions_by_file: BTreeMap<&str, BTreeMap<usize, (&str, usize)>> = BTreeMap::new();
+        for (name, function) in &self.0 {
+            functions_by_file
+                .entry(&function.location.file_path)
+                .or_default()
+                .entry(function.location.start_line)
+                .and_modify(|(_, hits)| *hits += function.hits)
+                .or_insert((name, function.hits));
+        }
+
+        let mut lcov = String::new();
+        for (file_path, functions) in functions_by_file {
+            let hit_count = functions.values().filter(|(_, hits)| *hits > 0).count();
+
+            writeln!(lcov, "TN:").unwrap();
+            writeln!(lcov, "SF:{file_path}").unwrap();
+            for (line, (name, _)) in &functions {
+                writeln!(lcov, "FN:{line},{name}").unwrap();
+            }
+            for (name, hits) in functions.values() {
+                writeln!(lcov, "FNDA:{hits},{name}").unwrap();
+            }
+            writeln!(lcov, "FNF:{}", functions.len()).unwrap();
+            writeln!(lcov, "FNH:{hit_count}").unwrap();
+            for (line, (_, hits)) in &functions {
+                writeln!(lcov, "DA:{line},{hits}").unwrap();
+            }
+            writeln!(lcov, "LF:{}", functions.len()).unwrap();
+            writeln!(lcov, "LH:{hit_count}").unwrap();
+            writeln!(lcov, "end_of_record").unwrap();
+        }
+        lcov
+    }
+
+    /// Writes the LCOV report to the workspace and returns its path
+    pub fn save(&self, workspace_root: &Utf8Path) -> Result<Utf8PathBuf> {
+        let path = workspace_root.join(COVERAGE_REPORT_PATH);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory = {parent}"))?;
+        }
+        fs::write(&path, self.to_lcov()).with_context(|| format!("Failed to write to {path:?}"))?;
+        Ok(path)
+    }
+}
+
+/// Sierra names functions of generic impls with their generic arguments, e.g. `ArrayImpl::<felt252>::new`
+fn strip_generic_args(function_name: &str) -> String {
+    let mut result = String::with_capacity(function_name.len());
+    let mut depth = 0_usize;
+    for char in function_name.chars() {
+        match char {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => result.push(char),
+            _ => {}
+        }
+    }
+    result.replace("::::", "::")
+}
+
+/// Records coverage of the tests from a single test file
+pub(crate) struct CoverageCollector<'a> {
+    coverage: Coverage,
+    program_functions: ProgramFunctions,
+    contract_entry_points: ContractEntryPoints<'a>,
+}
+
+impl<'a> CoverageCollector<'a> {
+    pub fn new(
+        function_locations: &FunctionLocations,
+        sierra_program: &Program,
+        casm_program: &CairoProgram,
+        contracts: &'a HashMap<String, StarknetContractArtifacts>,
+    ) -> Self {
+        Self {
+            coverage: Coverage::new(function_locations),
+            program_functions: ProgramFunctions::new(sierra_program, casm_program),
+            contract_entry_points: ContractEntryPoints::new(contracts),
+        }
+    }
+
+    pub fn record(&mut self, executed_code: &ExecutedCode) -> Result<()> {
+        for entry in &executed_code.trace {
+            if let Some(function_name) = self.program_functions.function_starting_at(entry.pc) {
+                self.coverage.record_hit(function_name);
+            }
+        }
+        for (_, call) in &executed_code.contract_calls {
+            self.record_contract_call(call)?;
+        }
+        Ok(())
+    }
+
+    fn record_contract_call(&mut self, call: &ContractCall) -> Result<()> {
+        if let Some(contract_name) = &call.contract_name {
+            if let Some(wrapper_name) = self
+                .contract_entry_points
+                .function_name(contract_name, &call.entry_point_selector)?
+            {
+                self.coverage.record_entry_point_hit(wrapper_name);
+            }
+        }
+        for inner_call in &call.inner_calls {
+            self.record_contract_call(inner_call)?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Coverage {
+        self.coverage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn location(file_path: &str, start_line: usize) -> FunctionLocation {
+        FunctionLocation {
+            file_path: file_path.to_string(),
+            start_line,
+            end_line: start_line + 2,
+        }
+    }
+
+    fn coverage() -> Coverage {
+        Coverage::new(&HashMap::from([
+            (
+                "package::add".to_string(),
+                location("/package/src/lib.cairo", 1),
+            ),
+            (
+                "package::Contract::ContractImpl::increase".to_string(),
+                location("/package/src/contract.cairo", 10),
+            ),
+            (
+                "package::Contract::ContractImpl::get".to_string(),
+                location("/package/src/contract.cairo", 4),
+            ),
+        ]))
+    }
+
+    #[test]
+    fn strips_generic_args() {
+        assert_eq!(
+            strip_generic_args("core::array::ArrayImpl::<core::felt252>::new"),
+            "core::array::ArrayImpl::new"
+        );
+        assert_eq!(
+            strip_generic_args("package::Impl::<Array::<felt252>>::f"),
+            "package::Impl::f"
+        );
+        assert_eq!(strip_generic_args("package::add"), "package::add");
+    }
+
+    #[test]
+    fn records_hits() {
+        let mut coverage = coverage();
+        coverage.record_hit("package::add");
+        coverage.record_hit("package::add");
+        coverage.record_hit("core::array::ArrayImpl::<core::felt252>::new");
+        coverage.record_entry_point_hit("package::Contract::__external::increase");
+
+        assert_eq!(coverage.0["package::add"].hits, 2);
+        assert_eq!(
+            coverage.0["package::Contract::ContractImpl::increase"].hits,
+            1
+        );
+        assert_eq!(coverage.0["package::Contract::ContractImpl::get"].hits, 0);
+        assert_eq!(coverage.0.len(), 3);
+    }
+
+    #[test]
+    fn merges_coverage() {
+        let mut first = coverage();
+        first.record_hit("package::add");
+        let mut second = Coverage::new(&HashMap::from([(
+            "package::other".to_string(),
+            location("/package/src/lib.cairo", 5),
+        )]));
+        second.record_hit("package::other");
+
+        first.merge(&second);
+        first.merge(&second);
+
+        assert_eq!(first.0["package::add"].hits, 1);
+        assert_eq!(first.0["package::other"].hits, 2);
+    }
+
+    #[test]
+    fn renders_functions_with_the_same_location_once() {
+        let mut coverage = Coverage::new(&HashMap::from([
+            (
+                "src::add".to_string(),
+                location("/package/src/lib.cairo", 1),
+            ),
+            (
+                "package::add".to_string(),
+                location("/package/src/lib.cairo", 1),
+            ),
+        ]));
+        coverage.record_hit("src::add");
+        coverage.record_hit("package::add");
+
+        assert_eq!(
+            coverage.to_lcov(),
+            indoc! {r"
+                TN:
+                SF:/package/src/lib.cairo
+                FN:1,package::add
+                FNDA:2,package::add
+                FNF:1
+                FNH:1
+                DA:1,2
+                LF:1
+                LH:1
+                end_of_record
+            "}
+        );
+    }
+
+    #[test]
+    fn renders_lcov() {
+        let mut coverage = coverage();
+        coverage.record_hit("package::add");
+        coverage.record_entry_point_hit("package::Contract::__external::get");
+
+        assert_eq!(
+            coverage.to_lcov(),
+            indoc! {r"
+                TN:
+                SF:/package/src/contract.cairo
+                FN:4,package::Contract::ContractImpl::get
+                FN:10,package::Contract::ContractImpl::increase
+                FNDA:1,package::Contract::ContractImpl::get
+                FNDA:0,package::Contract::ContractImpl::increase
+                FNF:2
+                FNH:1
+                DA:4,1
+                DA:10,0
+                LF:2
+                LH:1
+                end_of_record
+                TN:
+                SF:/package/src/lib.cairo
+                FN:1,package::add
+                FNDA:1,package::add
+                FNF:1
+                FNH:1
+                DA:1,1
+                LF:1
+                LH:1
+                end_of_record
+            "}
+        );
+    }
+}
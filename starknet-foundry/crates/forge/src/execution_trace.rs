@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context, Result};
+use cairo_felt::Felt252;
+use cairo_lang_sierra::program::Program;
+use cairo_lang_sierra_to_casm::compiler::CairoProgram;
+use cairo_lang_starknet::contract_class::ContractClass;
+use itertools::chain;
+
+use crate::scarb::StarknetContractArtifacts;
+
+/// Step of the test code executed by the VM
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) struct TraceEntry {
+    /// Program counter relative to the beginning of the Sierra program code
+    pub pc: usize,
+    pub ap: usize,
+    pub fp: usize,
+}
+
+/// Call of a contract entry point executed by blockifier
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct ContractCall {
+    /// Name of the contract, `None` if it was not declared in the test (e.g. a predeployed account)
+    pub contract_name: Option<String>,
+    pub entry_point_selector: Felt252,
+    /// Steps of the call including the steps of the inner calls
+    pub steps: usize,
+    pub inner_calls: Vec<ContractCall>,
+}
+
+/// Code executed by a single test
+#[derive(Debug, Default)]
+pub(crate) struct ExecutedCode {
+    pub trace: Vec<TraceEntry>,
+    /// Contract calls made from the test code with the index of the trace entry making them
+    pub contract_calls: Vec<(usize, ContractCall)>,
+    /// Codes of the hints of the test code, keyed by their program counters
+    pub hints: HashMap<usize, Vec<String>>,
+}
+
+/// Functions of the Sierra program ordered by the offsets of their code
+pub(crate) struct ProgramFunctions {
+    entry_pcs: Vec<(usize, String)>,
+    code_size: usize,
+}
+
+impl ProgramFunctions {
+    pub fn new(sierra_program: &Program, casm_program: &CairoProgram) -> Self {
+        let mut entry_pcs: Vec<(usize, String)> = sierra_program
+            .funcs
+            .iter()
+            .filter_map(|function| {
+                let statement_info = casm_program
+                    .debug_info
+                    .sierra_statement_info
+                    .get(function.entry_point.0)?;
+                let name = function.id.debug_name.as_ref()?;
+                Some((statement_info.code_offset, name.to_string()))
+            })
+            .collect();
+        entry_pcs.sort();
+
+        let code_size = casm_program
+            .instructions
+            .iter()
+            .map(|instruction| instruction.body.op_size())
+            .sum();
+
+        Self {
+            entry_pcs,
+            code_size,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn from_entry_pcs(entry_pcs: Vec<(usize, String)>, code_size: usize) -> Self {
+        Self {
+            entry_pcs,
+            code_size,
+        }
+    }
+
+    /// Name of the function starting at the program counter
+    pub fn function_starting_at(&self, pc: usize) -> Option<&str> {
+        self.entry_pcs
+            .binary_search_by_key(&pc, |(entry_pc, _)| *entry_pc)
+            .ok()
+            .map(|index| self.entry_pcs[index].1.as_str())
+    }
+
+    /// Name of the function whose code contains the program counter
+    pub fn function_containing(&self, pc: usize) -> Option<&str> {
+        if pc >= self.code_size {
+            return None;
+        }
+        let index = self
+            .entry_pcs
+            .partition_point(|(entry_pc, _)| *entry_pc <= pc);
+        index
+            .checked_sub(1)
+            .map(|index| self.entry_pcs[index].1.as_str())
+    }
+}
+
+/// Offsets of the code of the Sierra program statements
+pub(crate) struct SierraStatements(Vec<usize>);
+
+impl SierraStatements {
+    pub fn new(casm_program: &CairoProgram) -> Self {
+        Self(
+            casm_program
+                .debug_info
+                .sierra_statement_info
+                .iter()
+                .map(|statement_info| statement_info.code_offset)
+                .collect(),
+        )
+    }
+
+    #[cfg(test)]
+    pub fn from_code_offsets(code_offsets: Vec<usize>) -> Self {
+        Self(code_offsets)
+    }
+
+    /// Index of the statement whose code contains the program counter
+    pub fn statement_containing(&self, pc: usize) -> Option<usize> {
+        self.0
+            .partition_point(|code_offset| *code_offset <= pc)
+            .checked_sub(1)
+    }
+}
+
+/// Names of the functions implementing entry points of the contracts, loaded lazily from artifacts
+pub(crate) struct ContractEntryPoints<'a> {
+    contracts: &'a HashMap<String, StarknetContractArtifacts>,
+    entry_points: HashMap<String, HashMap<Felt252, String>>,
+}
+
+impl<'a> ContractEntryPoints<'a> {
+    pub fn new(contracts: &'a HashMap<String, StarknetContractArtifacts>) -> Self {
+        Self {
+            contracts,
+            entry_points: HashMap::new(),
+        }
+    }
+
+    pub fn function_name(
+        &mut self,
+        contract_name: &str,
+        entry_point_selector: &Felt252,
+    ) -> Result<Option<&str>> {
+        if !self.entry_points.contains_key(contract_name) {
+            let artifacts = self.contracts.get(contract_name).ok_or_else(|| {
+                anyhow!("Failed to get contract artifact for name = {contract_name}")
+            })?;
+            self.entry_points.insert(
+                contract_name.to_string(),
+                contract_entry_points(&artifacts.sierra)?,
+            );
+        }
+        Ok(self.entry_points[contract_name]
+            .get(entry_point_selector)
+            .map(String::as_str))
+    }
+}
+
+/// Maps selectors of the contract entry points to names of the functions implementing them
+fn contract_entry_points(sierra: &str) -> Result<HashMap<Felt252, String>> {
+    let contract_class: ContractClass =
+        serde_json::from_str(sierra).context("Failed to parse contract class")?;
+    let Some(debug_info) = &contract_class.sierra_program_debug_info else {
+        return Ok(HashMap::new());
+    };
+    let program = contract_class
+        .extract_sierra_program()
+        .map_err(|err| anyhow!("Failed to extract Sierra program from contract class: {err}"))?;
+
+    let entry_points = &contract_class.entry_points_by_type;
+    Ok(chain!(
+        &entry_points.external,
+        &entry_points.l1_handler,
+        &entry_points.constructor
+    )
+    .filter_map(|entry_point| {
+        let function_id = &program.funcs.get(entry_point.function_idx)?.id;
+        let name = debug_info.user_func_names.get(function_id)?;
+        Some((
+            Felt252::from(entry_point.selector.clone()),
+            name.to_string(),
+        ))
+    })
+    .collect())
+}
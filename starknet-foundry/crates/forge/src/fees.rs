@@ -0,0 +1,129 @@
+use anyhow::{anyhow, Context, Result};
+use blockifier::fee::fee_utils::calculate_tx_fee;
+use blockifier::state::state_api::State;
+use blockifier::transaction::objects::ResourcesMapping;
+use cairo_felt::Felt252;
+use cheatable_starknet::constants::{
+    build_block_context, erc20_balance_keys, TEST_ACCOUNT_CONTRACT_ADDRESS,
+};
+use num_traits::ToPrimitive;
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::patricia_key;
+use starknet_api::state::StorageKey;
+
+/// `Uint256` balance of the fee token
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Balance {
+    pub low: u128,
+    pub high: u128,
+}
+
+impl Balance {
+    fn checked_add(self, amount: u128) -> Option<Self> {
+        let (low, carry) = self.low.overflowing_add(amount);
+        let high = if carry {
+            self.high.checked_add(1)?
+        } else {
+            self.high
+        };
+        Some(Self { low, high })
+    }
+
+    fn checked_sub(self, amount: u128) -> Option<Self> {
+        let (low, borrow) = self.low.overflowing_sub(amount);
+        let high = if borrow {
+            self.high.checked_sub(1)?
+        } else {
+            self.high
+        };
+        Some(Self { low, high })
+    }
+}
+
+fn read_u128(state: &mut dyn State, address: ContractAddress, key: StorageKey) -> Result<u128> {
+    let value = state.get_storage_at(address, key)?;
+    Felt252::from_bytes_be(value.bytes())
+        .to_u128()
+        .context("Fee token balance does not fit in u128")
+}
+
+fn fee_token_balance(state: &mut dyn State, address: ContractAddress) -> Result<Balance> {
+    let fee_token_address = build_block_context().fee_token_address;
+    let (low_key, high_key) = erc20_balance_keys(address);
+    Ok(Balance {
+        low: read_u128(state, fee_token_address, low_key)?,
+        high: read_u128(state, fee_token_address, high_key)?,
+    })
+}
+
+pub(crate) fn set_fee_token_balance(
+    state: &mut dyn State,
+    address: ContractAddress,
+    balance: Balance,
+) {
+    let fee_token_address = build_block_context().fee_token_address;
+    let (low_key, high_key) = erc20_balance_keys(address);
+    state.set_storage_at(fee_token_address, low_key, StarkFelt::from(balance.low));
+    state.set_storage_at(fee_token_address, high_key, StarkFelt::from(balance.high));
+}
+
+/// Calculates the fee of the transaction resources and transfers it from the test account to the sequencer
+pub(crate) fn charge_fee(
+    state: &mut impl State,
+    actual_resources: &ResourcesMapping,
+) -> Result<u128> {
+    let block_context = build_block_context();
+    let fee = calculate_tx_fee(actual_resources, &block_context)
+        .map_err(|err| anyhow!("Failed to calculate the fee: {err}"))?
+        .0;
+
+    let account_address = ContractAddress(patricia_key!(TEST_ACCOUNT_CONTRACT_ADDRESS));
+    let account_balance = fee_token_balance(state, account_address)?
+        .checked_sub(fee)
+        .ok_or_else(|| {
+            anyhow!("Insufficient fee token balance of the test account to pay the fee of {fee}")
+        })?;
+    let sequencer_balance = fee_token_balance(state, block_context.sequencer_address)?
+        .checked_add(fee)
+        .context("Fee token balance of the sequencer overflowed")?;
+
+    set_fee_token_balance(state, account_address, account_balance);
+    set_fee_token_balance(state, block_context.sequencer_address, sequencer_balance);
+    Ok(fee)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adding_to_balance_carries_to_high_part() {
+        let balance = Balance {
+            low: u128::MAX,
+            high: 0,
+        };
+        assert_eq!(balance.checked_add(1), Some(Balance { low: 0, high: 1 }));
+        assert_eq!(
+            Balance {
+                low: u128::MAX,
+                high: u128::MAX
+            }
+            .checked_add(1),
+            None
+        );
+    }
+
+    #[test]
+    fn subtracting_from_balance_borrows_from_high_part() {
+        let balance = Balance { low: 0, high: 1 };
+        assert_eq!(
+            balance.checked_sub(1),
+            Some(Balance {
+                low: u128::MAX,
+                high: 0
+            })
+        );
+        assert_eq!(Balance { low: 5, high: 0 }.checked_sub(6), None);
+    }
+}
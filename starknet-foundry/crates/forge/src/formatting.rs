@@ -0,0 +1,297 @@
+use std::fmt;
+
+use anyhow::{anyhow, Context, Result};
+use cairo_felt::Felt252;
+use cairo_lang_runner::short_string::as_cairo_short_string;
+use num_traits::ToPrimitive;
+
+use crate::account_transactions::read_array;
+
+/// Value written by `forge_print::Format`, rendered like Rust `Debug`
+#[derive(Debug, PartialEq)]
+enum FormattedValue {
+    Felt252(Felt252),
+    Bool(bool),
+    Unsigned(Felt252),
+    /// Negative values wrap around the field prime
+    Signed(Felt252),
+    U256 {
+        low: Felt252,
+        high: Felt252,
+    },
+    Address(Felt252),
+    Sequence(Vec<FormattedValue>),
+    Tuple(Vec<FormattedValue>),
+    Struct {
+        name: String,
+        fields: Vec<(String, FormattedValue)>,
+    },
+    Enum {
+        name: String,
+        variant: String,
+        value: Box<FormattedValue>,
+    },
+}
+
+impl FormattedValue {
+    /// Reads the value encoded as its kind followed by its data, see `forge_print.cairo`
+    fn parse(inputs: &mut impl Iterator<Item = Felt252>) -> Result<Self> {
+        let kind = next_usize(inputs).context("Invalid kind of the formatted value")?;
+        let value = match kind {
+            0 => Self::Felt252(next(inputs)?),
+            1 => Self::Bool(next(inputs)? != Felt252::from(0)),
+            2 => Self::Unsigned(next(inputs)?),
+            3 => Self::Signed(next(inputs)?),
+            4 => Self::U256 {
+                low: next(inputs)?,
+                high: next(inputs)?,
+            },
+            5 => Self::Address(next(inputs)?),
+            6 => Self::Sequence(Self::parse_values(inputs)?),
+            7 => Self::Tuple(Self::parse_values(inputs)?),
+            8 => {
+                let name = next_short_string(inputs)?;
+                let fields_count = next_usize(inputs).context("Invalid number of fields")?;
+                let fields = (0..fields_count)
+                    .map(|_| Ok((next_short_string(inputs)?, Self::parse(inputs)?)))
+                    .collect::<Result<_>>()?;
+                Self::Struct { name, fields }
+            }
+            9 => Self::Enum {
+                name: next_short_string(inputs)?,
+                variant: next_short_string(inputs)?,
+                value: Box::new(Self::parse(inputs)?),
+            },
+            _ => return Err(anyhow!("Unknown kind of the formatted value: {kind}")),
+        };
+        Ok(value)
+    }
+
+    fn parse_values(inputs: &mut impl Iterator<Item = Felt252>) -> Result<Vec<Self>> {
+        let length = next_usize(inputs).context("Invalid number of values")?;
+        (0..length).map(|_| Self::parse(inputs)).collect()
+    }
+}
+
+impl fmt::Display for FormattedValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Felt252(value) | Self::Unsigned(value) => write!(f, "{value}"),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Signed(value) => write!(f, "{}", value.to_bigint()),
+            Self::U256 { low, high } => {
+                write!(f, "{}", (high.to_biguint() << 128) + low.to_biguint())
+            }
+            Self::Address(value) => write!(f, "{:#x}", value.to_biguint()),
+            Self::Sequence(values) => {
+                write!(f, "[")?;
+                write_separated(f, values)?;
+                write!(f, "]")
+            }
+            Self::Tuple(values) => {
+                write!(f, "(")?;
+                write_separated(f, values)?;
+                if values.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            Self::Struct { name, fields } => {
+                write!(f, "{name}")?;
+                if !fields.is_empty() {
+                    write!(f, " {{ ")?;
+                    for (index, (field_name, value)) in fields.iter().enumerate() {
+                        if index > 0 {
+                            write!(f, ", ")?;
+                        }
+                        write!(f, "{field_name}: {value}")?;
+                    }
+                    write!(f, " }}")?;
+                }
+                Ok(())
+            }
+            Self::Enum {
+                name,
+                variant,
+                value,
+            } => {
+                write!(f, "{name}::{variant}")?;
+                match value.as_ref() {
+                    Self::Tuple(values) if values.is_empty() => Ok(()),
+                    value => write!(f, "({value})"),
+                }
+            }
+        }
+    }
+}
+
+fn write_separated(f: &mut fmt::Formatter<'_>, values: &[FormattedValue]) -> fmt::Result {
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{value}")?;
+    }
+    Ok(())
+}
+
+fn next(inputs: &mut impl Iterator<Item = Felt252>) -> Result<Felt252> {
+    inputs.next().context("Missing formatted value data")
+}
+
+fn next_usize(inputs: &mut impl Iterator<Item = Felt252>) -> Result<usize> {
+    next(inputs)?.to_usize().context("Failed to read usize")
+}
+
+fn next_short_string(inputs: &mut impl Iterator<Item = Felt252>) -> Result<String> {
+    let value = next(inputs)?;
+    as_cairo_short_string(&value).with_context(|| format!("{value} is not a short string"))
+}
+
+/// Renders the inputs of the `println` cheatcode: the format string split into short strings,
+/// followed by the arguments replacing `{}` in it
+pub(crate) fn format(inputs: &[Felt252]) -> Result<String> {
+    let mut inputs = inputs.iter().cloned();
+    let format_string = read_array(&mut inputs)
+        .context("Invalid format string")?
+        .iter()
+        .map(|chunk| {
+            as_cairo_short_string(chunk)
+                .with_context(|| format!("Format string chunk {chunk} is not a short string"))
+        })
+        .collect::<Result<String>>()?;
+
+    let mut output = String::new();
+    let mut chars = format_string.chars().peekable();
+    while let Some(char) = chars.next() {
+        match (char, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                output.push(char);
+                chars.next();
+            }
+            ('{', Some('}')) => {
+                chars.next();
+                let value = FormattedValue::parse(&mut inputs)
+                    .context("Missing argument for a placeholder in the format string")?;
+                output.push_str(&value.to_string());
+            }
+            ('{' | '}', _) => {
+                return Err(anyhow!(
+                    "Invalid format string: {format_string}, use {{{{ and }}}} to print braces"
+                ));
+            }
+            _ => output.push(char),
+        }
+    }
+    if inputs.next().is_some() {
+        return Err(anyhow!(
+            "More arguments than placeholders in the format string: {format_string}"
+        ));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn short_string(value: &str) -> Felt252 {
+        Felt252::from_bytes_be(value.as_bytes())
+    }
+
+    fn format_inputs(format_string: &str, args: Vec<Felt252>) -> Vec<Felt252> {
+        let mut inputs = vec![Felt252::from(1), short_string(format_string)];
+        inputs.extend(args);
+        inputs
+    }
+
+    #[test]
+    fn formatting_primitive_values() {
+        let inputs = format_inputs(
+            "{} {} {} {} {}",
+            vec![
+                Felt252::from(0),
+                Felt252::from(123),
+                Felt252::from(1),
+                Felt252::from(1),
+                Felt252::from(3),
+                Felt252::from(-1),
+                Felt252::from(4),
+                Felt252::from(5),
+                Felt252::from(1),
+                Felt252::from(5),
+                Felt252::from(255),
+            ],
+        );
+        assert_eq!(
+            format(&inputs).unwrap(),
+            "123 true -1 340282366920938463463374607431768211461 0xff"
+        );
+    }
+
+    #[test]
+    fn formatting_nested_values() {
+        // Option::Some(Point { x: 1, y: [2, 3] }), (), (true,)
+        let inputs = format_inputs(
+            "{}, {}, {}",
+            vec![
+                Felt252::from(9),
+                short_string("Option"),
+                short_string("Some"),
+                Felt252::from(8),
+                short_string("Point"),
+                Felt252::from(2),
+                short_string("x"),
+                Felt252::from(2),
+                Felt252::from(1),
+                short_string("y"),
+                Felt252::from(6),
+                Felt252::from(2),
+                Felt252::from(2),
+                Felt252::from(2),
+                Felt252::from(2),
+                Felt252::from(3),
+                Felt252::from(7),
+                Felt252::from(0),
+                Felt252::from(7),
+                Felt252::from(1),
+                Felt252::from(1),
+                Felt252::from(1),
+            ],
+        );
+        assert_eq!(
+            format(&inputs).unwrap(),
+            "Option::Some(Point { x: 1, y: [2, 3] }), (), (true,)"
+        );
+    }
+
+    #[test]
+    fn formatting_enum_without_value() {
+        let inputs = format_inputs(
+            "{{{}}}",
+            vec![
+                Felt252::from(9),
+                short_string("Option"),
+                short_string("None"),
+                Felt252::from(7),
+                Felt252::from(0),
+            ],
+        );
+        assert_eq!(format(&inputs).unwrap(), "{Option::None}");
+    }
+
+    #[test]
+    fn mismatched_arguments_fail() {
+        assert!(format(&format_inputs(
+            "{} {}",
+            vec![Felt252::from(0), Felt252::from(1)]
+        ))
+        .is_err());
+        assert!(format(&format_inputs(
+            "{}",
+            vec![Felt252::from(0), Felt252::from(1), Felt252::from(0)]
+        ))
+        .is_err());
+        assert!(format(&format_inputs("{", vec![])).is_err());
+    }
+}
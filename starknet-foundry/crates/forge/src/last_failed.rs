@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use anyhow::{Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::test_case_summary::TestCaseSummary;
+use crate::TestFileSummary;
+
+const LAST_FAILED_PATH: &str = "target/snforge/last-failed.json";
+
+/// Names of the tests that failed in the previous runs, grouped by the package name
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct LastFailed(BTreeMap<String, Vec<String>>);
+
+impl LastFailed {
+    /// Load failed tests saved in the workspace, returns an empty record if there is none
+    pub fn load(workspace_root: &Utf8Path) -> Result<Self> {
+        let path = last_failed_path(workspace_root);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {path:?} contents"))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {path:?} contents"))
+    }
+
+    pub fn save(&self, workspace_root: &Utf8Path) -> Result<()> {
+        let path = last_failed_path(workspace_root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory = {parent}"))?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(&path, content).with_context(|| format!("Failed to write to {path:?}"))
+    }
+
+    /// Returns tests which failed for the package, or `None` if there were no failures
+    #[must_use]
+    pub fn tests_for_package(&self, package_name: &str) -> Option<&Vec<String>> {
+        self.0.get(package_name).filter(|tests| !tests.is_empty())
+    }
+
+    /// Record results of the package run
+    ///
+    /// Tests that passed are forgotten and tests that failed are added. Failures of the tests
+    /// that were not run this time are kept.
+    pub fn update(&mut self, package_name: &str, summaries: &[TestFileSummary]) {
+        let failed_tests = self.0.entry(package_name.to_string()).or_default();
+
+        for case in summaries
+            .iter()
+            .flat_map(|summary| &summary.test_case_summaries)
+        {
+            match case {
+                TestCaseSummary::Passed { name, .. } => failed_tests.retain(|test| test != name),
+                TestCaseSummary::Failed { name, .. }
+                | TestCaseSummary::Interrupted { name, .. } => {
+                    if !failed_tests.contains(name) {
+                        failed_tests.push(name.clone());
+                    }
+                }
+                TestCaseSummary::Skipped { .. } => {}
+            }
+        }
+
+        if failed_tests.is_empty() {
+            self.0.remove(package_name);
+        }
+    }
+}
+
+fn last_failed_path(workspace_root: &Utf8Path) -> Utf8PathBuf {
+    workspace_root.join(LAST_FAILED_PATH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RunnerStatus;
+
+    fn file_summary(test_case_summaries: Vec<TestCaseSummary>) -> TestFileSummary {
+        TestFileSummary {
+            test_case_summaries,
+            runner_exit_status: RunnerStatus::Default,
+            relative_path: Utf8PathBuf::from("tests/test_file.cairo"),
+            coverage: None,
+        }
+    }
+
+    fn failed(name: &str) -> TestCaseSummary {
+        TestCaseSummary::Failed {
+            name: name.to_string(),
+            run_result: None,
+            msg: None,
+            fee: None,
+        }
+    }
+
+    #[test]
+    fn update_adds_failed_and_keeps_not_run_tests() {
+        let mut last_failed = LastFailed::default();
+
+        last_failed.update(
+            "package",
+            &[file_summary(vec![
+                failed("test::first"),
+                failed("test::second"),
+            ])],
+        );
+        assert_eq!(
+            last_failed.tests_for_package("package"),
+            Some(&vec!["test::first".to_string(), "test::second".to_string()])
+        );
+
+        last_failed.update(
+            "package",
+            &[file_summary(vec![
+                failed("test::third"),
+                TestCaseSummary::Skipped {
+                    name: "test::first".to_string(),
+                },
+            ])],
+        );
+        assert_eq!(
+            last_failed.tests_for_package("package"),
+            Some(&vec![
+                "test::first".to_string(),
+                "test::second".to_string(),
+                "test::third".to_string()
+            ])
+        );
+        assert_eq!(last_failed.tests_for_package("other_package"), None);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let workspace_root = Utf8PathBuf::from_path_buf(temp.to_path_buf()).unwrap();
+
+        assert_eq!(
+            LastFailed::load(&workspace_root).unwrap(),
+            LastFailed::default()
+        );
+
+        let mut last_failed = LastFailed::default();
+        last_failed.update("package", &[file_summary(vec![failed("test::first")])]);
+        last_failed.save(&workspace_root).unwrap();
+
+        assert!(workspace_root.join(LAST_FAILED_PATH).exists());
+        assert_eq!(LastFailed::load(&workspace_root).unwrap(), last_failed);
+    }
+}
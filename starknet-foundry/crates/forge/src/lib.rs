@@ -1,50 +1,139 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
 
 use anyhow::{Context, Result};
 use camino::{Utf8Path, Utf8PathBuf};
-use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use test_case_summary::TestCaseSummary;
 use walkdir::WalkDir;
 
+use cairo_lang_filesystem::cfg::Cfg;
 use cairo_lang_runner::SierraCasmRunner;
 use cairo_lang_sierra::program::Program;
 use cairo_lang_sierra_to_casm::metadata::MetadataComputationConfig;
 use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
 
+use crate::coverage::{Coverage, CoverageCollector};
+use crate::execution_trace::{ContractEntryPoints, ProgramFunctions, SierraStatements};
+use crate::profiler::Profile;
 use crate::running::run_from_test_case;
 use crate::scarb::StarknetContractArtifacts;
-use test_collector::{collect_tests, LinkedLibrary, TestCase};
-
+use crate::test_filter::TestFilter;
+use crate::trace_dump::TraceDump;
+use test_collector::discovery::{discover_tests, DiscoveredTest};
+use test_collector::{
+    collect_tests_from_roots, CollectedTests, FunctionLocation, FunctionLocations, LinkedLibrary,
+    TestCase,
+};
+
+pub mod cfg_items;
+pub mod coverage;
+pub mod last_failed;
 pub mod pretty_printing;
 pub mod scarb;
 pub mod test_case_summary;
+pub mod test_filter;
+pub mod watch;
 
+mod account_transactions;
 mod cheatcodes_hint_processor;
+mod execution_trace;
+mod fees;
+mod formatting;
+mod profiler;
 mod running;
+mod signing;
+mod trace_dump;
 mod vm_memory;
 
 /// Configuration of the test runner
 #[derive(Deserialize, Debug, PartialEq, Default)]
 pub struct RunnerConfig {
-    test_name_filter: Option<String>,
-    exact_match: bool,
+    test_filter: TestFilter,
     exit_first: bool,
+    tests_to_rerun: Option<Vec<String>>,
+    test_files: Option<Vec<Utf8PathBuf>>,
+    coverage: bool,
+    profile_dir: Option<Utf8PathBuf>,
+    trace_dir: Option<Utf8PathBuf>,
+    max_steps: Option<usize>,
+    timeout: Option<u64>,
+    charge_fees: bool,
+    #[serde(skip)]
+    cfg_items: Vec<Cfg>,
 }
 
 impl RunnerConfig {
     #[must_use]
     pub fn new(
-        test_name_filter: Option<String>,
-        exact_match: bool,
+        test_filter: TestFilter,
         exit_first: bool,
+        tests_to_rerun: Option<Vec<String>>,
+        max_steps: Option<usize>,
+        timeout: Option<u64>,
         forge_config_from_scarb: &ForgeConfigFromScarb,
     ) -> Self {
         Self {
-            test_name_filter,
-            exact_match,
+            test_filter,
             exit_first: forge_config_from_scarb.exit_first || exit_first,
+            max_steps: max_steps.or(forge_config_from_scarb.max_steps),
+            timeout: timeout.or(forge_config_from_scarb.timeout),
+            tests_to_rerun,
+            test_files: None,
+            coverage: false,
+            profile_dir: None,
+            trace_dir: None,
+            charge_fees: false,
+            cfg_items: vec![],
+        }
+    }
+
+    /// Record which functions of the tested code are executed
+    #[must_use]
+    pub fn with_coverage(self, coverage: bool) -> Self {
+        Self { coverage, ..self }
+    }
+
+    /// Save profiles of the executed tests to the directory
+    #[must_use]
+    pub fn with_profile_dir(self, profile_dir: Option<Utf8PathBuf>) -> Self {
+        Self {
+            profile_dir,
+            ..self
+        }
+    }
+
+    /// Save traces of the executed tests to the directory
+    #[must_use]
+    pub fn with_trace_dir(self, trace_dir: Option<Utf8PathBuf>) -> Self {
+        Self { trace_dir, ..self }
+    }
+
+    /// Charge fees for declarations, deployments and contract calls made by the tests
+    #[must_use]
+    pub fn with_charge_fees(self, charge_fees: bool) -> Self {
+        Self {
+            charge_fees,
+            ..self
+        }
+    }
+
+    /// Compile the tests with the cfg items enabled in addition to `test`
+    #[must_use]
+    pub fn with_cfg_items(self, cfg_items: Vec<Cfg>) -> Self {
+        Self { cfg_items, ..self }
+    }
+
+    fn record_execution(&self) -> bool {
+        self.coverage || self.profile_dir.is_some() || self.trace_dir.is_some()
+    }
+
+    /// Restrict collection to the given test files (compilation roots) of the package
+    #[must_use]
+    pub fn with_test_files(self, test_files: Vec<Utf8PathBuf>) -> Self {
+        Self {
+            test_files: Some(test_files),
+            ..self
         }
     }
 }
@@ -61,22 +150,50 @@ pub enum RunnerStatus {
 pub struct ForgeConfigFromScarb {
     #[serde(default)]
     exit_first: bool,
+    /// Default maximum number of steps of a single test
+    max_steps: Option<usize>,
+    /// Default timeout of a single test in seconds
+    timeout: Option<u64>,
 }
 
-struct TestsFromFile {
+/// Tests compiled from a single test root into one Sierra program
+struct CompiledTests {
     sierra_program: Program,
+    function_locations: FunctionLocations,
+    /// Tests grouped by the files they are reported for
+    tests_from_files: Vec<TestsFromFile>,
+}
+
+struct TestsFromFile {
     test_cases: Vec<TestCase>,
     relative_path: Utf8PathBuf,
 }
 
+/// Finds the test roots of the package, restricted to the selected test files if there are any
+fn select_test_roots(
+    package_path: &Utf8PathBuf,
+    lib_path: &Utf8PathBuf,
+    runner_config: &RunnerConfig,
+) -> Result<Vec<Utf8PathBuf>> {
+    let test_files = find_cairo_root_files_in_directory(package_path, lib_path)?;
+    Ok(if let Some(selected_files) = &runner_config.test_files {
+        test_files
+            .into_iter()
+            .filter(|test_file| selected_files.contains(test_file))
+            .collect()
+    } else {
+        test_files
+    })
+}
+
 fn collect_tests_from_directory(
     package_path: &Utf8PathBuf,
     lib_path: &Utf8PathBuf,
     linked_libraries: &Option<Vec<LinkedLibrary>>,
     corelib_path: Option<&Utf8PathBuf>,
     runner_config: &RunnerConfig,
-) -> Result<Vec<TestsFromFile>> {
-    let test_files = find_cairo_root_files_in_directory(package_path, lib_path)?;
+) -> Result<Vec<CompiledTests>> {
+    let test_files = select_test_roots(package_path, lib_path, runner_config)?;
     internal_collect_tests(
         package_path,
         linked_libraries,
@@ -86,6 +203,16 @@ fn collect_tests_from_directory(
     )
 }
 
+/// Root of the `tests` directory compiled as a single crate, relative to the package
+const TESTS_CRATE_ROOT: &str = "tests/lib.cairo";
+
+/// Returns the root of the `tests` crate if the package compiles the `tests` directory as a single crate
+#[must_use]
+pub fn tests_crate_root(package_path: &Utf8Path) -> Option<Utf8PathBuf> {
+    let root = package_path.join(TESTS_CRATE_ROOT);
+    root.is_file().then_some(root)
+}
+
 fn find_cairo_root_files_in_directory(
     package_path: &Utf8PathBuf,
     lib_path: &Utf8PathBuf,
@@ -94,11 +221,20 @@ fn find_cairo_root_files_in_directory(
     let src_path = lib_path.parent().with_context(|| {
         format!("Failed to get parent directory of a package at path = {lib_path}")
     })?;
+    // Files of the tests crate are its modules, not separate roots
+    let tests_crate_root = tests_crate_root(package_path);
+    let tests_path = package_path.join("tests");
+    if let Some(tests_crate_root) = &tests_crate_root {
+        test_files.push(tests_crate_root.clone());
+    }
 
     for entry in WalkDir::new(package_path)
         .sort_by_file_name()
         .into_iter()
-        .filter_entry(|e| !e.path().starts_with(src_path))
+        .filter_entry(|e| {
+            !e.path().starts_with(src_path)
+                && !(tests_crate_root.is_some() && e.path().starts_with(&tests_path))
+        })
     {
         let entry =
             entry.with_context(|| format!("Failed to read directory at path = {package_path}"))?;
@@ -121,29 +257,7 @@ fn internal_collect_tests(
     test_roots: &[Utf8PathBuf],
     corelib_path: Option<&Utf8PathBuf>,
     runner_config: &RunnerConfig,
-) -> Result<Vec<TestsFromFile>> {
-    let tests: Result<Vec<TestsFromFile>> = test_roots
-        .par_iter()
-        .map(|tf| {
-            collect_tests_from_tree(
-                tf,
-                package_path,
-                linked_libraries,
-                corelib_path,
-                runner_config,
-            )
-        })
-        .collect();
-    tests
-}
-
-fn collect_tests_from_tree(
-    test_root: &Utf8PathBuf,
-    package_path: &Utf8PathBuf,
-    linked_libraries: &Option<Vec<LinkedLibrary>>,
-    corelib_path: Option<&Utf8PathBuf>,
-    runner_config: &RunnerConfig,
-) -> Result<TestsFromFile> {
+) -> Result<Vec<CompiledTests>> {
     let builtins = vec![
         "Pedersen",
         "RangeCheck",
@@ -155,29 +269,169 @@ fn collect_tests_from_tree(
         "System",
     ];
 
-    let (sierra_program, tests_configs) = collect_tests(
-        test_root.as_str(),
-        None,
+    let input_paths: Vec<&str> = test_roots.iter().map(Utf8PathBuf::as_str).collect();
+    let collected_tests = collect_tests_from_roots(
+        &input_paths,
         linked_libraries.clone(),
-        Some(builtins.clone()),
+        Some(builtins),
         corelib_path.map(|corelib_path| corelib_path.as_str()),
+        &runner_config.cfg_items,
     )?;
 
+    test_roots
+        .iter()
+        .zip(collected_tests)
+        .map(|(test_root, collected_tests)| {
+            tests_from_tree(test_root, package_path, runner_config, collected_tests)
+        })
+        .collect()
+}
+
+/// Returns tests compiled from the test root,
+/// tests of the `tests` crate are reported by the files of the modules they are defined in
+fn tests_from_tree(
+    test_root: &Utf8PathBuf,
+    package_path: &Utf8PathBuf,
+    runner_config: &RunnerConfig,
+    (sierra_program, tests_configs, function_locations): CollectedTests,
+) -> Result<CompiledTests> {
     let test_cases = strip_path_from_test_names(tests_configs)?;
-    let test_cases = if let Some(test_name_filter) = &runner_config.test_name_filter {
-        filter_tests_by_name(test_name_filter, runner_config.exact_match, test_cases)?
+    let relative_path = test_root.strip_prefix(package_path)?.to_path_buf();
+    let test_cases_by_file = if relative_path == TESTS_CRATE_ROOT {
+        group_by_module_file(
+            test_cases,
+            &function_locations,
+            package_path,
+            &relative_path,
+        )
     } else {
-        test_cases
+        vec![(relative_path, test_cases)]
     };
 
-    let relative_path = test_root.strip_prefix(package_path)?.to_path_buf();
-    Ok(TestsFromFile {
+    let tests_from_files = test_cases_by_file
+        .into_iter()
+        .map(|(relative_path, test_cases)| {
+            let test_cases = runner_config.test_filter.filter(test_cases)?;
+            let test_cases = if let Some(tests_to_rerun) = &runner_config.tests_to_rerun {
+                filter_tests_to_rerun(tests_to_rerun, test_cases)?
+            } else {
+                test_cases
+            };
+
+            Ok(TestsFromFile {
+                test_cases,
+                relative_path,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(CompiledTests {
         sierra_program,
-        test_cases,
-        relative_path,
+        function_locations,
+        tests_from_files,
     })
 }
 
+/// Groups test cases by the files they are defined in, relative to the package,
+/// tests without a known location are assigned to the crate root
+fn group_by_module_file(
+    test_cases: Vec<TestCase>,
+    function_locations: &FunctionLocations,
+    package_path: &Utf8Path,
+    crate_root: &Utf8Path,
+) -> Vec<(Utf8PathBuf, Vec<TestCase>)> {
+    // Names of the test cases are stripped of the crate path, so the locations have to be as well
+    let locations_by_test_name: HashMap<&str, &FunctionLocation> = function_locations
+        .iter()
+        .filter_map(|(name, location)| Some((test_name_without_path(name)?, location)))
+        .collect();
+    let mut test_cases_by_file: BTreeMap<Utf8PathBuf, Vec<TestCase>> = BTreeMap::new();
+    for test_case in test_cases {
+        let file = locations_by_test_name
+            .get(test_case.name.as_str())
+            .and_then(|location| {
+                Utf8Path::new(&location.file_path)
+                    .strip_prefix(package_path)
+                    .ok()
+            })
+            .unwrap_or(crate_root)
+            .to_path_buf();
+        test_cases_by_file.entry(file).or_default().push(test_case);
+    }
+    test_cases_by_file.into_iter().collect()
+}
+
+/// Prints names of the collected tests without running them
+pub fn list(
+    package_path: &Utf8PathBuf,
+    lib_path: &Utf8PathBuf,
+    linked_libraries: &Option<Vec<LinkedLibrary>>,
+    runner_config: &RunnerConfig,
+    corelib_path: Option<&Utf8PathBuf>,
+) -> Result<()> {
+    let tests = collect_tests_from_directory(
+        package_path,
+        lib_path,
+        linked_libraries,
+        corelib_path,
+        runner_config,
+    )?;
+
+    for test_case in tests
+        .iter()
+        .flat_map(|tests| &tests.tests_from_files)
+        .flat_map(|tests_from_file| &tests_from_file.test_cases)
+    {
+        pretty_printing::print_test_name(&test_case.name);
+    }
+    Ok(())
+}
+
+/// Test of a package found by `discover`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PackageTest {
+    /// Name of the package the test belongs to
+    pub package: String,
+    #[serde(flatten)]
+    pub test: DiscoveredTest,
+}
+
+/// Finds tests of the package selected by the runner config, without compiling them to Sierra
+pub fn discover(
+    package_name: &str,
+    package_path: &Utf8PathBuf,
+    lib_path: &Utf8PathBuf,
+    linked_libraries: &Option<Vec<LinkedLibrary>>,
+    runner_config: &RunnerConfig,
+    corelib_path: Option<&Utf8PathBuf>,
+) -> Result<Vec<PackageTest>> {
+    let test_roots = select_test_roots(package_path, lib_path, runner_config)?;
+    let input_paths: Vec<&str> = test_roots.iter().map(Utf8PathBuf::as_str).collect();
+    let tests = discover_tests(
+        &input_paths,
+        linked_libraries.clone(),
+        corelib_path.map(|corelib_path| corelib_path.as_str()),
+        &runner_config.cfg_items,
+    )?;
+
+    let tests = runner_config
+        .test_filter
+        .filter_by_path(tests, |test| &test.path)?;
+    Ok(tests
+        .into_iter()
+        .filter(|test| {
+            runner_config
+                .tests_to_rerun
+                .as_ref()
+                .map_or(true, |tests_to_rerun| tests_to_rerun.contains(&test.path))
+        })
+        .map(|test| PackageTest {
+            package: package_name.to_string(),
+            test,
+        })
+        .collect())
+}
+
 #[allow(clippy::implicit_hasher)]
 pub fn run(
     package_path: &Utf8PathBuf,
@@ -188,7 +442,7 @@ pub fn run(
     contracts: &HashMap<String, StarknetContractArtifacts>,
     predeployed_contracts: &Utf8PathBuf,
 ) -> Result<Vec<TestFileSummary>> {
-    let tests = collect_tests_from_directory(
+    let compiled_tests = collect_tests_from_directory(
         package_path,
         lib_path,
         linked_libraries,
@@ -196,73 +450,103 @@ pub fn run(
         runner_config,
     )?;
 
+    let tests_from_files = compiled_tests
+        .iter()
+        .flat_map(|compiled_tests| &compiled_tests.tests_from_files);
     pretty_printing::print_collected_tests_count(
-        tests.iter().map(|tests| tests.test_cases.len()).sum(),
-        tests.len(),
+        tests_from_files
+            .clone()
+            .map(|tests_from_file| tests_from_file.test_cases.len())
+            .sum(),
+        tests_from_files.count(),
     );
 
-    let mut tests_iterator = tests.into_iter();
-
     let mut summaries = vec![];
-    for tests_from_file in tests_iterator.by_ref() {
-        let summary = run_tests_from_file(
-            tests_from_file,
-            runner_config,
-            contracts,
-            predeployed_contracts,
-        )?;
-        summaries.push(summary.clone());
-        if summary.runner_exit_status == RunnerStatus::TestFailed {
-            break;
+    let mut test_failed = false;
+    for compiled_tests in &compiled_tests {
+        if test_failed {
+            summaries.extend(
+                compiled_tests
+                    .tests_from_files
+                    .iter()
+                    .map(skip_tests_from_file),
+            );
+            continue;
         }
-    }
-
-    for tests_from_file in tests_iterator {
-        let skipped: Vec<TestCaseSummary> = tests_from_file
-            .test_cases
-            .iter()
-            .map(TestCaseSummary::skipped)
-            .collect();
 
-        for test_case_summary in &skipped {
-            pretty_printing::print_test_result(test_case_summary);
+        let mut program_runner = ProgramRunner::new(compiled_tests, contracts)?;
+        for tests_from_file in &compiled_tests.tests_from_files {
+            let summary = if test_failed {
+                skip_tests_from_file(tests_from_file)
+            } else {
+                run_tests_from_file(
+                    tests_from_file,
+                    &mut program_runner,
+                    runner_config,
+                    contracts,
+                    predeployed_contracts,
+                )?
+            };
+            test_failed |= summary.runner_exit_status == RunnerStatus::TestFailed;
+            summaries.push(summary);
         }
-
-        let file_summary = TestFileSummary {
-            test_case_summaries: skipped,
-            runner_exit_status: RunnerStatus::DidNotRun,
-            relative_path: tests_from_file.relative_path,
-        };
-        summaries.push(file_summary);
     }
 
     pretty_printing::print_test_summary(&summaries);
     Ok(summaries)
 }
 
+fn skip_tests_from_file(tests_from_file: &TestsFromFile) -> TestFileSummary {
+    let skipped: Vec<TestCaseSummary> = tests_from_file
+        .test_cases
+        .iter()
+        .map(TestCaseSummary::skipped)
+        .collect();
+
+    for test_case_summary in &skipped {
+        pretty_printing::print_test_result(test_case_summary);
+    }
+
+    TestFileSummary {
+        test_case_summaries: skipped,
+        runner_exit_status: RunnerStatus::DidNotRun,
+        relative_path: tests_from_file.relative_path.clone(),
+        coverage: None,
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct TestFileSummary {
     pub test_case_summaries: Vec<TestCaseSummary>,
     pub runner_exit_status: RunnerStatus,
     pub relative_path: Utf8PathBuf,
+    pub coverage: Option<Coverage>,
 }
 
 impl TestFileSummary {
-    fn count_passed(&self) -> usize {
+    #[must_use]
+    pub fn count_passed(&self) -> usize {
         self.test_case_summaries
             .iter()
             .filter(|tu| matches!(tu, TestCaseSummary::Passed { .. }))
             .count()
     }
 
-    fn count_failed(&self) -> usize {
+    #[must_use]
+    pub fn count_failed(&self) -> usize {
         self.test_case_summaries
             .iter()
-            .filter(|tu| matches!(tu, TestCaseSummary::Failed { .. }))
+            .filter(|tu| {
+                matches!(
+                    tu,
+                    TestCaseSummary::Failed { .. } | TestCaseSummary::Interrupted { .. }
+                )
+            })
             .count()
     }
 
-    fn count_skipped(&self) -> usize {
+    #[must_use]
+    pub fn count_skipped(&self) -> usize {
         self.test_case_summaries
             .iter()
             .filter(|tu| matches!(tu, TestCaseSummary::Skipped { .. }))
@@ -270,99 +554,175 @@ impl TestFileSummary {
     }
 }
 
+/// Runner of a compiled Sierra program, set up once for all of its test files
+struct ProgramRunner<'a> {
+    runner: SierraCasmRunner,
+    sierra_program: &'a Program,
+    function_locations: &'a FunctionLocations,
+    program_functions: ProgramFunctions,
+    sierra_statements: SierraStatements,
+    contract_entry_points: ContractEntryPoints<'a>,
+}
+
+impl<'a> ProgramRunner<'a> {
+    fn new(
+        compiled_tests: &'a CompiledTests,
+        contracts: &'a HashMap<String, StarknetContractArtifacts>,
+    ) -> Result<Self> {
+        let runner = SierraCasmRunner::new(
+            compiled_tests.sierra_program.clone(),
+            Some(MetadataComputationConfig::default()),
+            OrderedHashMap::default(),
+        )
+        .context("Failed setting up runner.")?;
+        let program_functions =
+            ProgramFunctions::new(&compiled_tests.sierra_program, runner.get_casm_program());
+        let sierra_statements = SierraStatements::new(runner.get_casm_program());
+
+        Ok(Self {
+            runner,
+            sierra_program: &compiled_tests.sierra_program,
+            function_locations: &compiled_tests.function_locations,
+            program_functions,
+            sierra_statements,
+            contract_entry_points: ContractEntryPoints::new(contracts),
+        })
+    }
+}
+
 fn run_tests_from_file(
-    tests: TestsFromFile,
+    tests: &TestsFromFile,
+    program_runner: &mut ProgramRunner,
     runner_config: &RunnerConfig,
     contracts: &HashMap<String, StarknetContractArtifacts>,
     predeployed_contracts: &Utf8PathBuf,
 ) -> Result<TestFileSummary> {
-    let runner = SierraCasmRunner::new(
-        tests.sierra_program,
-        Some(MetadataComputationConfig::default()),
-        OrderedHashMap::default(),
-    )
-    .context("Failed setting up runner.")?;
+    let runner = &program_runner.runner;
+    let program_functions = &program_runner.program_functions;
+    let sierra_statements = &program_runner.sierra_statements;
+    let contract_entry_points = &mut program_runner.contract_entry_points;
+
+    let mut coverage_collector = runner_config.coverage.then(|| {
+        CoverageCollector::new(
+            program_runner.function_locations,
+            program_runner.sierra_program,
+            runner.get_casm_program(),
+            contracts,
+        )
+    });
 
     pretty_printing::print_running_tests(&tests.relative_path, tests.test_cases.len());
     let mut results = vec![];
+    let mut runner_exit_status = RunnerStatus::Default;
     for (i, case) in tests.test_cases.iter().enumerate() {
-        let result = run_from_test_case(&runner, case, contracts, predeployed_contracts)?;
+        let (result, executed_code) = run_from_test_case(
+            runner,
+            case,
+            contracts,
+            predeployed_contracts,
+            runner_config,
+        )?;
+        if let (Some(collector), Some(executed_code)) = (&mut coverage_collector, &executed_code) {
+            collector.record(executed_code)?;
+        }
         results.push(result.clone());
 
         pretty_printing::print_test_result(&result);
+        if let (Some(profile_dir), Some(executed_code)) =
+            (&runner_config.profile_dir, &executed_code)
+        {
+            let profile = Profile::from_executed_code(
+                executed_code,
+                program_functions,
+                contract_entry_points,
+            )?;
+            let (folded_path, pprof_path) = profile.save(profile_dir, &case.name)?;
+            pretty_printing::print_profile_saved(&case.name, &folded_path, &pprof_path);
+        }
+        if let (Some(trace_dir), Some(executed_code)) = (&runner_config.trace_dir, &executed_code) {
+            let trace_dump = TraceDump::new(
+                &case.name,
+                executed_code,
+                program_functions,
+                sierra_statements,
+                contract_entry_points,
+            )?;
+            let trace_path = trace_dump.save(trace_dir)?;
+            pretty_printing::print_trace_saved(&case.name, &trace_path);
+        }
         if runner_config.exit_first {
-            if let TestCaseSummary::Failed { .. } = result {
+            if let TestCaseSummary::Failed { .. } | TestCaseSummary::Interrupted { .. } = result {
                 for case in &tests.test_cases[i + 1..] {
                     let skipped_result = TestCaseSummary::skipped(case);
                     pretty_printing::print_test_result(&skipped_result);
                     results.push(skipped_result);
                 }
-                return Ok(TestFileSummary {
-                    test_case_summaries: results,
-                    runner_exit_status: RunnerStatus::TestFailed,
-                    relative_path: tests.relative_path,
-                });
+                runner_exit_status = RunnerStatus::TestFailed;
+                break;
             }
         }
     }
     Ok(TestFileSummary {
         test_case_summaries: results,
-        runner_exit_status: RunnerStatus::Default,
-        relative_path: tests.relative_path,
+        runner_exit_status,
+        relative_path: tests.relative_path.clone(),
+        coverage: coverage_collector.map(CoverageCollector::finish),
     })
 }
 
+/// Name of the test without the path prefix added to the names of tests from `lib.cairo` crates
+fn test_name_without_path(name: &str) -> Option<&str> {
+    name.rsplit('/').next()
+}
+
 fn strip_path_from_test_names(test_cases: Vec<TestCase>) -> Result<Vec<TestCase>> {
     test_cases
         .into_iter()
         .map(|test_case| {
-            let name: String = test_case
-                .name
-                .rsplit('/')
-                .next()
+            let name: String = test_name_without_path(&test_case.name)
                 .with_context(|| format!("Failed to get test name from = {}", test_case.name))?
                 .into();
 
-            Ok(TestCase {
-                name,
-                available_gas: test_case.available_gas,
-            })
+            Ok(TestCase { name, ..test_case })
         })
         .collect()
 }
 
-fn filter_tests_by_name(
-    test_name_filter: &str,
-    exact_match: bool,
-    test_cases: Vec<TestCase>,
-) -> Result<Vec<TestCase>> {
+fn filter_tests_by_name(test_name: &str, test_cases: Vec<TestCase>) -> Result<Vec<TestCase>> {
     let mut result = vec![];
     for test in test_cases {
-        if exact_match {
-            if test.name == test_name_filter {
-                result.push(test);
-            }
-        } else if test_name_contains(test_name_filter, &test)? {
+        if test.name == test_name {
             result.push(test);
         }
     }
     Ok(result)
 }
 
-fn test_name_contains(test_name_filter: &str, test: &TestCase) -> Result<bool> {
-    let name = test
-        .name
-        .rsplit("::")
-        .next()
-        .context(format!("Failed to get test name from = {}", test.name))?;
-    Ok(name.contains(test_name_filter))
+fn filter_tests_to_rerun(
+    tests_to_rerun: &[String],
+    test_cases: Vec<TestCase>,
+) -> Result<Vec<TestCase>> {
+    let mut result = vec![];
+    for test_name in tests_to_rerun {
+        result.extend(filter_tests_by_name(test_name, test_cases.clone())?);
+    }
+    Ok(result)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_filter::FilterMode;
     use assert_fs::fixture::PathCopy;
 
+    fn substring_filter(filter: &str) -> TestFilter {
+        TestFilter::new(vec![filter.to_string()], vec![], FilterMode::Substring).unwrap()
+    }
+
+    fn exact_filter(filter: &str) -> TestFilter {
+        TestFilter::new(vec![filter.to_string()], vec![], FilterMode::Exact).unwrap()
+    }
+
     #[test]
     fn collecting_tests() {
         let temp = assert_fs::TempDir::new().unwrap();
@@ -376,6 +736,142 @@ mod tests {
         assert!(!tests.is_empty());
     }
 
+    #[test]
+    fn collecting_tests_crate_as_single_root() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.copy_from(
+            "tests/data/tests_crate_package",
+            &["**/*.cairo", "**/*.toml"],
+        )
+        .unwrap();
+        let package_path = Utf8PathBuf::from_path_buf(temp.to_path_buf()).unwrap();
+        let lib_path = package_path.join("src/lib.cairo");
+
+        let tests = find_cairo_root_files_in_directory(&package_path, &lib_path).unwrap();
+
+        assert_eq!(tests, vec![lib_path, package_path.join(TESTS_CRATE_ROOT)]);
+    }
+
+    #[test]
+    fn grouping_tests_by_module_file() {
+        let location = |file_path: &str| FunctionLocation {
+            file_path: file_path.to_string(),
+            start_line: 1,
+            end_line: 3,
+        };
+        let test_case = |name: &str| TestCase {
+            name: name.to_string(),
+            available_gas: None,
+            max_steps: None,
+            timeout: None,
+        };
+        let function_locations = HashMap::from([
+            (
+                "/package/tests::test_a::first".to_string(),
+                location("/package/tests/test_a.cairo"),
+            ),
+            (
+                "/package/tests::test_b::second".to_string(),
+                location("/package/tests/test_b.cairo"),
+            ),
+            (
+                "/package/tests::test_a::third".to_string(),
+                location("/package/tests/test_a.cairo"),
+            ),
+        ]);
+
+        let grouped = group_by_module_file(
+            vec![
+                test_case("tests::test_a::first"),
+                test_case("tests::test_b::second"),
+                test_case("tests::test_a::third"),
+                test_case("tests::unknown"),
+            ],
+            &function_locations,
+            Utf8Path::new("/package"),
+            Utf8Path::new(TESTS_CRATE_ROOT),
+        );
+
+        assert_eq!(
+            grouped,
+            vec![
+                (
+                    Utf8PathBuf::from("tests/lib.cairo"),
+                    vec![test_case("tests::unknown")]
+                ),
+                (
+                    Utf8PathBuf::from("tests/test_a.cairo"),
+                    vec![
+                        test_case("tests::test_a::first"),
+                        test_case("tests::test_a::third")
+                    ]
+                ),
+                (
+                    Utf8PathBuf::from("tests/test_b.cairo"),
+                    vec![test_case("tests::test_b::second")]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn collecting_tests_crate_grouped_by_module_files() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.copy_from(
+            "tests/data/tests_crate_package",
+            &["**/*.cairo", "**/*.toml"],
+        )
+        .unwrap();
+        let package_path = Utf8PathBuf::from_path_buf(temp.to_path_buf()).unwrap();
+        let lib_path = package_path.join("src/lib.cairo");
+        let linked_libraries = Some(vec![LinkedLibrary {
+            name: "tests_crate_package".to_string(),
+            path: package_path.join("src").into_std_path_buf(),
+        }]);
+        let corelib_path = Utf8PathBuf::from("../../../corelib/src");
+
+        let compiled_tests = collect_tests_from_directory(
+            &package_path,
+            &lib_path,
+            &linked_libraries,
+            Some(&corelib_path),
+            &RunnerConfig::default(),
+        )
+        .unwrap();
+
+        let tests_by_file: Vec<(Utf8PathBuf, Vec<String>)> = compiled_tests
+            .iter()
+            .flat_map(|compiled_tests| &compiled_tests.tests_from_files)
+            .map(|tests| {
+                (
+                    tests.relative_path.clone(),
+                    tests
+                        .test_cases
+                        .iter()
+                        .map(|test_case| test_case.name.clone())
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            tests_by_file,
+            vec![
+                (Utf8PathBuf::from("src/lib.cairo"), vec![]),
+                (
+                    Utf8PathBuf::from("tests/test_add.cairo"),
+                    vec!["tests::test_add::test_add".to_string()]
+                ),
+                (
+                    Utf8PathBuf::from("tests/test_mul.cairo"),
+                    vec![
+                        "tests::test_mul::test_mul".to_string(),
+                        "tests::test_mul::test_mul_by_zero".to_string()
+                    ]
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn collecting_tests_err_on_invalid_dir() {
         let tests_path = Utf8PathBuf::from("aaee");
@@ -393,96 +889,146 @@ mod tests {
             TestCase {
                 name: "crate1::do_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
             TestCase {
                 name: "crate2::run_other_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
             TestCase {
                 name: "outer::crate2::execute_next_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
         ];
 
-        let filtered = filter_tests_by_name("do", false, mocked_tests.clone()).unwrap();
+        let filtered = substring_filter("do").filter(mocked_tests.clone()).unwrap();
         assert_eq!(
             filtered,
             vec![TestCase {
                 name: "crate1::do_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },]
         );
 
-        let filtered = filter_tests_by_name("run", false, mocked_tests.clone()).unwrap();
+        let filtered = substring_filter("run")
+            .filter(mocked_tests.clone())
+            .unwrap();
         assert_eq!(
             filtered,
             vec![TestCase {
                 name: "crate2::run_other_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },]
         );
 
-        let filtered = filter_tests_by_name("thing", false, mocked_tests.clone()).unwrap();
+        let filtered = substring_filter("thing")
+            .filter(mocked_tests.clone())
+            .unwrap();
         assert_eq!(
             filtered,
             vec![
                 TestCase {
                     name: "crate1::do_thing".to_string(),
                     available_gas: None,
+                    max_steps: None,
+                    timeout: None,
                 },
                 TestCase {
                     name: "crate2::run_other_thing".to_string(),
                     available_gas: None,
+                    max_steps: None,
+                    timeout: None,
                 },
                 TestCase {
                     name: "outer::crate2::execute_next_thing".to_string(),
                     available_gas: None,
+                    max_steps: None,
+                    timeout: None,
                 },
             ]
         );
 
-        let filtered = filter_tests_by_name("nonexistent", false, mocked_tests.clone()).unwrap();
+        let filtered = substring_filter("nonexistent")
+            .filter(mocked_tests.clone())
+            .unwrap();
         assert_eq!(filtered, vec![]);
 
-        let filtered = filter_tests_by_name("", false, mocked_tests).unwrap();
+        let filtered = substring_filter("").filter(mocked_tests).unwrap();
         assert_eq!(
             filtered,
             vec![
                 TestCase {
                     name: "crate1::do_thing".to_string(),
                     available_gas: None,
+                    max_steps: None,
+                    timeout: None,
                 },
                 TestCase {
                     name: "crate2::run_other_thing".to_string(),
                     available_gas: None,
+                    max_steps: None,
+                    timeout: None,
                 },
                 TestCase {
                     name: "outer::crate2::execute_next_thing".to_string(),
                     available_gas: None,
+                    max_steps: None,
+                    timeout: None,
                 },
             ]
         );
     }
 
     #[test]
-    fn filtering_tests_only_uses_name() {
+    fn filtering_tests_uses_full_path() {
         let mocked_tests: Vec<TestCase> = vec![
             TestCase {
                 name: "crate1::do_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
             TestCase {
                 name: "crate2::run_other_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
             TestCase {
                 name: "outer::crate2::run_other_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
         ];
 
-        let filtered = filter_tests_by_name("crate", false, mocked_tests).unwrap();
-        assert_eq!(filtered, vec![]);
+        let filtered = substring_filter("crate2").filter(mocked_tests).unwrap();
+        assert_eq!(
+            filtered,
+            vec![
+                TestCase {
+                    name: "crate2::run_other_thing".to_string(),
+                    available_gas: None,
+                    max_steps: None,
+                    timeout: None,
+                },
+                TestCase {
+                    name: "outer::crate2::run_other_thing".to_string(),
+                    available_gas: None,
+                    max_steps: None,
+                    timeout: None,
+                },
+            ]
+        );
     }
 
     #[test]
@@ -491,93 +1037,178 @@ mod tests {
             TestCase {
                 name: "crate1::do_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
             TestCase {
                 name: "crate2::run_other_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
             TestCase {
                 name: "outer::crate3::run_other_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
             TestCase {
                 name: "do_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
         ];
 
-        let filtered = filter_tests_by_name("", true, mocked_tests.clone()).unwrap();
+        let filtered = exact_filter("").filter(mocked_tests.clone()).unwrap();
         assert_eq!(filtered, vec![]);
 
-        let filtered = filter_tests_by_name("thing", true, mocked_tests.clone()).unwrap();
+        let filtered = exact_filter("thing").filter(mocked_tests.clone()).unwrap();
         assert_eq!(filtered, vec![]);
 
-        let filtered = filter_tests_by_name("do_thing", true, mocked_tests.clone()).unwrap();
+        let filtered = exact_filter("do_thing")
+            .filter(mocked_tests.clone())
+            .unwrap();
         assert_eq!(
             filtered,
             vec![TestCase {
                 name: "do_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },]
         );
 
-        let filtered =
-            filter_tests_by_name("crate1::do_thing", true, mocked_tests.clone()).unwrap();
+        let filtered = exact_filter("crate1::do_thing")
+            .filter(mocked_tests.clone())
+            .unwrap();
         assert_eq!(
             filtered,
             vec![TestCase {
                 name: "crate1::do_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },]
         );
 
-        let filtered =
-            filter_tests_by_name("crate3::run_other_thing", true, mocked_tests.clone()).unwrap();
+        let filtered = exact_filter("crate3::run_other_thing")
+            .filter(mocked_tests.clone())
+            .unwrap();
         assert_eq!(filtered, vec![]);
 
-        let filtered =
-            filter_tests_by_name("outer::crate3::run_other_thing", true, mocked_tests).unwrap();
+        let filtered = exact_filter("outer::crate3::run_other_thing")
+            .filter(mocked_tests)
+            .unwrap();
         assert_eq!(
             filtered,
             vec![TestCase {
                 name: "outer::crate3::run_other_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },]
         );
     }
 
+    #[test]
+    fn filtering_tests_to_rerun() {
+        let mocked_tests: Vec<TestCase> = vec![
+            TestCase {
+                name: "crate1::do_thing".to_string(),
+                available_gas: None,
+                max_steps: None,
+                timeout: None,
+            },
+            TestCase {
+                name: "crate2::run_other_thing".to_string(),
+                available_gas: None,
+                max_steps: None,
+                timeout: None,
+            },
+            TestCase {
+                name: "outer::crate2::run_other_thing".to_string(),
+                available_gas: None,
+                max_steps: None,
+                timeout: None,
+            },
+        ];
+
+        let filtered = filter_tests_to_rerun(
+            &[
+                "outer::crate2::run_other_thing".to_string(),
+                "do_thing".to_string(),
+                "crate1::do_thing".to_string(),
+            ],
+            mocked_tests.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            filtered,
+            vec![
+                TestCase {
+                    name: "outer::crate2::run_other_thing".to_string(),
+                    available_gas: None,
+                    max_steps: None,
+                    timeout: None,
+                },
+                TestCase {
+                    name: "crate1::do_thing".to_string(),
+                    available_gas: None,
+                    max_steps: None,
+                    timeout: None,
+                },
+            ]
+        );
+
+        let filtered = filter_tests_to_rerun(&[], mocked_tests).unwrap();
+        assert_eq!(filtered, vec![]);
+    }
+
     #[test]
     fn filtering_tests_works_without_crate_in_test_name() {
         let mocked_tests: Vec<TestCase> = vec![
             TestCase {
                 name: "crate1::do_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
             TestCase {
                 name: "crate2::run_other_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
             TestCase {
                 name: "thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
         ];
 
-        let result = filter_tests_by_name("thing", false, mocked_tests).unwrap();
+        let result = substring_filter("thing").filter(mocked_tests).unwrap();
         assert_eq!(
             result,
             vec![
                 TestCase {
                     name: "crate1::do_thing".to_string(),
                     available_gas: None,
+                    max_steps: None,
+                    timeout: None,
                 },
                 TestCase {
                     name: "crate2::run_other_thing".to_string(),
                     available_gas: None,
+                    max_steps: None,
+                    timeout: None,
                 },
                 TestCase {
                     name: "thing".to_string(),
                     available_gas: None,
+                    max_steps: None,
+                    timeout: None,
                 },
             ]
         );
@@ -589,14 +1220,20 @@ mod tests {
             TestCase {
                 name: "/Users/user/forge/tests/data/simple_package/src::test::test_fib".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
             TestCase {
                 name: "crate2::run_other_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
             TestCase {
                 name: "src/crate2::run_other_thing".to_string(),
                 available_gas: None,
+                max_steps: None,
+                timeout: None,
             },
         ];
 
@@ -607,14 +1244,20 @@ mod tests {
                 TestCase {
                     name: "src::test::test_fib".to_string(),
                     available_gas: None,
+                    max_steps: None,
+                    timeout: None,
                 },
                 TestCase {
                     name: "crate2::run_other_thing".to_string(),
                     available_gas: None,
+                    max_steps: None,
+                    timeout: None,
                 },
                 TestCase {
                     name: "crate2::run_other_thing".to_string(),
                     available_gas: None,
+                    max_steps: None,
+                    timeout: None,
                 },
             ]
         );
@@ -1,32 +1,139 @@
 use anyhow::{anyhow, Context, Result};
+use cairo_lang_filesystem::cfg::Cfg;
 use camino::Utf8PathBuf;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use include_dir::{include_dir, Dir};
-use scarb_metadata::MetadataCommand;
+use scarb_metadata::{Metadata, MetadataCommand, PackageId};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
 use tempfile::{tempdir, TempDir};
 
-use forge::run;
+use forge::cfg_items::cfg_items_from_args;
+use forge::coverage::Coverage;
+use forge::last_failed::LastFailed;
+use forge::test_filter::{FilterMode, TestFilter};
+use forge::watch::{affected_tests, is_manifest, AffectedTests, WatchedFiles};
+use forge::{discover, list, run, PackageTest, TestFileSummary};
 use forge::{pretty_printing, RunnerConfig};
 
-use forge::scarb::{get_contracts_map, try_get_starknet_artifacts_path};
-use std::process::Command;
+use forge::scarb::{
+    build_packages, build_workspace, get_contracts_map_for_package, path_dependencies_roots,
+    select_packages, StarknetContractArtifacts, DEFAULT_PROFILE,
+};
 
 static CORELIB_PATH: Dir = include_dir!("../corelib/src");
 static PREDEPLOYED_CONTRACTS: Dir = include_dir!("crates/cheatable-starknet/predeployed-contracts");
 
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+const PROFILE_DIR: &str = "target/snforge/profile";
+const TRACE_DIR: &str = "target/snforge/trace";
+
+/// Output format of `--list`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Default)]
+enum ListFormat {
+    /// Full paths of the tests, one per line
+    #[default]
+    Human,
+    /// Tests with their packages, locations and attributes as a JSON array
+    Json,
+}
+
 #[derive(Parser, Debug)]
 #[command(version)]
 struct Args {
-    /// Name used to filter tests
-    test_name: Option<String>,
-    /// Use exact matches for `test_filter`
-    #[arg(short, long)]
+    /// Filters used to select tests, a test is run if its full path matches any of them
+    test_filters: Vec<String>,
+    /// Use exact matches for test filters and skip patterns
+    #[arg(short, long, conflicts_with_all = ["regex", "glob"])]
     exact: bool,
+    /// Treat test filters and skip patterns as regular expressions
+    #[arg(long, conflicts_with = "glob")]
+    regex: bool,
+    /// Treat test filters and skip patterns as glob patterns
+    #[arg(long)]
+    glob: bool,
+    /// Skip tests whose full path matches the pattern, can be passed multiple times
+    #[arg(long, value_name = "PATTERN")]
+    skip: Vec<String>,
+
+    /// Print names of the collected tests without running them
+    #[arg(long)]
+    list: bool,
+
+    /// Output format of `--list`
+    #[arg(long, value_enum, default_value_t, requires = "list")]
+    format: ListFormat,
 
     /// Stop test execution after first failed test
     #[arg(short = 'x', long)]
     exit_first: bool,
+
+    /// Maximum number of steps of a single test, overridden by the `#[max_steps]` attribute
+    #[arg(long, value_name = "N")]
+    max_steps: Option<usize>,
+
+    /// Timeout of a single test in seconds, overridden by the `#[timeout]` attribute
+    #[arg(long, value_name = "SECONDS")]
+    timeout: Option<u64>,
+
+    /// Charge fees for declarations, deployments and contract calls, and report the fee of every test
+    #[arg(long)]
+    charge_fees: bool,
+
+    /// Comma separated list of features enabled in the compiled tests, matched by `#[cfg(feature: 'name')]`
+    #[arg(short = 'F', long, value_delimiter = ',')]
+    features: Vec<String>,
+
+    /// Cfg item enabled in the compiled tests, given as `name` or `key=value`, can be passed multiple times
+    #[arg(long, value_name = "ITEM")]
+    cfg: Vec<String>,
+
+    /// Scarb profile used to build the contracts
+    #[arg(long, default_value = DEFAULT_PROFILE)]
+    profile: String,
+
+    /// Use the release profile, shorthand for `--profile release`
+    #[arg(long, conflicts_with = "profile")]
+    release: bool,
+
+    /// Name of the workspace package to run tests for
+    #[arg(short, long)]
+    package: Option<String>,
+
+    /// Run only tests that failed in the previous run
+    #[arg(long)]
+    rerun_failed: bool,
+
+    /// Run tests for all packages in the workspace
+    #[arg(short, long, conflicts_with = "package")]
+    workspace: bool,
+
+    /// Watch `src` and `tests` directories and manifests of the packages and their path dependencies and rerun affected tests on every change
+    #[arg(long, conflicts_with = "list")]
+    watch: bool,
+
+    /// Record which functions of the tested code and contracts are executed and save an LCOV report
+    #[arg(long, conflicts_with = "list")]
+    coverage: bool,
+
+    /// Run only the test with the given full path and save its profile as folded stacks and pprof files
+    #[arg(
+        long,
+        value_name = "TEST",
+        conflicts_with_all = ["test_filters", "exact", "regex", "glob", "list"]
+    )]
+    profiler: Option<String>,
+
+    /// Run only the test with the given full path and save its VM trace as JSON
+    #[arg(
+        long,
+        value_name = "TEST",
+        conflicts_with_all = ["test_filters", "exact", "regex", "glob", "list", "profiler"]
+    )]
+    trace: Option<String>,
 }
 
 fn load_corelib() -> Result<TempDir> {
@@ -45,8 +152,310 @@ fn load_predeployed_contracts() -> Result<TempDir> {
     Ok(tmp_dir)
 }
 
-fn main_execution() -> Result<()> {
+fn test_filter_from_args(args: &Args) -> Result<TestFilter> {
+    if let Some(test_name) = args.profiler.as_ref().or(args.trace.as_ref()) {
+        return TestFilter::new(
+            vec![test_name.clone()],
+            args.skip.clone(),
+            FilterMode::Exact,
+        );
+    }
+    let mode = if args.exact {
+        FilterMode::Exact
+    } else if args.regex {
+        FilterMode::Regex
+    } else if args.glob {
+        FilterMode::Glob
+    } else {
+        FilterMode::Substring
+    };
+    TestFilter::new(args.test_filters.clone(), args.skip.clone(), mode)
+}
+
+/// Paths and settings shared by all packages and watch iterations
+struct RunContext<'a> {
+    args: &'a Args,
+    test_filter: TestFilter,
+    cfg_items: Vec<Cfg>,
+    scarb_metadata: Metadata,
+    profile: &'a str,
+    corelib: Utf8PathBuf,
+    predeployed_contracts: Utf8PathBuf,
+}
+
+type PackageSummaries = Vec<(String, Vec<TestFileSummary>)>;
+
+/// Contracts available for each package, kept between watch iterations until contracts are rebuilt
+type ContractsMaps = HashMap<PackageId, HashMap<String, StarknetContractArtifacts>>;
+
+fn selected_packages(context: &RunContext) -> Result<Vec<PackageId>> {
+    select_packages(
+        &context.scarb_metadata,
+        context.args.package.as_deref(),
+        context.args.workspace,
+    )
+}
+
+fn run_packages(
+    context: &RunContext,
+    packages: &[PackageId],
+    last_failed: &mut LastFailed,
+    changed_files: Option<&[Utf8PathBuf]>,
+    contracts_maps: &mut ContractsMaps,
+) -> Result<PackageSummaries> {
+    let scarb_metadata = &context.scarb_metadata;
+    let mut package_summaries = vec![];
+    let mut discovered_tests: Vec<PackageTest> = vec![];
+
+    for package in packages {
+        let package_name = scarb_metadata
+            .get_package(package)
+            .ok_or_else(|| anyhow!("Failed to find metadata for package = {package}"))?
+            .name
+            .clone();
+
+        let forge_config = forge::scarb::config_from_scarb_for_package(scarb_metadata, package)?;
+
+        let (package_path, lib_path, _corelib_path, dependencies, _target_name) =
+            forge::scarb::dependencies_for_package(scarb_metadata, package)?;
+        let tests_to_rerun = if context.args.rerun_failed {
+            last_failed.tests_for_package(&package_name).cloned()
+        } else {
+            None
+        };
+        let runner_config = RunnerConfig::new(
+            context.test_filter.clone(),
+            context.args.exit_first,
+            tests_to_rerun,
+            context.args.max_steps,
+            context.args.timeout,
+            &forge_config,
+        )
+        .with_coverage(context.args.coverage)
+        .with_charge_fees(context.args.charge_fees)
+        .with_cfg_items(context.cfg_items.clone())
+        .with_profile_dir(
+            context
+                .args
+                .profiler
+                .as_ref()
+                .map(|_| scarb_metadata.workspace.root.join(PROFILE_DIR)),
+        )
+        .with_trace_dir(
+            context
+                .args
+                .trace
+                .as_ref()
+                .map(|_| scarb_metadata.workspace.root.join(TRACE_DIR)),
+        );
+        let runner_config = match changed_files.map(|changed_files| {
+            affected_tests(
+                &package_path,
+                &lib_path,
+                &path_dependencies_roots(scarb_metadata, package),
+                changed_files,
+            )
+        }) {
+            None | Some(AffectedTests::All) => runner_config,
+            Some(AffectedTests::Files(test_files)) => runner_config.with_test_files(test_files),
+            Some(AffectedTests::None) => continue,
+        };
+
+        if packages.len() > 1 && context.args.format == ListFormat::Human {
+            pretty_printing::print_running_package(&package_name);
+        }
+
+        if context.args.list {
+            match context.args.format {
+                ListFormat::Human => list(
+                    &package_path,
+                    &lib_path,
+                    &Some(dependencies),
+                    &runner_config,
+                    Some(&context.corelib),
+                )?,
+                ListFormat::Json => discovered_tests.extend(discover(
+                    &package_name,
+                    &package_path,
+                    &lib_path,
+                    &Some(dependencies),
+                    &runner_config,
+                    Some(&context.corelib),
+                )?),
+            }
+            continue;
+        }
+
+        let contracts = match contracts_maps.entry(package.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(get_contracts_map_for_package(
+                scarb_metadata,
+                package,
+                context.profile,
+            )?),
+        };
+
+        let summaries = run(
+            &package_path,
+            &lib_path,
+            &Some(dependencies.clone()),
+            &runner_config,
+            Some(&context.corelib),
+            contracts,
+            &context.predeployed_contracts,
+        )?;
+        last_failed.update(&package_name, &summaries);
+        package_summaries.push((package_name, summaries));
+    }
+
+    if context.args.format == ListFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&discovered_tests)?);
+    }
+
+    last_failed.save(&scarb_metadata.workspace.root)?;
+
+    if package_summaries.len() > 1 {
+        pretty_printing::print_packages_summary(&package_summaries);
+    }
+    if context.args.coverage {
+        save_coverage(&scarb_metadata.workspace.root, &package_summaries)?;
+    }
+    Ok(package_summaries)
+}
+
+fn save_coverage(workspace_root: &Utf8PathBuf, package_summaries: &PackageSummaries) -> Result<()> {
+    let mut coverage = Coverage::default();
+    for file_coverage in package_summaries
+        .iter()
+        .flat_map(|(_, summaries)| summaries)
+        .filter_map(|summary| summary.coverage.as_ref())
+    {
+        coverage.merge(file_coverage);
+    }
+    let report_path = coverage.save(workspace_root)?;
+    pretty_printing::print_coverage_report_saved(&report_path);
+    Ok(())
+}
+
+fn all_tests_passed(package_summaries: &PackageSummaries) -> bool {
+    package_summaries
+        .iter()
+        .flat_map(|(_, summaries)| summaries)
+        .all(|summary| summary.count_failed() == 0)
+}
+
+/// `src` and `tests` directories and manifests of the packages and their path dependencies,
+/// and the workspace manifest
+fn workspace_watched_paths(
+    metadata: &Metadata,
+    packages: &[PackageId],
+) -> Result<Vec<Utf8PathBuf>> {
+    let mut watched_paths = vec![metadata.workspace.manifest_path.clone()];
+    for package in packages {
+        let package_root = &metadata
+            .get_package(package)
+            .ok_or_else(|| anyhow!("Failed to find metadata for package = {package}"))?
+            .root;
+        watched_paths.extend(forge::watch::watched_paths(package_root));
+        for dependency_root in path_dependencies_roots(metadata, package) {
+            watched_paths.extend(forge::watch::watched_paths(&dependency_root));
+        }
+    }
+    watched_paths.sort();
+    watched_paths.dedup();
+    Ok(watched_paths)
+}
+
+/// Reruns tests affected by changes in `src` and `tests` directories of the packages
+/// and their path dependencies
+///
+/// Only the packages whose sources changed are rebuilt and the contracts are read again only
+/// after a rebuild. Changes in manifests reload the Scarb metadata and rerun all tests.
+fn watch(context: &mut RunContext, last_failed: &mut LastFailed) -> Result<()> {
+    let mut packages = selected_packages(context)?;
+    let mut watched_paths = workspace_watched_paths(&context.scarb_metadata, &packages)?;
+    let mut watched_files = WatchedFiles::snapshot(&watched_paths)?;
+    let mut changed_files = None;
+    let mut contracts_maps = ContractsMaps::new();
+
+    for iteration in 1.. {
+        let result = build_packages(
+            &context.scarb_metadata,
+            &context.scarb_metadata.workspace.members,
+            context.profile,
+        )
+        .and_then(|built_packages| {
+            // Contracts of every workspace member are available to each package
+            if !built_packages.is_empty() {
+                contracts_maps.clear();
+            }
+            run_packages(
+                context,
+                &packages,
+                last_failed,
+                changed_files.as_deref(),
+                &mut contracts_maps,
+            )
+        });
+        match result {
+            Ok(package_summaries) => {
+                let summaries: Vec<TestFileSummary> = package_summaries
+                    .into_iter()
+                    .flat_map(|(_, summaries)| summaries)
+                    .collect();
+                pretty_printing::print_watch_iteration_summary(iteration, &summaries);
+            }
+            Err(error) => pretty_printing::print_error_message(&error),
+        }
+
+        pretty_printing::print_watching_for_changes();
+        let new_changed_files = wait_for_changes(&watched_paths, &mut watched_files)?;
+        changed_files = if new_changed_files.iter().any(is_manifest) {
+            match reload_metadata(context) {
+                Ok(()) => {
+                    packages = selected_packages(context)?;
+                    watched_paths = workspace_watched_paths(&context.scarb_metadata, &packages)?;
+                    watched_files = WatchedFiles::snapshot(&watched_paths)?;
+                }
+                Err(error) => pretty_printing::print_error_message(&error),
+            }
+            contracts_maps.clear();
+            None
+        } else {
+            Some(new_changed_files)
+        };
+    }
+    Ok(())
+}
+
+fn reload_metadata(context: &mut RunContext) -> Result<()> {
+    context.scarb_metadata = MetadataCommand::new()
+        .manifest_path(&context.scarb_metadata.workspace.manifest_path)
+        .inherit_stderr()
+        .exec()?;
+    Ok(())
+}
+
+fn wait_for_changes(
+    watched_paths: &[Utf8PathBuf],
+    watched_files: &mut WatchedFiles,
+) -> Result<Vec<Utf8PathBuf>> {
+    loop {
+        thread::sleep(WATCH_INTERVAL);
+
+        let new_watched_files = WatchedFiles::snapshot(watched_paths)?;
+        let changed_files = watched_files.changed_files(&new_watched_files);
+        if !changed_files.is_empty() {
+            *watched_files = new_watched_files;
+            return Ok(changed_files);
+        }
+    }
+}
+
+fn main_execution() -> Result<bool> {
     let args = Args::parse();
+    let test_filter = test_filter_from_args(&args)?;
+    let cfg_items = cfg_items_from_args(&args.cfg, &args.features)?;
 
     // TODO #1997
     let corelib_dir = load_corelib()?;
@@ -62,41 +471,39 @@ fn main_execution() -> Result<()> {
     which::which("scarb")
         .context("Cannot find `scarb` binary in PATH. Make sure you have Scarb installed https://github.com/software-mansion/scarb")?;
 
-    let scarb_metadata = MetadataCommand::new().inherit_stderr().exec()?;
-    Command::new("scarb")
-        .current_dir(std::env::current_dir().context("Failed to get current directory")?)
-        .arg("build")
-        .output()
-        .context("Failed to build contracts with Scarb")?;
-
-    for package in &scarb_metadata.workspace.members {
-        let forge_config = forge::scarb::config_from_scarb_for_package(&scarb_metadata, package)?;
+    let profile = if args.release {
+        "release"
+    } else {
+        args.profile.as_str()
+    };
 
-        let (package_path, lib_path, _corelib_path, dependencies, target_name) =
-            forge::scarb::dependencies_for_package(&scarb_metadata, package)?;
-        let runner_config = RunnerConfig::new(
-            args.test_name.clone(),
-            args.exact,
-            args.exit_first,
-            &forge_config,
-        );
+    let scarb_metadata = MetadataCommand::new().inherit_stderr().exec()?;
 
-        let contracts_path = try_get_starknet_artifacts_path(&package_path, &target_name)?;
-        let contracts = contracts_path
-            .map(|path| get_contracts_map(&path))
-            .transpose()?
-            .unwrap_or_default();
+    let mut last_failed = LastFailed::load(&scarb_metadata.workspace.root)?;
+    let mut context = RunContext {
+        args: &args,
+        test_filter,
+        cfg_items,
+        scarb_metadata,
+        profile,
+        corelib,
+        predeployed_contracts,
+    };
 
-        run(
-            &package_path,
-            &lib_path,
-            &Some(dependencies.clone()),
-            &runner_config,
-            Some(&corelib),
-            &contracts,
-            &predeployed_contracts,
-        )?;
-    }
+    let package_summaries = if args.watch {
+        watch(&mut context, &mut last_failed)?;
+        vec![]
+    } else {
+        let packages = selected_packages(&context)?;
+        build_workspace(&context.scarb_metadata, profile)?;
+        run_packages(
+            &context,
+            &packages,
+            &mut last_failed,
+            None,
+            &mut ContractsMaps::new(),
+        )?
+    };
 
     // Explicitly close the temporary directories so we can handle the errors
     predeployed_contracts_dir.close().with_context(|| {
@@ -106,12 +513,13 @@ fn main_execution() -> Result<()> {
         )
     })?;
 
-    Ok(())
+    Ok(all_tests_passed(&package_summaries))
 }
 
 fn main() {
     match main_execution() {
-        Ok(()) => std::process::exit(0),
+        Ok(true) => std::process::exit(0),
+        Ok(false) => std::process::exit(1),
         Err(error) => {
             pretty_printing::print_error_message(&error);
             std::process::exit(1);
@@ -3,26 +3,53 @@ use crate::TestFileSummary;
 use anyhow::Error;
 use camino::Utf8PathBuf;
 use console::style;
+use test_collector::diagnostics::{CompilationError, CompilerDiagnostic, Severity};
 
 pub fn print_error_message(error: &Error) {
+    if let Some(compilation_error) = error.downcast_ref::<CompilationError>() {
+        for diagnostic in &compilation_error.diagnostics {
+            print_compiler_diagnostic(diagnostic);
+        }
+    }
     let error_tag = style("ERROR").red();
     println!("[{error_tag}] {error}");
 }
 
+fn print_compiler_diagnostic(diagnostic: &CompilerDiagnostic) {
+    let severity = match diagnostic.severity {
+        Severity::Error => style(diagnostic.severity).red(),
+        Severity::Warning => style(diagnostic.severity).yellow(),
+    };
+    println!("{severity}: {}", diagnostic.message);
+    if let Some(file_path) = &diagnostic.file_path {
+        match &diagnostic.span {
+            Some(span) => println!(" --> {file_path}:{}:{}", span.start.line, span.start.column),
+            None => println!(" --> {file_path}"),
+        }
+    }
+}
+
 pub fn print_collected_tests_count(tests_num: usize, tests_files_num: usize) {
     let plain_text = format!("Collected {tests_num} test(s) and {tests_files_num} test file(s)");
     println!("{}", style(plain_text).bold());
 }
 
+pub fn print_test_name(test_name: &str) {
+    println!("{test_name}");
+}
+
 pub fn print_running_tests(test_file: &Utf8PathBuf, tests_num: usize) {
     let plain_text = format!("Running {tests_num} test(s) from {test_file}");
     println!("{}", style(plain_text).bold());
 }
 
+pub fn print_running_package(package_name: &str) {
+    let plain_text = format!("Testing package {package_name}");
+    println!("{}", style(plain_text).bold());
+}
+
 pub fn print_test_summary(summaries: &[TestFileSummary]) {
-    let passed: usize = summaries.iter().map(TestFileSummary::count_passed).sum();
-    let skipped: usize = summaries.iter().map(TestFileSummary::count_skipped).sum();
-    let failed: usize = summaries.iter().map(TestFileSummary::count_failed).sum();
+    let (passed, failed, skipped) = count_results(summaries);
 
     println!(
         "{}: {} passed, {} failed, {} skipped",
@@ -33,24 +60,90 @@ pub fn print_test_summary(summaries: &[TestFileSummary]) {
     );
 }
 
+pub fn print_packages_summary(package_summaries: &[(String, Vec<TestFileSummary>)]) {
+    println!("{}", style("Tests summary for all packages").bold());
+    for (package_name, summaries) in package_summaries {
+        let (passed, failed, skipped) = count_results(summaries);
+        println!("    {package_name}: {passed} passed, {failed} failed, {skipped} skipped");
+    }
+
+    let summaries: Vec<TestFileSummary> = package_summaries
+        .iter()
+        .flat_map(|(_, summaries)| summaries.clone())
+        .collect();
+    print_test_summary(&summaries);
+}
+
+pub fn print_coverage_report_saved(report_path: &Utf8PathBuf) {
+    println!("{} {report_path}", style("Coverage report saved to").bold());
+}
+
+pub fn print_profile_saved(test_name: &str, folded_path: &Utf8PathBuf, pprof_path: &Utf8PathBuf) {
+    println!(
+        "{} {test_name} saved to {folded_path} and {pprof_path}",
+        style("Profile of").bold()
+    );
+}
+
+pub fn print_trace_saved(test_name: &str, trace_path: &Utf8PathBuf) {
+    println!(
+        "{} {test_name} saved to {trace_path}",
+        style("Trace of").bold()
+    );
+}
+
+pub fn print_watching_for_changes() {
+    println!("{}", style("Watching for changes...").dim());
+}
+
+pub fn print_watch_iteration_summary(iteration: usize, summaries: &[TestFileSummary]) {
+    let (passed, failed, skipped) = count_results(summaries);
+    let status = if failed == 0 {
+        style("OK").green()
+    } else {
+        style("FAILED").red()
+    };
+
+    println!(
+        "[{status}] {} #{iteration}: {passed} passed, {failed} failed, {skipped} skipped",
+        style("Run").bold()
+    );
+}
+
+fn count_results(summaries: &[TestFileSummary]) -> (usize, usize, usize) {
+    let passed: usize = summaries.iter().map(TestFileSummary::count_passed).sum();
+    let failed: usize = summaries.iter().map(TestFileSummary::count_failed).sum();
+    let skipped: usize = summaries.iter().map(TestFileSummary::count_skipped).sum();
+    (passed, failed, skipped)
+}
+
 pub fn print_test_result(test_result: &TestCaseSummary) {
     let result_header = match test_result {
         TestCaseSummary::Passed { .. } => format!("[{}]", style("PASS").green()),
         TestCaseSummary::Failed { .. } => format!("[{}]", style("FAIL").red()),
         TestCaseSummary::Skipped { .. } => format!("[{}]", style("SKIP").yellow()),
+        TestCaseSummary::Interrupted { .. } => format!("[{}]", style("INTERRUPTED").red()),
     };
 
     let result_name = match test_result {
         TestCaseSummary::Skipped { name }
         | TestCaseSummary::Failed { name, .. }
-        | TestCaseSummary::Passed { name, .. } => name,
+        | TestCaseSummary::Passed { name, .. }
+        | TestCaseSummary::Interrupted { name, .. } => name,
+    };
+
+    let result_fee = match test_result {
+        TestCaseSummary::Passed { fee: Some(fee), .. }
+        | TestCaseSummary::Failed { fee: Some(fee), .. } => format!(" (fee: {fee})"),
+        _ => String::new(),
     };
 
     let result_message = match test_result {
         TestCaseSummary::Passed { msg: Some(msg), .. } => format!("\n\nSuccess data:{msg}"),
         TestCaseSummary::Failed { msg: Some(msg), .. } => format!("\n\nFailure data:{msg}"),
+        TestCaseSummary::Interrupted { reason, .. } => format!("\n\nTest {reason}\n"),
         _ => String::new(),
     };
 
-    println!("{result_header} {result_name}{result_message}");
+    println!("{result_header} {result_name}{result_fee}{result_message}");
 }
@@ -0,0 +1,314 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::fs;
+
+use anyhow::{Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::execution_trace::{ContractCall, ContractEntryPoints, ExecutedCode, ProgramFunctions};
+
+/// Number of steps executed with the given call stacks, stacks start with the outermost function
+#[derive(Debug, PartialEq, Default)]
+pub(crate) struct Profile(BTreeMap<Vec<String>, usize>);
+
+impl Profile {
+    /// Reconstructs call stacks of the test code from the frame pointers in the trace,
+    /// steps of the contract calls are attributed to their entry points on top of the calling stack
+    pub fn from_executed_code(
+        executed_code: &ExecutedCode,
+        program_functions: &ProgramFunctions,
+        contract_entry_points: &mut ContractEntryPoints,
+    ) -> Result<Self> {
+        let mut profile = Profile::default();
+        let mut frames: Vec<(usize, String)> = vec![];
+        let mut contract_calls = executed_code.contract_calls.iter().peekable();
+
+        for (step, entry) in executed_code.trace.iter().enumerate() {
+            let Some(function_name) = program_functions.function_containing(entry.pc) else {
+                continue;
+            };
+            // Frame pointer grows with every call and is restored on return
+            while frames.last().map_or(false, |(fp, _)| *fp > entry.fp) {
+                frames.pop();
+            }
+            if frames.last().map_or(true, |(fp, _)| *fp < entry.fp) {
+                frames.push((entry.fp, function_name.to_string()));
+            }
+
+            let stack: Vec<String> = frames.iter().map(|(_, name)| name.clone()).collect();
+            profile.add_steps(stack.clone(), 1);
+
+            while let Some((_, call)) = contract_calls.next_if(|(call_step, _)| *call_step <= step)
+            {
+                profile.add_contract_call(&stack, call, contract_entry_points)?;
+            }
+        }
+        Ok(profile)
+    }
+
+    fn add_steps(&mut self, stack: Vec<String>, steps: usize) {
+        if steps > 0 {
+            *self.0.entry(stack).or_default() += steps;
+        }
+    }
+
+    fn add_contract_call(
+        &mut self,
+        caller_stack: &[String],
+        call: &ContractCall,
+        contract_entry_points: &mut ContractEntryPoints,
+    ) -> Result<()> {
+        let selector = format!("0x{}", call.entry_point_selector.to_str_radix(16));
+        let frame = match &call.contract_name {
+            Some(contract_name) => contract_entry_points
+                .function_name(contract_name, &call.entry_point_selector)?
+                .map_or_else(|| format!("{contract_name}::{selector}"), String::from),
+            None => selector,
+        };
+        let mut stack = caller_stack.to_vec();
+        stack.push(frame);
+
+        let inner_steps: usize = call.inner_calls.iter().map(|inner| inner.steps).sum();
+        self.add_steps(stack.clone(), call.steps.saturating_sub(inner_steps));
+        for inner_call in &call.inner_calls {
+            self.add_contract_call(&stack, inner_call, contract_entry_points)?;
+        }
+        Ok(())
+    }
+
+    /// Renders the profile in the folded stacks format used by flamegraph tools
+    pub fn to_folded(&self) -> String {
+        let mut folded = String::new();
+        for (stack, steps) in &self.0 {
+            writeln!(folded, "{} {steps}", stack.join(";")).unwrap();
+        }
+        folded
+    }
+
+    /// Encodes the profile as an uncompressed pprof protobuf message with a single `steps` sample type
+    pub fn to_pprof(&self) -> Vec<u8> {
+        let mut string_table = vec![String::new(), "steps".to_string(), "count".to_string()];
+        let mut function_ids: HashMap<&str, u64> = HashMap::new();
+        for name in self.0.keys().flatten() {
+            if !function_ids.contains_key(name.as_str()) {
+                let id = function_ids.len() as u64 + 1;
+                function_ids.insert(name.as_str(), id);
+                string_table.push(name.clone());
+            }
+        }
+
+        let mut profile = vec![];
+        let mut value_type = vec![];
+        pprof::write_uint(&mut value_type, 1, 1);
+        pprof::write_uint(&mut value_type, 2, 2);
+        pprof::write_bytes(&mut profile, 1, &value_type);
+
+        for (stack, steps) in &self.0 {
+            // Locations of a sample start with the innermost function
+            let location_ids: Vec<u64> = stack
+                .iter()
+                .rev()
+                .map(|name| function_ids[name.as_str()])
+                .collect();
+            let mut sample = vec![];
+            pprof::write_packed(&mut sample, 1, &location_ids);
+            pprof::write_packed(&mut sample, 2, &[*steps as u64]);
+            pprof::write_bytes(&mut profile, 2, &sample);
+        }
+
+        let mut functions: Vec<(&str, u64)> = function_ids.into_iter().collect();
+        functions.sort_by_key(|(_, id)| *id);
+        for (_, id) in &functions {
+            // Every function has a location with the same id
+            let mut line = vec![];
+            pprof::write_uint(&mut line, 1, *id);
+            let mut location = vec![];
+            pprof::write_uint(&mut location, 1, *id);
+            pprof::write_bytes(&mut location, 4, &line);
+            pprof::write_bytes(&mut profile, 4, &location);
+        }
+        for (_, id) in &functions {
+            // Function names are stored in the string table right after the sample type strings
+            let mut function = vec![];
+            pprof::write_uint(&mut function, 1, *id);
+            pprof::write_uint(&mut function, 2, *id + 2);
+            pprof::write_uint(&mut function, 3, *id + 2);
+            pprof::write_bytes(&mut profile, 5, &function);
+        }
+        for string in &string_table {
+            pprof::write_bytes(&mut profile, 6, string.as_bytes());
+        }
+        profile
+    }
+
+    /// Writes the folded stacks and pprof files of the test to the directory, returns their paths
+    pub fn save(&self, dir: &Utf8Path, test_name: &str) -> Result<(Utf8PathBuf, Utf8PathBuf)> {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create directory = {dir}"))?;
+        let file_name = test_name.replace("::", "__");
+
+        let folded_path = dir.join(format!("{file_name}.folded"));
+        fs::write(&folded_path, self.to_folded())
+            .with_context(|| format!("Failed to write to {folded_path:?}"))?;
+        let pprof_path = dir.join(format!("{file_name}.pb"));
+        fs::write(&pprof_path, self.to_pprof())
+            .with_context(|| format!("Failed to write to {pprof_path:?}"))?;
+
+        Ok((folded_path, pprof_path))
+    }
+}
+
+/// Minimal protobuf encoding of the pprof `Profile` message
+mod pprof {
+    const VARINT: u64 = 0;
+    const LENGTH_DELIMITED: u64 = 2;
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn write_varint(buffer: &mut Vec<u8>, mut value: u64) {
+        while value >= 0x80 {
+            buffer.push((value & 0x7f) as u8 | 0x80);
+            value >>= 7;
+        }
+        buffer.push(value as u8);
+    }
+
+    pub fn write_uint(buffer: &mut Vec<u8>, field: u64, value: u64) {
+        write_varint(buffer, (field << 3) | VARINT);
+        write_varint(buffer, value);
+    }
+
+    pub fn write_bytes(buffer: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+        write_varint(buffer, (field << 3) | LENGTH_DELIMITED);
+        write_varint(buffer, bytes.len() as u64);
+        buffer.extend_from_slice(bytes);
+    }
+
+    pub fn write_packed(buffer: &mut Vec<u8>, field: u64, values: &[u64]) {
+        let mut packed = vec![];
+        for value in values {
+            write_varint(&mut packed, *value);
+        }
+        write_bytes(buffer, field, &packed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution_trace::TraceEntry;
+    use cairo_felt::Felt252;
+
+    fn program_functions() -> ProgramFunctions {
+        ProgramFunctions::from_entry_pcs(
+            vec![(0, "test".to_string()), (10, "helper".to_string())],
+            20,
+        )
+    }
+
+    fn entry(pc: usize, fp: usize) -> TraceEntry {
+        TraceEntry { pc, ap: fp, fp }
+    }
+
+    fn stack(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn reconstructs_call_stacks() {
+        let executed_code = ExecutedCode {
+            trace: vec![
+                entry(0, 100),
+                entry(1, 100),
+                entry(10, 105),
+                entry(11, 105),
+                entry(10, 110),
+                entry(12, 105),
+                entry(2, 100),
+                entry(25, 100),
+            ],
+            contract_calls: vec![],
+            hints: HashMap::new(),
+        };
+        let contracts = HashMap::new();
+
+        let profile = Profile::from_executed_code(
+            &executed_code,
+            &program_functions(),
+            &mut ContractEntryPoints::new(&contracts),
+        )
+        .unwrap();
+
+        assert_eq!(
+            profile.0,
+            BTreeMap::from([
+                (stack(&["test"]), 3),
+                (stack(&["test", "helper"]), 3),
+                (stack(&["test", "helper", "helper"]), 1),
+            ])
+        );
+    }
+
+    #[test]
+    fn attributes_contract_calls_to_calling_stack() {
+        let executed_code = ExecutedCode {
+            trace: vec![entry(0, 100), entry(10, 105), entry(0, 100)],
+            contract_calls: vec![(
+                1,
+                ContractCall {
+                    contract_name: None,
+                    entry_point_selector: Felt252::from(0x1234),
+                    steps: 50,
+                    inner_calls: vec![ContractCall {
+                        contract_name: None,
+                        entry_point_selector: Felt252::from(0xabcd),
+                        steps: 20,
+                        inner_calls: vec![],
+                    }],
+                },
+            )],
+            hints: HashMap::new(),
+        };
+        let contracts = HashMap::new();
+
+        let profile = Profile::from_executed_code(
+            &executed_code,
+            &program_functions(),
+            &mut ContractEntryPoints::new(&contracts),
+        )
+        .unwrap();
+
+        assert_eq!(
+            profile.to_folded(),
+            "test 2\ntest;helper 1\ntest;helper;0x1234 30\ntest;helper;0x1234;0xabcd 20\n"
+        );
+    }
+
+    #[test]
+    fn encodes_varints() {
+        let mut buffer = vec![];
+        pprof::write_varint(&mut buffer, 1);
+        pprof::write_varint(&mut buffer, 300);
+        assert_eq!(buffer, vec![0x01, 0xac, 0x02]);
+    }
+
+    #[test]
+    fn encodes_pprof() {
+        let profile = Profile(BTreeMap::from([(stack(&["a"]), 2)]));
+
+        assert_eq!(
+            profile.to_pprof(),
+            vec![
+                // sample_type { type: "steps", unit: "count" }
+                0x0a, 0x04, 0x08, 0x01, 0x10, 0x02,
+                // sample { location_id: [1], value: [2] }
+                0x12, 0x06, 0x0a, 0x01, 0x01, 0x12, 0x01, 0x02,
+                // location { id: 1, line { function_id: 1 } }
+                0x22, 0x06, 0x08, 0x01, 0x22, 0x02, 0x08, 0x01,
+                // function { id: 1, name: 3, system_name: 3 }
+                0x2a, 0x06, 0x08, 0x01, 0x10, 0x03, 0x18, 0x03,
+                // string_table: ["", "steps", "count", "a"]
+                0x32, 0x00, 0x32, 0x05, b's', b't', b'e', b'p', b's', 0x32, 0x05, b'c', b'o', b'u',
+                b'n', b't', 0x32, 0x01, b'a',
+            ]
+        );
+    }
+}
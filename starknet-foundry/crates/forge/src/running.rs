@@ -1,6 +1,7 @@
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use cairo_vm::serde::deserialize_program::HintParams;
 use cheatable_starknet::constants::build_testing_state;
 use itertools::chain;
@@ -11,12 +12,15 @@ use cairo_lang_runner::casm_run::hint_to_hint_params;
 use cairo_lang_runner::{CairoHintProcessor as CoreCairoHintProcessor, RunnerError};
 use cairo_lang_runner::{SierraCasmRunner, StarknetState};
 use cairo_vm::vm::runners::cairo_runner::RunResources;
+use cairo_vm::vm::vm_core::VirtualMachine;
 use camino::Utf8PathBuf;
 use test_collector::TestCase;
 
-use crate::cheatcodes_hint_processor::CairoHintProcessor;
+use crate::cheatcodes_hint_processor::{CairoHintProcessor, ContractsExecution};
+use crate::execution_trace::{ContractCall, ExecutedCode, TraceEntry};
 use crate::scarb::StarknetContractArtifacts;
-use crate::test_case_summary::TestCaseSummary;
+use crate::test_case_summary::{InterruptReason, TestCaseSummary};
+use crate::RunnerConfig;
 
 /// Builds `hints_dict` required in `cairo_vm::types::program::Program` from instructions.
 fn build_hints_dict<'b>(
@@ -44,12 +48,77 @@ fn build_hints_dict<'b>(
     (hints_dict, string_to_hint)
 }
 
+/// Codes of the hints of the Sierra program code, keyed by program counters relative to its beginning
+fn hints_by_pc(
+    hints_dict: &HashMap<usize, Vec<HintParams>>,
+    entry_code_size: usize,
+) -> HashMap<usize, Vec<String>> {
+    hints_dict
+        .iter()
+        .filter_map(|(offset, hints)| {
+            let pc = offset.checked_sub(entry_code_size)?;
+            Some((pc, hints.iter().map(|hint| hint.code.clone()).collect()))
+        })
+        .collect()
+}
+
+/// Builds executed code from the VM trace, skipping the steps of the entry code
+fn executed_code(
+    vm: &mut VirtualMachine,
+    entry_code_size: usize,
+    contract_calls: Vec<(usize, ContractCall)>,
+    hints: HashMap<usize, Vec<String>>,
+) -> Result<ExecutedCode> {
+    vm.segments.compute_effective_sizes();
+    let relocation_table = vm
+        .segments
+        .relocate_segments()
+        .context("Failed to relocate memory segments")?;
+    vm.relocate_trace(&relocation_table)
+        .context("Failed to relocate the trace")?;
+    let relocated_trace = vm
+        .get_relocated_trace()
+        .context("Failed to get the trace")?;
+
+    // The program segment is relocated to address 1 and starts with the entry code
+    let program_start = 1 + entry_code_size;
+    let entry_code_steps = relocated_trace
+        .iter()
+        .take_while(|entry| entry.pc < program_start)
+        .count();
+    let trace = relocated_trace[entry_code_steps..]
+        .iter()
+        .map(|entry| TraceEntry {
+            pc: entry.pc.saturating_sub(program_start),
+            ap: entry.ap,
+            fp: entry.fp,
+        })
+        .collect();
+    let contract_calls = contract_calls
+        .into_iter()
+        .map(|(step, call)| (step.saturating_sub(entry_code_steps), call))
+        .collect();
+
+    Ok(ExecutedCode {
+        trace,
+        contract_calls,
+        hints,
+    })
+}
+
+/// Runs a single test, recording the executed code if the runner config requires reporting it
 pub(crate) fn run_from_test_case(
     runner: &SierraCasmRunner,
     case: &TestCase,
     contracts: &HashMap<String, StarknetContractArtifacts>,
     predeployed_contracts: &Utf8PathBuf,
-) -> Result<TestCaseSummary> {
+    runner_config: &RunnerConfig,
+) -> Result<(TestCaseSummary, Option<ExecutedCode>)> {
+    let record_execution = runner_config.record_execution();
+    // Limits set with test attributes take precedence over the ones from the runner config
+    let max_steps = case.max_steps.or(runner_config.max_steps);
+    let timeout = case.timeout.or(runner_config.timeout);
+
     let available_gas = if let Some(available_gas) = &case.available_gas {
         Some(*available_gas)
     } else {
@@ -58,6 +127,10 @@ pub(crate) fn run_from_test_case(
     let func = runner.find_function(case.name.as_str())?;
     let initial_gas = runner.get_initial_available_gas(func, available_gas)?;
     let (entry_code, builtins) = runner.create_entry_code(func, &[], initial_gas)?;
+    let entry_code_size = entry_code
+        .iter()
+        .map(|instruction| instruction.body.op_size())
+        .sum();
     let footer = runner.create_code_footer();
     let instructions = chain!(
         entry_code.iter(),
@@ -65,37 +138,82 @@ pub(crate) fn run_from_test_case(
         footer.iter()
     );
     let (hints_dict, string_to_hint) = build_hints_dict(instructions.clone());
+    let hints = if record_execution {
+        hints_by_pc(&hints_dict, entry_code_size)
+    } else {
+        HashMap::new()
+    };
     let core_cairo_hint_processor = CoreCairoHintProcessor {
         runner: Some(runner),
         starknet_state: StarknetState::default(),
         string_to_hint,
-        run_resources: RunResources::default(),
+        run_resources: max_steps.map_or_else(RunResources::default, RunResources::new),
     };
     let mut cairo_hint_processor = CairoHintProcessor {
         original_cairo_hint_processor: core_cairo_hint_processor,
         blockifier_state: build_testing_state(predeployed_contracts),
         contracts,
+        contracts_execution: ContractsExecution::new(runner_config.charge_fees),
+        deadline: timeout.map(|timeout| Instant::now() + Duration::from_secs(timeout)),
+        timed_out: false,
     };
 
-    match runner.run_function(
-        runner.find_function(case.name.as_str())?,
+    let mut vm = VirtualMachine::new(record_execution);
+    let run_result = runner.run_function_with_vm(
+        func,
+        &mut vm,
         &mut cairo_hint_processor,
         hints_dict,
         instructions,
         builtins,
-    ) {
-        Ok(result) => Ok(TestCaseSummary::from_run_result(result, case)),
+    );
+
+    let interrupt_reason = if cairo_hint_processor.timed_out {
+        timeout.map(|timeout| InterruptReason::TimedOut { timeout })
+    } else if cairo_hint_processor
+        .original_cairo_hint_processor
+        .run_resources
+        .consumed()
+    {
+        max_steps.map(|max_steps| InterruptReason::StepLimitExceeded { max_steps })
+    } else {
+        None
+    };
+
+    let fee = cairo_hint_processor.contracts_execution.fee_charged();
+    let summary = match (run_result, interrupt_reason) {
+        (Ok(result), _) => TestCaseSummary::from_run_result(result, case, fee),
+
+        // Execution stopped by the hint processor ends with an unfinished execution error
+        (Err(_), Some(reason)) => TestCaseSummary::Interrupted {
+            name: case.name.clone(),
+            reason,
+        },
 
         // CairoRunError comes from VirtualMachineError which may come from HintException that originates in the cheatcode processor
-        Err(RunnerError::CairoRunError(error)) => Ok(TestCaseSummary::Failed {
+        (Err(RunnerError::CairoRunError(error)), None) => TestCaseSummary::Failed {
             name: case.name.clone(),
             run_result: None,
             msg: Some(format!(
                 "\n    {}\n",
                 error.to_string().replace(" Custom Hint Error: ", "\n    ")
             )),
-        }),
+            fee,
+        },
 
-        Err(err) => Err(err.into()),
-    }
+        (Err(err), None) => return Err(err.into()),
+    };
+
+    let executed_code = if record_execution {
+        let contract_calls = cairo_hint_processor.executed_contract_calls()?;
+        Some(executed_code(
+            &mut vm,
+            entry_code_size,
+            contract_calls,
+            hints,
+        )?)
+    } else {
+        None
+    };
+    Ok((summary, executed_code))
 }
@@ -1,18 +1,24 @@
 use crate::ForgeConfigFromScarb;
 use anyhow::{anyhow, Context, Result};
-use camino::Utf8PathBuf;
-use scarb_metadata::{Metadata, PackageId};
+use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
+use scarb_metadata::{Metadata, PackageId, TargetMetadata};
 use serde::Deserialize;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::fs;
+use std::hash::{Hash, Hasher};
+use std::process::Command;
 use test_collector::LinkedLibrary;
+use walkdir::WalkDir;
 
-#[allow(dead_code)]
 #[derive(Deserialize, Debug, PartialEq, Clone)]
 pub struct StarknetContract {
-    id: String,
     package_name: String,
     contract_name: String,
+    /// Full path of the contract module, e.g. `package::module::Contract`
+    module_path: Option<String>,
     artifacts: StarknetContractArtifactPaths,
 }
 
@@ -35,6 +41,169 @@ pub struct StarknetContractArtifacts {
     pub casm: Option<String>,
 }
 
+pub const DEFAULT_PROFILE: &str = "dev";
+
+const BUILD_FINGERPRINT_FILE: &str = "snforge_build_fingerprint";
+
+/// Build the workspace with Scarb using the given `profile`
+///
+/// The build is skipped if none of the sources of the workspace packages and their dependencies
+/// changed since the last successful build.
+/// If Scarb fails, an error containing its diagnostics is returned.
+pub fn build_workspace(metadata: &Metadata, profile: &str) -> Result<()> {
+    let fingerprint_path = target_dir(metadata)
+        .join(profile)
+        .join(BUILD_FINGERPRINT_FILE);
+    let fingerprint = sources_fingerprint(metadata, profile)?;
+
+    if fs::read_to_string(&fingerprint_path).ok().as_ref() == Some(&fingerprint) {
+        return Ok(());
+    }
+
+    scarb_build(metadata, profile, None)?;
+    write_fingerprint(&fingerprint_path, &fingerprint)
+}
+
+/// Build the `packages` with Scarb using the given `profile`, returning the packages that were built
+///
+/// A package is skipped if none of its sources and the sources of its path dependencies
+/// changed since its last successful build, so only the changed packages are rebuilt.
+pub fn build_packages(
+    metadata: &Metadata,
+    packages: &[PackageId],
+    profile: &str,
+) -> Result<Vec<PackageId>> {
+    let target_dir = target_dir(metadata);
+    let mut built_packages = vec![];
+    for package in packages {
+        let package_metadata = metadata
+            .get_package(package)
+            .ok_or_else(|| anyhow!("Failed to find metadata for package = {package}"))?;
+        let fingerprint_path = target_dir.join(profile).join(format!(
+            "{}.{BUILD_FINGERPRINT_FILE}",
+            package_metadata.name
+        ));
+        let mut roots = path_dependencies_roots(metadata, package);
+        roots.push(package_metadata.root.clone());
+        let fingerprint = roots_fingerprint(roots, &target_dir, profile)?;
+
+        if fs::read_to_string(&fingerprint_path).ok().as_ref() == Some(&fingerprint) {
+            continue;
+        }
+
+        scarb_build(metadata, profile, Some(&package_metadata.name))?;
+        write_fingerprint(&fingerprint_path, &fingerprint)?;
+        built_packages.push(package.clone());
+    }
+    Ok(built_packages)
+}
+
+/// Runs `scarb build` for the whole workspace or only for the package with `package_name`
+fn scarb_build(metadata: &Metadata, profile: &str, package_name: Option<&str>) -> Result<()> {
+    let mut command = Command::new("scarb");
+    command
+        .current_dir(&metadata.workspace.root)
+        .env("SCARB_TARGET_DIR", target_dir(metadata));
+    if profile != DEFAULT_PROFILE {
+        command.arg("--profile").arg(profile);
+    }
+    command.arg("build");
+    if let Some(package_name) = package_name {
+        command.arg("--package").arg(package_name);
+    }
+    let output = command
+        .output()
+        .context("Failed to build contracts with Scarb")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Scarb build did not succeed:\n{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(())
+}
+
+fn write_fingerprint(fingerprint_path: &Utf8Path, fingerprint: &str) -> Result<()> {
+    if let Some(dir) = fingerprint_path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create directory = {dir}"))?;
+    }
+    fs::write(fingerprint_path, fingerprint)
+        .with_context(|| format!("Failed to write build fingerprint to {fingerprint_path}"))
+}
+
+fn target_dir(metadata: &Metadata) -> Utf8PathBuf {
+    metadata
+        .target_dir
+        .clone()
+        .unwrap_or_else(|| metadata.workspace.root.join("target"))
+}
+
+/// Computes a fingerprint of the sources of all packages in the `metadata`
+/// based on their paths, sizes and modification times
+fn sources_fingerprint(metadata: &Metadata, profile: &str) -> Result<String> {
+    let roots = metadata
+        .packages
+        .iter()
+        .map(|package| package.root.clone())
+        .collect();
+    roots_fingerprint(roots, &target_dir(metadata), profile)
+}
+
+/// Computes a fingerprint of the sources in the package `roots`, skipping the `target_dir`
+fn roots_fingerprint(
+    roots: Vec<Utf8PathBuf>,
+    target_dir: &Utf8Path,
+    profile: &str,
+) -> Result<String> {
+    let mut hasher = DefaultHasher::new();
+    profile.hash(&mut hasher);
+
+    let roots: Vec<Utf8PathBuf> = roots.into_iter().sorted().dedup().collect();
+    // Packages nested in other packages are hashed together with them
+    let roots = roots
+        .iter()
+        .filter(|root| {
+            !roots
+                .iter()
+                .any(|other| root != &other && root.starts_with(other))
+        })
+        .collect_vec();
+
+    for root in roots {
+        for entry in WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| {
+                e.depth() == 0
+                    || (e.path() != target_dir.as_std_path() && !is_hidden(e.file_name()))
+            })
+        {
+            let entry =
+                entry.with_context(|| format!("Failed to read directory at path = {root}"))?;
+            let path = entry.path();
+            let is_source = path.extension().unwrap_or_default() == "cairo"
+                || entry.file_name() == "Scarb.toml"
+                || entry.file_name() == "Scarb.lock";
+
+            if path.is_file() && is_source {
+                let file_metadata = entry
+                    .metadata()
+                    .with_context(|| format!("Failed to read metadata of file = {path:?}"))?;
+                path.hash(&mut hasher);
+                file_metadata.len().hash(&mut hasher);
+                file_metadata.modified().ok().hash(&mut hasher);
+            }
+        }
+    }
+    Ok(format!("{:016x}", hasher.finish()))
+}
+
+fn is_hidden(file_name: &OsStr) -> bool {
+    file_name.to_str().is_some_and(|name| name.starts_with('.'))
+}
+
 /// Get deserialized contents of `starknet_artifacts.json` file generated by Scarb
 ///
 /// # Arguments
@@ -49,11 +218,13 @@ pub fn artifacts_for_package(path: &Utf8PathBuf) -> Result<StarknetArtifacts> {
     Ok(starknet_artifacts)
 }
 
+/// Finds `starknet_artifacts.json` of the target in the `target_dir` built with the `profile`
 pub fn try_get_starknet_artifacts_path(
-    path: &Utf8PathBuf,
+    target_dir: &Utf8PathBuf,
     target_name: &str,
+    profile: &str,
 ) -> Result<Option<Utf8PathBuf>> {
-    let path = path.join("target/dev");
+    let path = target_dir.join(profile);
     let paths = fs::read_dir(path);
     let Ok(mut paths) = paths else {
         return Ok(None);
@@ -72,24 +243,216 @@ pub fn try_get_starknet_artifacts_path(
     starknet_artifacts.transpose()
 }
 
+/// Get a map of contract name to contract artifacts from `starknet_artifacts.json` file
+///
+/// Every contract is available under its fully qualified `package::Contract` name
+/// and, as long as it is unambiguous, under its short name.
 pub fn get_contracts_map(path: &Utf8PathBuf) -> Result<HashMap<String, StarknetContractArtifacts>> {
+    let contracts = contracts_from_artifacts(path)?;
+    Ok(build_contracts_map(contracts, None))
+}
+
+/// Get a map of contracts available for the `package`
+///
+/// Collects artifacts of every package in the workspace, including contracts from dependencies
+/// listed in `build-external-contracts` of the `starknet-contract` targets.
+/// Contracts of the `package` itself always take precedence when referenced by their short name.
+pub fn get_contracts_map_for_package(
+    metadata: &Metadata,
+    package: &PackageId,
+    profile: &str,
+) -> Result<HashMap<String, StarknetContractArtifacts>> {
+    let package_name = &metadata
+        .get_package(package)
+        .ok_or_else(|| anyhow!("Failed to find metadata for package = {package}"))?
+        .name;
+
+    let target_dir = target_dir(metadata);
+    let mut contracts = vec![];
+    for member in &metadata.workspace.members {
+        for target in starknet_targets(metadata, member) {
+            if let Some(path) = try_get_starknet_artifacts_path(&target_dir, &target.name, profile)?
+            {
+                let external_contracts = external_contracts(target)?;
+                contracts.extend(qualify_external_contracts(
+                    contracts_from_artifacts(&path)?,
+                    &external_contracts,
+                    &target.name,
+                )?);
+            }
+        }
+    }
+
+    Ok(build_contracts_map(contracts, Some(package_name.as_str())))
+}
+
+fn starknet_targets<'a>(
+    metadata: &'a Metadata,
+    package: &'a PackageId,
+) -> impl Iterator<Item = &'a TargetMetadata> {
+    metadata
+        .compilation_units
+        .iter()
+        .filter(move |unit| unit.package == *package && unit.target.kind == "starknet-contract")
+        .map(|unit| &unit.target)
+        .unique_by(|target| &target.name)
+}
+
+/// Contract built from a dependency of the package, e.g. `dependency::module::Contract`
+#[derive(Debug, PartialEq)]
+struct ExternalContract {
+    path: String,
+    package_name: String,
+}
+
+fn external_contracts(target: &TargetMetadata) -> Result<Vec<ExternalContract>> {
+    let Some(paths) = target.params.get("build-external-contracts") else {
+        return Ok(vec![]);
+    };
+    let paths: Vec<String> = serde_json::from_value(paths.clone()).with_context(|| {
+        format!(
+            "Failed to parse build-external-contracts of target = {}",
+            target.name
+        )
+    })?;
+
+    paths
+        .iter()
+        .map(|path| match path.split_once("::") {
+            Some((package_name, _)) => Ok(ExternalContract {
+                path: path.clone(),
+                package_name: package_name.to_string(),
+            }),
+            None => Err(anyhow!(
+                "Invalid contract path = {path} in build-external-contracts of target = {}",
+                target.name
+            )),
+        })
+        .collect()
+}
+
+/// Attributes contracts built with `build-external-contracts` to the dependencies defining them
+///
+/// Contracts are matched by their full module path, so a contract of the package sharing
+/// the name with an external one stays attributed to the package.
+fn qualify_external_contracts(
+    contracts: Vec<(StarknetContract, StarknetContractArtifacts)>,
+    external_contracts: &[ExternalContract],
+    target_name: &str,
+) -> Result<Vec<(StarknetContract, StarknetContractArtifacts)>> {
+    let is_external = |contract: &StarknetContract, external: &ExternalContract| {
+        contract.module_path.as_ref() == Some(&external.path)
+    };
+
+    for external_contract in external_contracts {
+        if !contracts
+            .iter()
+            .any(|(contract, _)| is_external(contract, external_contract))
+        {
+            return Err(anyhow!(
+                "Failed to find artifacts of contract = {} in target = {target_name}",
+                external_contract.path
+            ));
+        }
+    }
+
+    Ok(contracts
+        .into_iter()
+        .map(|(mut contract, artifacts)| {
+            if let Some(external_contract) = external_contracts
+                .iter()
+                .find(|external| is_external(&contract, external))
+            {
+                contract.package_name = external_contract.package_name.clone();
+            }
+            (contract, artifacts)
+        })
+        .collect())
+}
+
+fn contracts_from_artifacts(
+    path: &Utf8PathBuf,
+) -> Result<Vec<(StarknetContract, StarknetContractArtifacts)>> {
     let base_path = path
         .parent()
         .ok_or_else(|| anyhow!("Failed to get parent for path = {}", path))?;
     let artifacts = artifacts_for_package(path)?;
-    let mut map = HashMap::new();
+    let mut contracts = vec![];
     for contract in artifacts.contracts {
-        let name = contract.contract_name;
-        let sierra_path = base_path.join(contract.artifacts.sierra);
+        let sierra_path = base_path.join(&contract.artifacts.sierra);
         let casm_path = contract
             .artifacts
             .casm
+            .as_ref()
             .map(|casm_path| base_path.join(casm_path));
         let sierra = fs::read_to_string(sierra_path)?;
         let casm: Option<String> = casm_path.map(fs::read_to_string).transpose()?;
-        map.insert(name, StarknetContractArtifacts { sierra, casm });
+        contracts.push((contract, StarknetContractArtifacts { sierra, casm }));
     }
-    Ok(map)
+    Ok(contracts)
+}
+
+fn build_contracts_map(
+    contracts: Vec<(StarknetContract, StarknetContractArtifacts)>,
+    preferred_package: Option<&str>,
+) -> HashMap<String, StarknetContractArtifacts> {
+    let mut qualified = HashMap::new();
+    for (contract, artifacts) in contracts {
+        qualified.insert(
+            (
+                contract.package_name.clone(),
+                contract.contract_name.clone(),
+            ),
+            artifacts,
+        );
+    }
+
+    let short_name_counts = qualified.keys().map(|(_, name)| name).counts();
+
+    let mut map = HashMap::new();
+    for ((package_name, contract_name), artifacts) in &qualified {
+        let is_preferred = preferred_package == Some(package_name.as_str());
+        if is_preferred || short_name_counts[contract_name] == 1 {
+            map.insert(contract_name.clone(), artifacts.clone());
+        }
+    }
+    for ((package_name, contract_name), artifacts) in qualified {
+        map.insert(format!("{package_name}::{contract_name}"), artifacts);
+    }
+    map
+}
+
+/// Select the workspace members to run tests for
+///
+/// With `package_name` only the member with this name is selected,
+/// otherwise or with `workspace` all members are.
+pub fn select_packages(
+    metadata: &Metadata,
+    package_name: Option<&str>,
+    workspace: bool,
+) -> Result<Vec<PackageId>> {
+    let members = metadata
+        .workspace
+        .members
+        .iter()
+        .map(|member| {
+            metadata
+                .get_package(member)
+                .ok_or_else(|| anyhow!("Failed to find metadata for package = {member}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if let (Some(package_name), false) = (package_name, workspace) {
+        let package = members
+            .iter()
+            .find(|package| package.name == package_name)
+            .ok_or_else(|| {
+                anyhow!("Failed to find package = {package_name} in the workspace members")
+            })?;
+        return Ok(vec![package.id.clone()]);
+    }
+
+    Ok(members.iter().map(|package| package.id.clone()).collect())
 }
 
 pub fn config_from_scarb_for_package(
@@ -107,6 +470,23 @@ pub fn config_from_scarb_for_package(
     )
 }
 
+/// Roots of the path dependencies of the `package`, including the transitive ones and other
+/// workspace members, sorted and without duplicates
+pub fn path_dependencies_roots(metadata: &Metadata, package: &PackageId) -> Vec<Utf8PathBuf> {
+    metadata
+        .compilation_units
+        .iter()
+        .filter(|unit| unit.package == *package)
+        .flat_map(|unit| &unit.components)
+        .filter(|component| component.package != *package)
+        .filter_map(|component| metadata.get_package(&component.package))
+        .filter(|dependency| dependency.source.repr.starts_with("path+"))
+        .map(|dependency| dependency.root.clone())
+        .sorted()
+        .dedup()
+        .collect()
+}
+
 pub fn dependencies_for_package(
     metadata: &Metadata,
     package: &PackageId,
@@ -172,6 +552,150 @@ mod tests {
     use scarb_metadata::MetadataCommand;
     use std::process::Command;
 
+    fn metadata_for(path: &std::path::Path) -> Metadata {
+        MetadataCommand::new()
+            .inherit_stderr()
+            .current_dir(path)
+            .exec()
+            .unwrap()
+    }
+
+    #[test]
+    fn build_workspace_writes_fingerprint() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+            .unwrap();
+        let metadata = metadata_for(temp.path());
+
+        build_workspace(&metadata, DEFAULT_PROFILE).unwrap();
+
+        let fingerprint_path = temp.join("target/dev").join(BUILD_FINGERPRINT_FILE);
+        let fingerprint = fs::read_to_string(fingerprint_path).unwrap();
+        assert_eq!(
+            fingerprint,
+            sources_fingerprint(&metadata, DEFAULT_PROFILE).unwrap()
+        );
+        assert!(temp
+            .join("target/dev/simple_package.starknet_artifacts.json")
+            .exists());
+    }
+
+    #[test]
+    fn sources_fingerprint_changes_with_sources() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+            .unwrap();
+        let metadata = metadata_for(temp.path());
+
+        let before = sources_fingerprint(&metadata, DEFAULT_PROFILE).unwrap();
+        temp.child("target/dev/ignored.cairo").touch().unwrap();
+        assert_eq!(
+            before,
+            sources_fingerprint(&metadata, DEFAULT_PROFILE).unwrap()
+        );
+
+        temp.child("src/new_module.cairo").touch().unwrap();
+        assert_ne!(
+            before,
+            sources_fingerprint(&metadata, DEFAULT_PROFILE).unwrap()
+        );
+        assert_ne!(
+            sources_fingerprint(&metadata, DEFAULT_PROFILE).unwrap(),
+            sources_fingerprint(&metadata, "release").unwrap()
+        );
+    }
+
+    #[test]
+    fn sources_fingerprint_changes_with_path_dependency() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("dependency")
+            .copy_from(
+                "tests/data/external_contracts/dependency",
+                &["**/*.cairo", "**/*.toml"],
+            )
+            .unwrap();
+        temp.child("package/Scarb.toml")
+            .write_str(indoc!(
+                r#"
+                [package]
+                name = "package"
+                version = "0.1.0"
+
+                [dependencies]
+                starknet = "2.0.1"
+                dependency = { path = "../dependency" }
+                "#
+            ))
+            .unwrap();
+        temp.child("package/src/lib.cairo").touch().unwrap();
+        let metadata = metadata_for(&temp.join("package"));
+
+        let before = sources_fingerprint(&metadata, DEFAULT_PROFILE).unwrap();
+        temp.child("dependency/src/new_module.cairo")
+            .touch()
+            .unwrap();
+
+        assert_ne!(
+            before,
+            sources_fingerprint(&metadata, DEFAULT_PROFILE).unwrap()
+        );
+    }
+
+    #[test]
+    fn build_packages_rebuilds_only_changed_packages() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.copy_from("tests/data/workspace", &["**/*.cairo", "**/*.toml"])
+            .unwrap();
+        let metadata = metadata_for(temp.path());
+        let members = &metadata.workspace.members;
+        let package_named = |name: &str| {
+            members
+                .iter()
+                .find(|member| metadata.get_package(member).unwrap().name == name)
+                .unwrap()
+                .clone()
+        };
+
+        let built = build_packages(&metadata, members, DEFAULT_PROFILE).unwrap();
+        assert_eq!(built.len(), 2);
+        assert!(temp
+            .join("target/dev")
+            .join(format!("addition.{BUILD_FINGERPRINT_FILE}"))
+            .exists());
+
+        assert!(build_packages(&metadata, members, DEFAULT_PROFILE)
+            .unwrap()
+            .is_empty());
+
+        temp.child("crates/addition/src/new_module.cairo")
+            .touch()
+            .unwrap();
+        assert_eq!(
+            build_packages(&metadata, members, DEFAULT_PROFILE).unwrap(),
+            vec![package_named("addition")]
+        );
+    }
+
+    #[test]
+    fn build_workspace_err_on_compilation_error() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+            .unwrap();
+        temp.child("src/lib.cairo")
+            .write_str("fn broken() -> felt252 { 'a' + }")
+            .unwrap();
+        let metadata = metadata_for(temp.path());
+
+        let result = build_workspace(&metadata, DEFAULT_PROFILE);
+        let err = result.unwrap_err();
+
+        assert!(err.to_string().contains("Scarb build did not succeed"));
+        assert!(!temp
+            .join("target/dev")
+            .join(BUILD_FINGERPRINT_FILE)
+            .exists());
+    }
+
     #[test]
     fn get_starknet_artifacts_path() {
         let temp = assert_fs::TempDir::new().unwrap();
@@ -184,8 +708,9 @@ mod tests {
             .unwrap();
 
         let result = try_get_starknet_artifacts_path(
-            &Utf8PathBuf::from_path_buf(temp.to_path_buf()).unwrap(),
+            &Utf8PathBuf::from_path_buf(temp.join("target")).unwrap(),
             "simple_package",
+            "dev",
         );
         let path = result.unwrap().unwrap();
         assert_eq!(
@@ -195,6 +720,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_starknet_artifacts_path_with_custom_target_dir() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+            .unwrap();
+        let target_dir = temp.join("custom_target");
+        Command::new("scarb")
+            .current_dir(&temp)
+            .env("SCARB_TARGET_DIR", &target_dir)
+            .arg("build")
+            .output()
+            .unwrap();
+
+        let result = try_get_starknet_artifacts_path(
+            &Utf8PathBuf::from_path_buf(target_dir.clone()).unwrap(),
+            "simple_package",
+            "dev",
+        );
+        let path = result.unwrap().unwrap();
+        assert_eq!(
+            path,
+            target_dir.join("dev/simple_package.starknet_artifacts.json")
+        );
+        assert!(!temp.join("target").exists());
+    }
+
     #[test]
     fn get_starknet_artifacts_path_for_project_with_different_package_and_target_name() {
         let temp = assert_fs::TempDir::new().unwrap();
@@ -225,8 +776,9 @@ mod tests {
             .unwrap();
 
         let result = try_get_starknet_artifacts_path(
-            &Utf8PathBuf::from_path_buf(temp.to_path_buf()).unwrap(),
+            &Utf8PathBuf::from_path_buf(temp.join("target")).unwrap(),
             "essa",
+            "dev",
         );
         let path = result.unwrap().unwrap();
         assert_eq!(
@@ -247,8 +799,9 @@ mod tests {
             .unwrap();
 
         let result = try_get_starknet_artifacts_path(
-            &Utf8PathBuf::from_path_buf(temp.to_path_buf()).unwrap(),
+            &Utf8PathBuf::from_path_buf(temp.join("target")).unwrap(),
             "print_test",
+            "dev",
         );
         let path = result.unwrap();
         assert!(path.is_none());
@@ -261,8 +814,9 @@ mod tests {
             .unwrap();
 
         let result = try_get_starknet_artifacts_path(
-            &Utf8PathBuf::from_path_buf(temp.to_path_buf()).unwrap(),
+            &Utf8PathBuf::from_path_buf(temp.join("target")).unwrap(),
             "simple_package",
+            "dev",
         );
         let path = result.unwrap();
         assert!(path.is_none());
@@ -339,6 +893,218 @@ mod tests {
         let contract = contracts.get("HelloStarknet").unwrap();
         assert_eq!(&sierra_contents_erc20, &contract.sierra);
         assert_eq!(&casm_contents_erc20, &contract.casm.clone().unwrap());
+
+        assert_eq!(
+            contracts.get("simple_package::ERC20"),
+            contracts.get("ERC20")
+        );
+        assert_eq!(
+            contracts.get("simple_package::HelloStarknet"),
+            contracts.get("HelloStarknet")
+        );
+    }
+
+    #[test]
+    fn get_contracts_for_package() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+            .unwrap();
+        Command::new("scarb")
+            .current_dir(&temp)
+            .arg("build")
+            .output()
+            .unwrap();
+        let scarb_metadata = MetadataCommand::new()
+            .inherit_stderr()
+            .current_dir(temp.path())
+            .exec()
+            .unwrap();
+
+        let contracts = get_contracts_map_for_package(
+            &scarb_metadata,
+            &scarb_metadata.workspace.members[0],
+            "dev",
+        )
+        .unwrap();
+
+        assert!(contracts.contains_key("ERC20"));
+        assert!(contracts.contains_key("HelloStarknet"));
+        assert!(contracts.contains_key("simple_package::ERC20"));
+        assert!(contracts.contains_key("simple_package::HelloStarknet"));
+    }
+
+    #[test]
+    fn get_contracts_for_package_with_custom_target_dir() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+            .unwrap();
+        let target_dir = temp.join("custom_target");
+        let scarb_metadata = MetadataCommand::new()
+            .inherit_stderr()
+            .current_dir(temp.path())
+            .env("SCARB_TARGET_DIR", &target_dir)
+            .exec()
+            .unwrap();
+        build_workspace(&scarb_metadata, DEFAULT_PROFILE).unwrap();
+        let contracts = get_contracts_map_for_package(
+            &scarb_metadata,
+            &scarb_metadata.workspace.members[0],
+            "dev",
+        )
+        .unwrap();
+
+        assert!(target_dir.join("dev").exists());
+        assert!(!temp.join("target").exists());
+        assert!(contracts.contains_key("simple_package::ERC20"));
+        assert!(contracts.contains_key("simple_package::HelloStarknet"));
+    }
+
+    #[test]
+    fn get_contracts_for_package_with_external_contracts() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.copy_from(
+            "tests/data/external_contracts",
+            &["**/*.cairo", "**/*.toml"],
+        )
+        .unwrap();
+        Command::new("scarb")
+            .current_dir(&temp)
+            .arg("build")
+            .output()
+            .unwrap();
+        let scarb_metadata = MetadataCommand::new()
+            .inherit_stderr()
+            .current_dir(temp.path())
+            .exec()
+            .unwrap();
+
+        let contracts = get_contracts_map_for_package(
+            &scarb_metadata,
+            &scarb_metadata.workspace.members[0],
+            "dev",
+        )
+        .unwrap();
+
+        assert!(contracts.contains_key("dependency::HelloStarknet"));
+        assert_eq!(
+            contracts.get("HelloStarknet"),
+            contracts.get("dependency::HelloStarknet")
+        );
+        assert!(!contracts.contains_key("external_contracts::HelloStarknet"));
+    }
+
+    #[test]
+    fn external_contracts_err_on_invalid_path() {
+        let target: TargetMetadata = serde_json::from_value(serde_json::json!({
+            "kind": "starknet-contract",
+            "name": "package",
+            "source_path": "src/lib.cairo",
+            "params": { "build-external-contracts": ["HelloStarknet"] }
+        }))
+        .unwrap();
+
+        let err = external_contracts(&target).unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("Invalid contract path = HelloStarknet"));
+    }
+
+    #[test]
+    fn qualify_external_contracts_by_module_path() {
+        let contract = |module_path: &str, contract_name: &str| {
+            (
+                StarknetContract {
+                    package_name: "package".to_string(),
+                    contract_name: contract_name.to_string(),
+                    module_path: Some(module_path.to_string()),
+                    artifacts: StarknetContractArtifactPaths {
+                        sierra: Utf8PathBuf::from("contract.sierra.json"),
+                        casm: None,
+                    },
+                },
+                StarknetContractArtifacts {
+                    sierra: module_path.to_string(),
+                    casm: None,
+                },
+            )
+        };
+        let contracts = vec![
+            contract("package::HelloStarknet", "HelloStarknet"),
+            contract("dependency::hello_starknet::HelloStarknet", "HelloStarknet"),
+        ];
+        let external_contracts = vec![ExternalContract {
+            path: "dependency::hello_starknet::HelloStarknet".to_string(),
+            package_name: "dependency".to_string(),
+        }];
+
+        let contracts =
+            qualify_external_contracts(contracts, &external_contracts, "package").unwrap();
+
+        let package_names: Vec<(&str, &str)> = contracts
+            .iter()
+            .map(|(contract, artifacts)| {
+                (contract.package_name.as_str(), artifacts.sierra.as_str())
+            })
+            .collect();
+        assert_eq!(
+            package_names,
+            vec![
+                ("package", "package::HelloStarknet"),
+                ("dependency", "dependency::hello_starknet::HelloStarknet")
+            ]
+        );
+    }
+
+    #[test]
+    fn qualify_external_contracts_err_on_missing_contract() {
+        let external_contracts = vec![ExternalContract {
+            path: "dependency::hello_starknet::HelloStarknet".to_string(),
+            package_name: "dependency".to_string(),
+        }];
+
+        let err = qualify_external_contracts(vec![], &external_contracts, "package").unwrap_err();
+
+        assert!(err.to_string().contains(
+            "Failed to find artifacts of contract = dependency::hello_starknet::HelloStarknet"
+        ));
+    }
+
+    #[test]
+    fn contracts_map_with_ambiguous_names() {
+        let contract = |package_name: &str, contract_name: &str| {
+            (
+                StarknetContract {
+                    package_name: package_name.to_string(),
+                    contract_name: contract_name.to_string(),
+                    module_path: Some(format!("{package_name}::{contract_name}")),
+                    artifacts: StarknetContractArtifactPaths {
+                        sierra: Utf8PathBuf::from("contract.sierra.json"),
+                        casm: None,
+                    },
+                },
+                StarknetContractArtifacts {
+                    sierra: format!("{package_name}::{contract_name}"),
+                    casm: None,
+                },
+            )
+        };
+        let contracts = vec![
+            contract("first", "ERC20"),
+            contract("second", "ERC20"),
+            contract("second", "Unique"),
+        ];
+
+        let map = build_contracts_map(contracts.clone(), None);
+        assert!(!map.contains_key("ERC20"));
+        assert_eq!(map["first::ERC20"].sierra, "first::ERC20");
+        assert_eq!(map["second::ERC20"].sierra, "second::ERC20");
+        assert_eq!(map["Unique"].sierra, "second::Unique");
+
+        let map = build_contracts_map(contracts, Some("second"));
+        assert_eq!(map["ERC20"].sierra, "second::ERC20");
+        assert_eq!(map["first::ERC20"].sierra, "first::ERC20");
+        assert_eq!(map["Unique"].sierra, "second::Unique");
     }
 
     #[test]
@@ -380,6 +1146,24 @@ mod tests {
         assert!(lib_path.starts_with(package_path));
     }
 
+    #[test]
+    fn get_path_dependencies_roots() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.copy_from(
+            "tests/data/external_contracts",
+            &["**/*.cairo", "**/*.toml"],
+        )
+        .unwrap();
+        let scarb_metadata = metadata_for(temp.path());
+
+        let roots = path_dependencies_roots(&scarb_metadata, &scarb_metadata.workspace.members[0]);
+
+        assert_eq!(
+            roots,
+            vec![Utf8PathBuf::from_path_buf(temp.join("dependency")).unwrap()]
+        );
+    }
+
     #[test]
     fn get_dependencies_for_package_err_on_invalid_package() {
         let temp = assert_fs::TempDir::new().unwrap();
@@ -415,7 +1199,14 @@ mod tests {
             config_from_scarb_for_package(&scarb_metadata, &scarb_metadata.workspace.members[0])
                 .unwrap();
 
-        assert_eq!(config, ForgeConfigFromScarb { exit_first: false });
+        assert_eq!(
+            config,
+            ForgeConfigFromScarb {
+                exit_first: false,
+                max_steps: None,
+                timeout: None,
+            }
+        );
     }
 
     #[test]
@@ -0,0 +1,94 @@
+use crate::account_transactions::{felt_from_field_element, field_element_from_felt};
+use anyhow::{anyhow, Result};
+use cairo_felt::Felt252;
+use num_traits::Zero;
+use starknet::core::crypto::pedersen_hash;
+use starknet::core::types::FieldElement;
+use starknet::signers::SigningKey;
+
+/// Stark key pair returned to the test as `cheatcodes::StarkKeyPair`
+#[derive(Debug, PartialEq)]
+pub(crate) struct StarkKeyPair {
+    pub private_key: Felt252,
+    pub public_key: Felt252,
+}
+
+impl StarkKeyPair {
+    fn from_signing_key(signing_key: &SigningKey) -> Self {
+        Self {
+            private_key: felt_from_field_element(&signing_key.secret_scalar()),
+            public_key: felt_from_field_element(&signing_key.verifying_key().scalar()),
+        }
+    }
+
+    pub(crate) fn to_felts(&self) -> [Felt252; 2] {
+        [self.private_key.clone(), self.public_key.clone()]
+    }
+}
+
+pub(crate) fn generate_stark_keys() -> StarkKeyPair {
+    StarkKeyPair::from_signing_key(&SigningKey::from_random())
+}
+
+/// Derives the key pair deterministically, the private key is the Pedersen hash of the seed and zero
+pub(crate) fn derive_stark_keys(seed: &Felt252) -> Result<StarkKeyPair> {
+    let private_key = pedersen_hash(&field_element_from_felt(seed)?, &FieldElement::ZERO);
+    Ok(StarkKeyPair::from_signing_key(
+        &SigningKey::from_secret_scalar(private_key),
+    ))
+}
+
+/// Signs the message hash with the private key, returning the `(r, s)` signature
+pub(crate) fn sign_message_hash(
+    private_key: &Felt252,
+    message_hash: &Felt252,
+) -> Result<(Felt252, Felt252)> {
+    if private_key.is_zero() {
+        return Err(anyhow!("Private key must not be zero"));
+    }
+    let signing_key = SigningKey::from_secret_scalar(field_element_from_felt(private_key)?);
+    let signature = signing_key
+        .sign(&field_element_from_felt(message_hash)?)
+        .map_err(|err| anyhow!("Failed to sign message hash {message_hash}: {err}"))?;
+    Ok((
+        felt_from_field_element(&signature.r),
+        felt_from_field_element(&signature.s),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starknet::core::crypto::{ecdsa_verify, Signature};
+
+    #[test]
+    fn deriving_keys_is_deterministic() {
+        let keys = derive_stark_keys(&Felt252::from(123)).unwrap();
+        assert_eq!(keys, derive_stark_keys(&Felt252::from(123)).unwrap());
+        assert_ne!(keys, derive_stark_keys(&Felt252::from(124)).unwrap());
+        assert_ne!(keys, generate_stark_keys());
+    }
+
+    #[test]
+    fn signature_is_valid_for_public_key() {
+        let keys = derive_stark_keys(&Felt252::from(123)).unwrap();
+        let message_hash = Felt252::from(0x1234);
+        let (r, s) = sign_message_hash(&keys.private_key, &message_hash).unwrap();
+
+        let signature = Signature {
+            r: field_element_from_felt(&r).unwrap(),
+            s: field_element_from_felt(&s).unwrap(),
+        };
+        assert!(ecdsa_verify(
+            &field_element_from_felt(&keys.public_key).unwrap(),
+            &field_element_from_felt(&message_hash).unwrap(),
+            &signature,
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn signing_with_zero_private_key_fails() {
+        assert!(sign_message_hash(&Felt252::from(0), &Felt252::from(0x1234)).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use cairo_lang_runner::short_string::as_cairo_short_string;
 use cairo_lang_runner::{RunResult, RunResultValue};
+use std::fmt::{Display, Formatter};
 use std::option::Option;
 use test_collector::TestCase;
 
@@ -9,30 +10,60 @@ pub enum TestCaseSummary {
         name: String,
         run_result: RunResult,
         msg: Option<String>,
+        /// Fee charged for the transactions made by the test, if fees are charged
+        fee: Option<u128>,
     },
     Failed {
         name: String,
         run_result: Option<RunResult>,
         msg: Option<String>,
+        /// Fee charged for the transactions made by the test, if fees are charged
+        fee: Option<u128>,
     },
     Skipped {
         name: String,
     },
+    /// Test was stopped before finishing
+    Interrupted {
+        name: String,
+        reason: InterruptReason,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum InterruptReason {
+    StepLimitExceeded { max_steps: usize },
+    TimedOut { timeout: u64 },
+}
+
+impl Display for InterruptReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InterruptReason::StepLimitExceeded { max_steps } => {
+                write!(f, "exceeded the limit of {max_steps} steps")
+            }
+            InterruptReason::TimedOut { timeout } => {
+                write!(f, "exceeded the timeout of {timeout} second(s)")
+            }
+        }
+    }
 }
 
 impl TestCaseSummary {
     #[must_use]
-    pub fn from_run_result(run_result: RunResult, test_case: &TestCase) -> Self {
+    pub fn from_run_result(run_result: RunResult, test_case: &TestCase, fee: Option<u128>) -> Self {
         match run_result.value {
             RunResultValue::Success(_) => TestCaseSummary::Passed {
                 name: test_case.name.to_string(),
                 msg: extract_result_data(&run_result),
                 run_result,
+                fee,
             },
             RunResultValue::Panic(_) => TestCaseSummary::Failed {
                 name: test_case.name.to_string(),
                 msg: extract_result_data(&run_result),
                 run_result: Some(run_result),
+                fee,
             },
         }
     }
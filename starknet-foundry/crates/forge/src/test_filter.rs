@@ -0,0 +1,235 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use test_collector::TestCase;
+
+/// Describes how filters are matched against the full test paths
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+pub enum FilterMode {
+    /// Test path contains the filter
+    #[default]
+    Substring,
+    /// Test path is equal to the filter
+    Exact,
+    /// Test path matches the filter as a regular expression
+    Regex,
+    /// Test path matches the filter as a glob pattern, `*` matches any sequence of characters
+    /// and `?` matches a single character
+    Glob,
+}
+
+/// Selects tests to run based on their full paths e.g. `tests::erc20::test_transfer`
+#[derive(Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct TestFilter {
+    name_filters: Vec<String>,
+    skip_filters: Vec<String>,
+    mode: FilterMode,
+}
+
+impl TestFilter {
+    /// Test is selected if it matches any of the `name_filters` (or there are none)
+    /// and doesn't match any of the `skip_filters`
+    pub fn new(
+        name_filters: Vec<String>,
+        skip_filters: Vec<String>,
+        mode: FilterMode,
+    ) -> Result<Self> {
+        let filter = Self {
+            name_filters,
+            skip_filters,
+            mode,
+        };
+        // Validate patterns early, so invalid ones are reported before any test is collected
+        filter.matchers(&filter.name_filters)?;
+        filter.matchers(&filter.skip_filters)?;
+        Ok(filter)
+    }
+
+    pub fn filter(&self, test_cases: Vec<TestCase>) -> Result<Vec<TestCase>> {
+        self.filter_by_path(test_cases, |test| &test.name)
+    }
+
+    /// Selects items by the full test paths returned by `test_path`
+    pub fn filter_by_path<T>(
+        &self,
+        items: Vec<T>,
+        test_path: impl Fn(&T) -> &str,
+    ) -> Result<Vec<T>> {
+        let name_matchers = self.matchers(&self.name_filters)?;
+        let skip_matchers = self.matchers(&self.skip_filters)?;
+
+        Ok(items
+            .into_iter()
+            .filter(|item| {
+                let path = test_path(item);
+                (name_matchers.is_empty()
+                    || name_matchers.iter().any(|matcher| matcher.matches(path)))
+                    && !skip_matchers.iter().any(|matcher| matcher.matches(path))
+            })
+            .collect())
+    }
+
+    fn matchers(&self, filters: &[String]) -> Result<Vec<Matcher>> {
+        filters
+            .iter()
+            .map(|filter| Matcher::new(filter, self.mode))
+            .collect()
+    }
+}
+
+enum Matcher {
+    Substring(String),
+    Exact(String),
+    Pattern(Regex),
+}
+
+impl Matcher {
+    fn new(filter: &str, mode: FilterMode) -> Result<Self> {
+        Ok(match mode {
+            FilterMode::Substring => Matcher::Substring(filter.to_string()),
+            FilterMode::Exact => Matcher::Exact(filter.to_string()),
+            FilterMode::Regex => Matcher::Pattern(
+                Regex::new(filter).with_context(|| format!("Failed to parse regex = {filter}"))?,
+            ),
+            FilterMode::Glob => Matcher::Pattern(glob_to_regex(filter)?),
+        })
+    }
+
+    fn matches(&self, test_name: &str) -> bool {
+        match self {
+            Matcher::Substring(filter) => test_name.contains(filter.as_str()),
+            Matcher::Exact(filter) => test_name == filter,
+            Matcher::Pattern(regex) => regex.is_match(test_name),
+        }
+    }
+}
+
+fn glob_to_regex(glob: &str) -> Result<Regex> {
+    let pattern = regex::escape(glob).replace(r"\*", ".*").replace(r"\?", ".");
+    Regex::new(&format!("^{pattern}$"))
+        .with_context(|| format!("Failed to parse glob pattern = {glob}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mocked_tests() -> Vec<TestCase> {
+        [
+            "tests::erc20::test_transfer",
+            "tests::erc20::test_approve",
+            "tests::account::test_transfer",
+            "src::test_fib",
+        ]
+        .into_iter()
+        .map(|name| TestCase {
+            name: name.to_string(),
+            available_gas: None,
+            max_steps: None,
+            timeout: None,
+        })
+        .collect()
+    }
+
+    fn filtered_names(filter: &TestFilter) -> Vec<String> {
+        filter
+            .filter(mocked_tests())
+            .unwrap()
+            .into_iter()
+            .map(|test| test.name)
+            .collect()
+    }
+
+    #[test]
+    fn no_filters_select_everything() {
+        let filter = TestFilter::default();
+        assert_eq!(filtered_names(&filter).len(), mocked_tests().len());
+    }
+
+    #[test]
+    fn substring_matches_module_path() {
+        let filter =
+            TestFilter::new(vec!["erc20".to_string()], vec![], FilterMode::Substring).unwrap();
+        assert_eq!(
+            filtered_names(&filter),
+            vec!["tests::erc20::test_transfer", "tests::erc20::test_approve"]
+        );
+    }
+
+    #[test]
+    fn multiple_filters_and_skip() {
+        let filter = TestFilter::new(
+            vec!["transfer".to_string(), "fib".to_string()],
+            vec!["account".to_string()],
+            FilterMode::Substring,
+        )
+        .unwrap();
+        assert_eq!(
+            filtered_names(&filter),
+            vec!["tests::erc20::test_transfer", "src::test_fib"]
+        );
+    }
+
+    #[test]
+    fn exact_match() {
+        let filter = TestFilter::new(
+            vec![
+                "tests::erc20::test_approve".to_string(),
+                "test_fib".to_string(),
+            ],
+            vec![],
+            FilterMode::Exact,
+        )
+        .unwrap();
+        assert_eq!(filtered_names(&filter), vec!["tests::erc20::test_approve"]);
+    }
+
+    #[test]
+    fn regex_match() {
+        let filter = TestFilter::new(
+            vec![r"^tests::\w+::test_transfer$".to_string()],
+            vec![],
+            FilterMode::Regex,
+        )
+        .unwrap();
+        assert_eq!(
+            filtered_names(&filter),
+            vec![
+                "tests::erc20::test_transfer",
+                "tests::account::test_transfer"
+            ]
+        );
+    }
+
+    #[test]
+    fn glob_match() {
+        let filter = TestFilter::new(
+            vec!["tests::erc20::*".to_string(), "src::test_fi?".to_string()],
+            vec!["*approve".to_string()],
+            FilterMode::Glob,
+        )
+        .unwrap();
+        assert_eq!(
+            filtered_names(&filter),
+            vec!["tests::erc20::test_transfer", "src::test_fib"]
+        );
+    }
+
+    #[test]
+    fn filtering_by_path() {
+        let filter =
+            TestFilter::new(vec!["erc20".to_string()], vec![], FilterMode::Substring).unwrap();
+        let paths = vec!["tests::erc20::test_transfer", "src::test_fib"];
+        assert_eq!(
+            filter.filter_by_path(paths, |path| *path).unwrap(),
+            vec!["tests::erc20::test_transfer"]
+        );
+    }
+
+    #[test]
+    fn err_on_invalid_regex() {
+        let result = TestFilter::new(vec!["test(".to_string()], vec![], FilterMode::Regex);
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("Failed to parse regex = test("));
+    }
+}
@@ -0,0 +1,206 @@
+use std::fs;
+
+use anyhow::{Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
+
+use crate::execution_trace::{
+    ContractCall, ContractEntryPoints, ExecutedCode, ProgramFunctions, SierraStatements,
+};
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum TraceItem {
+    Step {
+        pc: usize,
+        ap: usize,
+        fp: usize,
+        hints: Vec<String>,
+        sierra_statement_idx: Option<usize>,
+        function: Option<String>,
+    },
+    ContractCallStart {
+        contract: Option<String>,
+        entry_point: String,
+        steps: usize,
+    },
+    ContractCallEnd {
+        contract: Option<String>,
+        entry_point: String,
+    },
+}
+
+/// Steps of the test code executed by the VM, delimited by the contract calls made by the test
+#[derive(Serialize, Debug, PartialEq)]
+pub(crate) struct TraceDump {
+    test: String,
+    trace: Vec<TraceItem>,
+}
+
+impl TraceDump {
+    pub fn new(
+        test_name: &str,
+        executed_code: &ExecutedCode,
+        program_functions: &ProgramFunctions,
+        sierra_statements: &SierraStatements,
+        contract_entry_points: &mut ContractEntryPoints,
+    ) -> Result<Self> {
+        let mut trace = vec![];
+        let mut contract_calls = executed_code.contract_calls.iter().peekable();
+
+        for (step, entry) in executed_code.trace.iter().enumerate() {
+            trace.push(TraceItem::Step {
+                pc: entry.pc,
+                ap: entry.ap,
+                fp: entry.fp,
+                hints: executed_code
+                    .hints
+                    .get(&entry.pc)
+                    .cloned()
+                    .unwrap_or_default(),
+                sierra_statement_idx: sierra_statements.statement_containing(entry.pc),
+                function: program_functions
+                    .function_containing(entry.pc)
+                    .map(String::from),
+            });
+
+            while let Some((_, call)) = contract_calls.next_if(|(call_step, _)| *call_step <= step)
+            {
+                push_contract_call(&mut trace, call, contract_entry_points)?;
+            }
+        }
+
+        Ok(Self {
+            test: test_name.to_string(),
+            trace,
+        })
+    }
+
+    /// Writes the trace as JSON to the directory and returns its path
+    pub fn save(&self, dir: &Utf8Path) -> Result<Utf8PathBuf> {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create directory = {dir}"))?;
+        let path = dir.join(format!("{}.json", self.test.replace("::", "__")));
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(&path, content).with_context(|| format!("Failed to write to {path:?}"))?;
+        Ok(path)
+    }
+}
+
+fn push_contract_call(
+    trace: &mut Vec<TraceItem>,
+    call: &ContractCall,
+    contract_entry_points: &mut ContractEntryPoints,
+) -> Result<()> {
+    let selector = format!("0x{}", call.entry_point_selector.to_str_radix(16));
+    let entry_point = match &call.contract_name {
+        Some(contract_name) => contract_entry_points
+            .function_name(contract_name, &call.entry_point_selector)?
+            .map_or(selector, String::from),
+        None => selector,
+    };
+
+    trace.push(TraceItem::ContractCallStart {
+        contract: call.contract_name.clone(),
+        entry_point: entry_point.clone(),
+        steps: call.steps,
+    });
+    for inner_call in &call.inner_calls {
+        push_contract_call(trace, inner_call, contract_entry_points)?;
+    }
+    trace.push(TraceItem::ContractCallEnd {
+        contract: call.contract_name.clone(),
+        entry_point,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution_trace::TraceEntry;
+    use cairo_felt::Felt252;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn delimits_contract_calls() {
+        let executed_code = ExecutedCode {
+            trace: vec![
+                TraceEntry {
+                    pc: 0,
+                    ap: 10,
+                    fp: 10,
+                },
+                TraceEntry {
+                    pc: 12,
+                    ap: 15,
+                    fp: 15,
+                },
+            ],
+            contract_calls: vec![(
+                0,
+                ContractCall {
+                    contract_name: None,
+                    entry_point_selector: Felt252::from(0x1234),
+                    steps: 50,
+                    inner_calls: vec![],
+                },
+            )],
+            hints: HashMap::from([(0, vec!["SystemCall".to_string()])]),
+        };
+        let contracts = HashMap::new();
+
+        let trace_dump = TraceDump::new(
+            "tests::test",
+            &executed_code,
+            &ProgramFunctions::from_entry_pcs(
+                vec![
+                    (0, "tests::test".to_string()),
+                    (10, "tests::helper".to_string()),
+                ],
+                20,
+            ),
+            &SierraStatements::from_code_offsets(vec![0, 4, 10, 10]),
+            &mut ContractEntryPoints::new(&contracts),
+        )
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(trace_dump).unwrap(),
+            json!({
+                "test": "tests::test",
+                "trace": [
+                    {
+                        "type": "step",
+                        "pc": 0,
+                        "ap": 10,
+                        "fp": 10,
+                        "hints": ["SystemCall"],
+                        "sierra_statement_idx": 0,
+                        "function": "tests::test"
+                    },
+                    {
+                        "type": "contract_call_start",
+                        "contract": null,
+                        "entry_point": "0x1234",
+                        "steps": 50
+                    },
+                    {
+                        "type": "contract_call_end",
+                        "contract": null,
+                        "entry_point": "0x1234"
+                    },
+                    {
+                        "type": "step",
+                        "pc": 12,
+                        "ap": 15,
+                        "fp": 15,
+                        "hints": [],
+                        "sierra_statement_idx": 3,
+                        "function": "tests::helper"
+                    }
+                ]
+            })
+        );
+    }
+}
@@ -1,12 +1,13 @@
 use cairo_felt::Felt252;
 use cairo_lang_runner::casm_run::MemBuffer;
+use cairo_vm::vm::errors::memory_errors::MemoryError;
 
-pub(crate) fn write_cheatcode_panic(buffer: &mut MemBuffer, panic_data: &[Felt252]) {
-    buffer.write(1).expect("Failed to insert err code");
-    buffer
-        .write(panic_data.len())
-        .expect("Failed to insert panic_data len");
-    buffer
-        .write_data(panic_data.iter())
-        .expect("Failed to insert error in memory");
+/// Writes the result of a failed cheatcode: error code followed by the serialized error
+pub(crate) fn write_cheatcode_error(
+    buffer: &mut MemBuffer,
+    error_data: &[Felt252],
+) -> Result<(), MemoryError> {
+    buffer.write(1)?;
+    buffer.write_data(error_data.iter())?;
+    Ok(())
 }
@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use walkdir::WalkDir;
+
+use crate::tests_crate_root;
+
+const MANIFEST_FILE_NAME: &str = "Scarb.toml";
+
+/// Modification times of Cairo files and manifests in the watched paths
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct WatchedFiles(HashMap<Utf8PathBuf, SystemTime>);
+
+impl WatchedFiles {
+    /// Reads modification times of all Cairo files and manifests in `paths`,
+    /// which can be directories or files, missing paths are ignored
+    pub fn snapshot(paths: &[Utf8PathBuf]) -> Result<Self> {
+        let mut files = HashMap::new();
+        for watched_path in paths.iter().filter(|path| path.exists()) {
+            for entry in WalkDir::new(watched_path) {
+                let entry = entry.with_context(|| {
+                    format!("Failed to read directory at path = {watched_path}")
+                })?;
+                let path = entry.path();
+                let is_watched =
+                    path.extension().unwrap_or_default() == "cairo" || is_manifest(path);
+
+                if path.is_file() && is_watched {
+                    let modified = entry
+                        .metadata()
+                        .ok()
+                        .and_then(|metadata| metadata.modified().ok())
+                        .with_context(|| {
+                            format!("Failed to read modification time of file = {path:?}")
+                        })?;
+                    let path = Utf8Path::from_path(path)
+                        .with_context(|| format!("Failed to convert path = {path:?} to utf-8"))?
+                        .to_path_buf();
+                    files.insert(path, modified);
+                }
+            }
+        }
+        Ok(Self(files))
+    }
+
+    /// Returns sorted paths of files that were added, modified or removed in `newer` snapshot
+    #[must_use]
+    pub fn changed_files(&self, newer: &Self) -> Vec<Utf8PathBuf> {
+        let modified_or_added = newer
+            .0
+            .iter()
+            .filter(|(path, modified)| self.0.get(*path) != Some(modified))
+            .map(|(path, _)| path.clone());
+        let removed = self
+            .0
+            .keys()
+            .filter(|path| !newer.0.contains_key(*path))
+            .cloned();
+
+        let mut changed: Vec<Utf8PathBuf> = modified_or_added.chain(removed).collect();
+        changed.sort();
+        changed
+    }
+}
+
+/// Directories and the manifest watched for changes in a package
+#[must_use]
+pub fn watched_paths(package_path: &Utf8Path) -> Vec<Utf8PathBuf> {
+    vec![
+        package_path.join("src"),
+        package_path.join("tests"),
+        package_path.join(MANIFEST_FILE_NAME),
+    ]
+}
+
+/// Changes in manifests can change dependencies and workspace members,
+/// so the Scarb metadata has to be read again
+#[must_use]
+pub fn is_manifest(path: impl AsRef<std::path::Path>) -> bool {
+    path.as_ref().file_name() == Some(MANIFEST_FILE_NAME.as_ref())
+}
+
+/// Test files of a package that have to be rerun after a change
+#[derive(Debug, PartialEq, Clone)]
+pub enum AffectedTests {
+    /// Sources of the package or of its path dependencies changed, so every test file has to be rerun
+    All,
+    /// Only these test files changed
+    Files(Vec<Utf8PathBuf>),
+    /// No changes in the package
+    None,
+}
+
+/// Maps changed files to the tests of the package located at `package_path`
+///
+/// `dependencies_roots` are roots of the path dependencies of the package,
+/// see `scarb::path_dependencies_roots`.
+#[must_use]
+pub fn affected_tests(
+    package_path: &Utf8Path,
+    lib_path: &Utf8Path,
+    dependencies_roots: &[Utf8PathBuf],
+    changed_files: &[Utf8PathBuf],
+) -> AffectedTests {
+    let in_dependency = |path: &Utf8PathBuf| {
+        dependencies_roots
+            .iter()
+            .any(|dependency_root| path.starts_with(dependency_root))
+    };
+    if changed_files.iter().any(in_dependency) {
+        return AffectedTests::All;
+    }
+
+    let src_path = lib_path.parent().unwrap_or(package_path);
+    let package_files: Vec<&Utf8PathBuf> = changed_files
+        .iter()
+        .filter(|path| path.starts_with(package_path))
+        .collect();
+
+    if package_files.is_empty() {
+        AffectedTests::None
+    } else if package_files.iter().any(|path| path.starts_with(src_path)) {
+        AffectedTests::All
+    } else if let Some(tests_crate_root) = tests_crate_root(package_path) {
+        // Any change in the tests crate, including a removed module, requires compiling it again
+        AffectedTests::Files(vec![tests_crate_root])
+    } else {
+        let existing_files: Vec<Utf8PathBuf> = package_files
+            .into_iter()
+            .filter(|path| path.exists())
+            .cloned()
+            .collect();
+        if existing_files.is_empty() {
+            AffectedTests::None
+        } else {
+            AffectedTests::Files(existing_files)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::fixture::{FileTouch, FileWriteStr, PathChild, PathCopy};
+    use std::time::Duration;
+
+    #[test]
+    fn snapshot_detects_changes() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+            .unwrap();
+        let package_path = Utf8PathBuf::from_path_buf(temp.to_path_buf()).unwrap();
+        let paths = watched_paths(&package_path);
+
+        let before = WatchedFiles::snapshot(&paths).unwrap();
+        assert!(before.changed_files(&before).is_empty());
+
+        temp.child("tests/new_test.cairo").touch().unwrap();
+        // Make sure the modification time differs on filesystems with coarse timestamps
+        std::thread::sleep(Duration::from_millis(10));
+        temp.child("src/lib.cairo")
+            .write_str("fn changed() {}")
+            .unwrap();
+        std::fs::remove_file(temp.child("tests/without_prefix.cairo").path()).unwrap();
+        temp.child("Scarb.toml")
+            .write_str("[package]\nname = \"simple_package\"\nversion = \"0.2.0\"\n")
+            .unwrap();
+
+        let after = WatchedFiles::snapshot(&paths).unwrap();
+        assert_eq!(
+            before.changed_files(&after),
+            vec![
+                package_path.join("Scarb.toml"),
+                package_path.join("src/lib.cairo"),
+                package_path.join("tests/new_test.cairo"),
+                package_path.join("tests/without_prefix.cairo"),
+            ]
+        );
+    }
+
+    #[test]
+    fn affected_tests_for_changes() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+            .unwrap();
+        let package_path = Utf8PathBuf::from_path_buf(temp.to_path_buf()).unwrap();
+        let lib_path = package_path.join("src/lib.cairo");
+
+        assert_eq!(
+            affected_tests(&package_path, &lib_path, &[], &[]),
+            AffectedTests::None
+        );
+        assert_eq!(
+            affected_tests(
+                &package_path,
+                &lib_path,
+                &[],
+                &[
+                    package_path.join("tests/contract.cairo"),
+                    package_path.join("src/hello_starknet.cairo"),
+                ]
+            ),
+            AffectedTests::All
+        );
+        assert_eq!(
+            affected_tests(
+                &package_path,
+                &lib_path,
+                &[],
+                &[
+                    package_path.join("tests/contract.cairo"),
+                    package_path.join("tests/removed.cairo"),
+                ]
+            ),
+            AffectedTests::Files(vec![package_path.join("tests/contract.cairo")])
+        );
+        assert_eq!(
+            affected_tests(
+                &package_path,
+                &lib_path,
+                &[],
+                &[Utf8PathBuf::from("/other_package/src/lib.cairo")]
+            ),
+            AffectedTests::None
+        );
+    }
+
+    #[test]
+    fn affected_tests_for_changes_in_tests_crate() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.copy_from(
+            "tests/data/tests_crate_package",
+            &["**/*.cairo", "**/*.toml"],
+        )
+        .unwrap();
+        let package_path = Utf8PathBuf::from_path_buf(temp.to_path_buf()).unwrap();
+        let lib_path = package_path.join("src/lib.cairo");
+
+        assert_eq!(
+            affected_tests(
+                &package_path,
+                &lib_path,
+                &[],
+                &[package_path.join("tests/utils.cairo")]
+            ),
+            AffectedTests::Files(vec![package_path.join("tests/lib.cairo")])
+        );
+        assert_eq!(
+            affected_tests(
+                &package_path,
+                &lib_path,
+                &[],
+                &[package_path.join("tests/removed.cairo")]
+            ),
+            AffectedTests::Files(vec![package_path.join("tests/lib.cairo")])
+        );
+    }
+
+    #[test]
+    fn affected_tests_for_changes_in_dependencies() {
+        let package_path = Utf8PathBuf::from("/workspace/package");
+        let lib_path = package_path.join("src/lib.cairo");
+        let dependencies_roots = vec![
+            Utf8PathBuf::from("/workspace/dependency"),
+            package_path.join("nested_dependency"),
+        ];
+
+        assert_eq!(
+            affected_tests(
+                &package_path,
+                &lib_path,
+                &dependencies_roots,
+                &[Utf8PathBuf::from("/workspace/dependency/src/lib.cairo")]
+            ),
+            AffectedTests::All
+        );
+        assert_eq!(
+            affected_tests(
+                &package_path,
+                &lib_path,
+                &dependencies_roots,
+                &[package_path.join("nested_dependency/src/lib.cairo")]
+            ),
+            AffectedTests::All
+        );
+        assert_eq!(
+            affected_tests(
+                &package_path,
+                &lib_path,
+                &dependencies_roots,
+                &[Utf8PathBuf::from("/workspace/unrelated/src/lib.cairo")]
+            ),
+            AffectedTests::None
+        );
+    }
+}
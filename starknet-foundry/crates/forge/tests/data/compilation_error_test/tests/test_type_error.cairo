@@ -0,0 +1,5 @@
+#[test]
+fn test_type_error() {
+    let value: felt252 = true;
+    assert(value == 1, 'wrong value');
+}
@@ -0,0 +1,39 @@
+use starknet::ContractAddress;
+
+#[starknet::interface]
+trait ICheatcodeCaller<TContractState> {
+    fn is_contract_deployed(self: @TContractState, address: felt252) -> bool;
+    fn is_contract_deployed_through(
+        self: @TContractState, caller: ContractAddress, address: felt252
+    ) -> bool;
+    fn declare_contract(self: @TContractState, contract: felt252) -> felt252;
+}
+
+// Contract using cheatcodes, directly and through calls to other contracts
+#[starknet::contract]
+mod CheatcodeCaller {
+    use result::ResultTrait;
+    use starknet::ContractAddress;
+    use super::ICheatcodeCallerDispatcher;
+    use super::ICheatcodeCallerDispatcherTrait;
+
+    #[storage]
+    struct Storage {}
+
+    #[external(v0)]
+    impl CheatcodeCallerImpl of super::ICheatcodeCaller<ContractState> {
+        fn is_contract_deployed(self: @ContractState, address: felt252) -> bool {
+            is_deployed(address)
+        }
+
+        fn is_contract_deployed_through(
+            self: @ContractState, caller: ContractAddress, address: felt252
+        ) -> bool {
+            ICheatcodeCallerDispatcher { contract_address: caller }.is_contract_deployed(address)
+        }
+
+        fn declare_contract(self: @ContractState, contract: felt252) -> felt252 {
+            declare(contract).unwrap()
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use starknet::account::Call;
+
+#[starknet::interface]
+trait IEcdsaAccount<TContractState> {
+    fn __validate_deploy__(
+        self: @TContractState, class_hash: felt252, contract_address_salt: felt252, public_key: felt252
+    ) -> felt252;
+    fn __validate_declare__(self: @TContractState, class_hash: felt252) -> felt252;
+    fn __validate__(self: @TContractState, calls: Array<Call>) -> felt252;
+    fn __execute__(ref self: TContractState, calls: Array<Call>) -> Array<Span<felt252>>;
+}
+
+// Account accepting transactions signed with the `(r, s)` Stark signature of the transaction hash
+#[starknet::contract]
+mod EcdsaAccount {
+    use array::ArrayTrait;
+    use array::SpanTrait;
+    use box::BoxTrait;
+    use option::OptionTrait;
+    use starknet::account::Call;
+    use starknet::SyscallResultTrait;
+
+    #[storage]
+    struct Storage {
+        public_key: felt252,
+    }
+
+    #[constructor]
+    fn constructor(ref self: ContractState, public_key: felt252) {
+        self.public_key.write(public_key);
+    }
+
+    #[external(v0)]
+    impl EcdsaAccountImpl of super::IEcdsaAccount<ContractState> {
+        fn __validate_deploy__(
+            self: @ContractState, class_hash: felt252, contract_address_salt: felt252, public_key: felt252
+        ) -> felt252 {
+            self.validate_transaction()
+        }
+
+        fn __validate_declare__(self: @ContractState, class_hash: felt252) -> felt252 {
+            self.validate_transaction()
+        }
+
+        fn __validate__(self: @ContractState, calls: Array<Call>) -> felt252 {
+            self.validate_transaction()
+        }
+
+        fn __execute__(ref self: ContractState, mut calls: Array<Call>) -> Array<Span<felt252>> {
+            let mut results = ArrayTrait::new();
+            loop {
+                match calls.pop_front() {
+                    Option::Some(call) => {
+                        let Call{to, selector, calldata } = call;
+                        let result = starknet::call_contract_syscall(to, selector, calldata.span())
+                            .unwrap_syscall();
+                        results.append(result);
+                    },
+                    Option::None(()) => {
+                        break ();
+                    },
+                };
+            };
+            results
+        }
+    }
+
+    #[generate_trait]
+    impl InternalImpl of InternalTrait {
+        fn validate_transaction(self: @ContractState) -> felt252 {
+            let tx_info = starknet::get_tx_info().unbox();
+            let signature = tx_info.signature;
+            assert(signature.len() == 2, 'INVALID_SIGNATURE_LENGTH');
+            assert(
+                ecdsa::check_ecdsa_signature(
+                    tx_info.transaction_hash, self.public_key.read(), *signature[0], *signature[1]
+                ),
+                'INVALID_SIGNATURE'
+            );
+            starknet::VALIDATED
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use starknet::ClassHash;
+use starknet::ContractAddress;
+
+#[starknet::interface]
+trait IExecutionInfoChecker<TContractState> {
+    fn get_caller_address(self: @TContractState) -> felt252;
+    fn get_block_number(self: @TContractState) -> u64;
+    fn get_block_timestamp(self: @TContractState) -> u64;
+    fn get_constructor_caller_address(self: @TContractState) -> felt252;
+    fn get_caller_address_through(self: @TContractState, checker: ContractAddress) -> felt252;
+    fn get_block_number_through(self: @TContractState, checker: ContractAddress) -> u64;
+    fn get_block_timestamp_through(self: @TContractState, checker: ContractAddress) -> u64;
+    fn get_caller_address_through_library_call(
+        self: @TContractState, class_hash: ClassHash
+    ) -> felt252;
+    fn get_balance_through(self: @TContractState, hello_starknet: ContractAddress) -> felt252;
+    fn deploy_checker(self: @TContractState, class_hash: ClassHash) -> ContractAddress;
+}
+
+#[starknet::interface]
+trait IHelloStarknet<TContractState> {
+    fn get_balance(self: @TContractState) -> felt252;
+}
+
+// Contract returning the execution info it sees, directly and through calls to other contracts
+#[starknet::contract]
+mod ExecutionInfoChecker {
+    use array::ArrayTrait;
+    use result::ResultTrait;
+    use starknet::ClassHash;
+    use starknet::ContractAddress;
+    use starknet::ContractAddressIntoFelt252;
+    use starknet::SyscallResultTrait;
+    use starknet::{get_block_number, get_block_timestamp, get_caller_address};
+    use traits::Into;
+    use super::{
+        IExecutionInfoCheckerDispatcher, IExecutionInfoCheckerDispatcherTrait,
+        IExecutionInfoCheckerLibraryDispatcher, IHelloStarknetDispatcher,
+        IHelloStarknetDispatcherTrait
+    };
+
+    #[storage]
+    struct Storage {
+        constructor_caller_address: ContractAddress,
+    }
+
+    #[constructor]
+    fn constructor(ref self: ContractState) {
+        self.constructor_caller_address.write(get_caller_address());
+    }
+
+    #[external(v0)]
+    impl ExecutionInfoCheckerImpl of super::IExecutionInfoChecker<ContractState> {
+        fn get_caller_address(self: @ContractState) -> felt252 {
+            get_caller_address().into()
+        }
+
+        fn get_block_number(self: @ContractState) -> u64 {
+            get_block_number()
+        }
+
+        fn get_block_timestamp(self: @ContractState) -> u64 {
+            get_block_timestamp()
+        }
+
+        fn get_constructor_caller_address(self: @ContractState) -> felt252 {
+            self.constructor_caller_address.read().into()
+        }
+
+        fn get_caller_address_through(self: @ContractState, checker: ContractAddress) -> felt252 {
+            IExecutionInfoCheckerDispatcher { contract_address: checker }.get_caller_address()
+        }
+
+        fn get_block_number_through(self: @ContractState, checker: ContractAddress) -> u64 {
+            IExecutionInfoCheckerDispatcher { contract_address: checker }.get_block_number()
+        }
+
+        fn get_block_timestamp_through(self: @ContractState, checker: ContractAddress) -> u64 {
+            IExecutionInfoCheckerDispatcher { contract_address: checker }.get_block_timestamp()
+        }
+
+        fn get_caller_address_through_library_call(
+            self: @ContractState, class_hash: ClassHash
+        ) -> felt252 {
+            IExecutionInfoCheckerLibraryDispatcher { class_hash }.get_caller_address()
+        }
+
+        fn get_balance_through(self: @ContractState, hello_starknet: ContractAddress) -> felt252 {
+            IHelloStarknetDispatcher { contract_address: hello_starknet }.get_balance()
+        }
+
+        fn deploy_checker(self: @ContractState, class_hash: ClassHash) -> ContractAddress {
+            let (contract_address, _) = starknet::deploy_syscall(
+                class_hash, 0, ArrayTrait::new().span(), false
+            )
+                .unwrap_syscall();
+            contract_address
+        }
+    }
+}
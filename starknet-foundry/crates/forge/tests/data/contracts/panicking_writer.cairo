@@ -0,0 +1,59 @@
+use starknet::ContractAddress;
+
+#[starknet::interface]
+trait IPanickingWriter<TContractState> {
+    fn get_value(self: @TContractState) -> felt252;
+    fn write_and_panic(ref self: TContractState, value: felt252);
+    fn write_and_call(
+        ref self: TContractState,
+        value: felt252,
+        writer: ContractAddress,
+        selector: felt252,
+        calldata: Array<felt252>
+    ) -> bool;
+}
+
+// Contract writing to its storage before panicking, directly and in the calls to other contracts
+#[starknet::contract]
+mod PanickingWriter {
+    use array::ArrayTrait;
+    use result::ResultTrait;
+    use starknet::ContractAddress;
+
+    #[storage]
+    struct Storage {
+        value: felt252,
+    }
+
+    #[constructor]
+    fn constructor(ref self: ContractState, value: felt252, should_panic: bool) {
+        self.value.write(value);
+        if should_panic {
+            panic_with_felt252('Constructor panicked');
+        }
+    }
+
+    #[external(v0)]
+    impl PanickingWriterImpl of super::IPanickingWriter<ContractState> {
+        fn get_value(self: @ContractState) -> felt252 {
+            self.value.read()
+        }
+
+        fn write_and_panic(ref self: ContractState, value: felt252) {
+            self.value.write(value);
+            panic_with_felt252('Write panicked');
+        }
+
+        // Returns whether the call to the writer succeeded
+        fn write_and_call(
+            ref self: ContractState,
+            value: felt252,
+            writer: ContractAddress,
+            selector: felt252,
+            calldata: Array<felt252>
+        ) -> bool {
+            self.value.write(value);
+            starknet::call_contract_syscall(writer, selector, calldata.span()).is_ok()
+        }
+    }
+}
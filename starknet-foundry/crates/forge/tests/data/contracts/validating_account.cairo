@@ -0,0 +1,79 @@
+use starknet::account::Call;
+
+#[starknet::interface]
+trait IValidatingAccount<TContractState> {
+    fn __validate_deploy__(
+        self: @TContractState, class_hash: felt252, contract_address_salt: felt252, secret: felt252
+    ) -> felt252;
+    fn __validate_declare__(self: @TContractState, class_hash: felt252) -> felt252;
+    fn __validate__(self: @TContractState, calls: Array<Call>) -> felt252;
+    fn __execute__(ref self: TContractState, calls: Array<Call>) -> Array<Span<felt252>>;
+}
+
+// Account accepting transactions signed with `[transaction_hash, secret]`, the secret is set in the constructor
+#[starknet::contract]
+mod ValidatingAccount {
+    use array::ArrayTrait;
+    use array::SpanTrait;
+    use box::BoxTrait;
+    use option::OptionTrait;
+    use starknet::account::Call;
+    use starknet::SyscallResultTrait;
+
+    #[storage]
+    struct Storage {
+        secret: felt252,
+    }
+
+    #[constructor]
+    fn constructor(ref self: ContractState, secret: felt252) {
+        self.secret.write(secret);
+    }
+
+    #[external(v0)]
+    impl ValidatingAccountImpl of super::IValidatingAccount<ContractState> {
+        fn __validate_deploy__(
+            self: @ContractState, class_hash: felt252, contract_address_salt: felt252, secret: felt252
+        ) -> felt252 {
+            self.validate_transaction()
+        }
+
+        fn __validate_declare__(self: @ContractState, class_hash: felt252) -> felt252 {
+            self.validate_transaction()
+        }
+
+        fn __validate__(self: @ContractState, calls: Array<Call>) -> felt252 {
+            self.validate_transaction()
+        }
+
+        fn __execute__(ref self: ContractState, mut calls: Array<Call>) -> Array<Span<felt252>> {
+            let mut results = ArrayTrait::new();
+            loop {
+                match calls.pop_front() {
+                    Option::Some(call) => {
+                        let Call{to, selector, calldata } = call;
+                        let result = starknet::call_contract_syscall(to, selector, calldata.span())
+                            .unwrap_syscall();
+                        results.append(result);
+                    },
+                    Option::None(()) => {
+                        break ();
+                    },
+                };
+            };
+            results
+        }
+    }
+
+    #[generate_trait]
+    impl InternalImpl of InternalTrait {
+        fn validate_transaction(self: @ContractState) -> felt252 {
+            let tx_info = starknet::get_tx_info().unbox();
+            let signature = tx_info.signature;
+            assert(signature.len() == 2, 'INVALID_SIGNATURE_LENGTH');
+            assert(*signature[0] == tx_info.transaction_hash, 'INVALID_TRANSACTION_HASH');
+            assert(*signature[1] == self.secret.read(), 'INVALID_SIGNATURE');
+            starknet::VALIDATED
+        }
+    }
+}
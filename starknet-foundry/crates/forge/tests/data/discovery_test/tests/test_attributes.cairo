@@ -0,0 +1,17 @@
+#[test]
+#[available_gas(100000)]
+fn test_with_gas() {
+    assert(1 == 1, 'simple check');
+}
+
+#[test]
+#[should_panic(expected: ('panic message', ))]
+fn test_should_panic() {
+    panic_with_felt252('panic message');
+}
+
+#[test]
+#[ignore]
+fn test_ignored() {
+    assert(1 == 1, 'simple check');
+}
@@ -0,0 +1 @@
+mod hello_starknet;
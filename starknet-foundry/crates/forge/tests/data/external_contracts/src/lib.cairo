@@ -0,0 +1,3 @@
+fn add(a: felt252, b: felt252) -> felt252 {
+    a + b
+}
@@ -0,0 +1,29 @@
+use array::ArrayTrait;
+use result::ResultTrait;
+use option::OptionTrait;
+use traits::TryInto;
+use starknet::ContractAddress;
+use starknet::Felt252TryIntoContractAddress;
+
+use dependency::hello_starknet::IHelloStarknetDispatcher;
+use dependency::hello_starknet::IHelloStarknetDispatcherTrait;
+
+#[test]
+fn declare_dependency_contract() {
+    let class_hash = declare('dependency::HelloStarknet').unwrap();
+    let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @ArrayTrait::new() };
+    let contract_address = deploy(prepared).unwrap();
+    let contract_address: ContractAddress = contract_address.try_into().unwrap();
+    let dispatcher = IHelloStarknetDispatcher { contract_address };
+
+    dispatcher.increase_balance(100);
+
+    let balance = dispatcher.get_balance();
+    assert(balance == 100, 'balance == 100');
+}
+
+#[test]
+fn declare_dependency_contract_by_short_name() {
+    let class_hash = declare('HelloStarknet').unwrap();
+    assert(class_hash != 0, 'class_hash != 0');
+}
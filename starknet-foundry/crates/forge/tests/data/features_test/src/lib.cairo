@@ -0,0 +1,4 @@
+#[cfg(feature: 'mock_oracle')]
+fn oracle_price() -> felt252 {
+    100
+}
@@ -0,0 +1,16 @@
+#[test]
+fn test_always_compiled() {
+    assert(1 == 1, 'simple check');
+}
+
+#[cfg(feature: 'mock_oracle')]
+#[test]
+fn test_with_mock_oracle() {
+    assert(features_test::oracle_price() == 100, 'wrong price');
+}
+
+#[cfg(network: 'testnet')]
+#[test]
+fn test_on_testnet() {
+    assert(1 == 1, 'simple check');
+}
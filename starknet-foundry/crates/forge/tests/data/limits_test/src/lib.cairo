@@ -0,0 +1,10 @@
+fn count_to(n: felt252) -> felt252 {
+    let mut i = 0;
+    loop {
+        if i == n {
+            break ();
+        }
+        i = i + 1;
+    };
+    i
+}
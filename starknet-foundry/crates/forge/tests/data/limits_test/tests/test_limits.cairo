@@ -0,0 +1,30 @@
+use limits_test::count_to;
+
+#[test]
+fn test_short() {
+    assert(count_to(10) == 10, 'wrong count');
+}
+
+#[test]
+#[max_steps(1000)]
+fn test_step_limit_from_attribute() {
+    assert(count_to(100000) == 100000, 'wrong count');
+}
+
+#[test]
+#[max_steps(1000000)]
+fn test_attribute_overrides_cli_limit() {
+    assert(count_to(1000) == 1000, 'wrong count');
+}
+
+#[test]
+fn test_step_limit_from_cli() {
+    assert(count_to(1000) == 1000, 'wrong count');
+}
+
+#[test]
+#[max_steps(100000000000)]
+#[timeout(1)]
+fn test_timeout() {
+    assert(count_to(1000000000) == 1000000000, 'wrong count');
+}
@@ -0,0 +1 @@
+mod printing_contract;
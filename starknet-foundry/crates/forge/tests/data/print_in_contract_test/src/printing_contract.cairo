@@ -0,0 +1,30 @@
+use starknet::ContractAddress;
+
+#[starknet::interface]
+trait IPrintingContract<TContractState> {
+    fn print_value(self: @TContractState, value: felt252);
+    fn print_and_forward(self: @TContractState, value: felt252, next: ContractAddress);
+}
+
+#[starknet::contract]
+mod PrintingContract {
+    use forge_print::PrintTrait;
+    use starknet::ContractAddress;
+    use super::IPrintingContractDispatcher;
+    use super::IPrintingContractDispatcherTrait;
+
+    #[storage]
+    struct Storage {}
+
+    #[external(v0)]
+    impl PrintingContractImpl of super::IPrintingContract<ContractState> {
+        fn print_value(self: @ContractState, value: felt252) {
+            value.print();
+        }
+
+        fn print_and_forward(self: @ContractState, value: felt252, next: ContractAddress) {
+            value.print();
+            IPrintingContractDispatcher { contract_address: next }.print_value(value + 1);
+        }
+    }
+}
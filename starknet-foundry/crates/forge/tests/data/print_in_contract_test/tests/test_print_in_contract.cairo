@@ -0,0 +1,23 @@
+use array::ArrayTrait;
+use result::ResultTrait;
+use option::OptionTrait;
+use traits::TryInto;
+use starknet::ContractAddress;
+use starknet::Felt252TryIntoContractAddress;
+
+use print_in_contract_test::printing_contract::IPrintingContractDispatcher;
+use print_in_contract_test::printing_contract::IPrintingContractDispatcherTrait;
+
+fn deploy_printing_contract(class_hash: felt252) -> ContractAddress {
+    let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @ArrayTrait::new() };
+    deploy(prepared).unwrap().try_into().unwrap()
+}
+
+#[test]
+fn test_print_in_contract() {
+    let class_hash = declare('PrintingContract').unwrap();
+    let first = deploy_printing_contract(class_hash);
+    let second = deploy_printing_contract(class_hash);
+
+    IPrintingContractDispatcher { contract_address: first }.print_and_forward(123, second);
+}
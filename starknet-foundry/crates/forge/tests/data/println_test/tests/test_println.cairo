@@ -0,0 +1,37 @@
+use array::ArrayTrait;
+use option::Option;
+use result::Result;
+use forge_print::{Format, println};
+
+#[derive(Drop, Format)]
+struct Point {
+    x: u32,
+    y: i64,
+}
+
+#[derive(Drop, Format)]
+enum Shape {
+    Circle: (Point, u128),
+    Empty: (),
+}
+
+#[test]
+fn test_println() {
+    let mut args = ArrayTrait::new();
+    'aaa'.fmt(ref args);
+    true.fmt(ref args);
+    u256 { low: 1, high: 1 }.fmt(ref args);
+    array![1_u8, 2_u8].fmt(ref args);
+    println(array!['felt: {}, bool: {}, ', 'u256: {}, array: {}'], args);
+
+    let mut args = ArrayTrait::new();
+    Shape::Circle((Point { x: 1, y: -2 }, 3)).fmt(ref args);
+    Shape::Empty(()).fmt(ref args);
+    println(array!['{} {}'], args);
+
+    let mut args = ArrayTrait::new();
+    Option::Some(5_u16).fmt(ref args);
+    let result: Result<u8, felt252> = Result::Err('error');
+    result.fmt(ref args);
+    println(array!['{{{}}} {}'], args);
+}
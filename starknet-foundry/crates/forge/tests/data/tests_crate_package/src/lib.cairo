@@ -0,0 +1,7 @@
+fn add(a: felt252, b: felt252) -> felt252 {
+    a + b
+}
+
+fn mul(a: felt252, b: felt252) -> felt252 {
+    a * b
+}
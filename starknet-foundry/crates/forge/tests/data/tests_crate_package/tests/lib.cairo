@@ -0,0 +1,3 @@
+mod utils;
+mod test_add;
+mod test_mul;
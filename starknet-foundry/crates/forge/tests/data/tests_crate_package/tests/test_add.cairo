@@ -0,0 +1,7 @@
+use tests_crate_package::add;
+use super::utils::fixture;
+
+#[test]
+fn test_add() {
+    assert(add(fixture(), fixture()) == 42, 'wrong sum');
+}
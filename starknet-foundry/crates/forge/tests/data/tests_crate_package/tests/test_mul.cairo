@@ -0,0 +1,12 @@
+use tests_crate_package::mul;
+use super::utils::fixture;
+
+#[test]
+fn test_mul() {
+    assert(mul(fixture(), 2) == 42, 'wrong product');
+}
+
+#[test]
+fn test_mul_by_zero() {
+    assert(mul(fixture(), 0) == 0, 'wrong product');
+}
@@ -0,0 +1,3 @@
+fn fixture() -> felt252 {
+    21
+}
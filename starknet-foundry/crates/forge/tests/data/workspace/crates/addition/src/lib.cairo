@@ -0,0 +1,8 @@
+fn add(a: felt252, b: felt252) -> felt252 {
+    a + b
+}
+
+#[test]
+fn test_add() {
+    assert(add(2, 3) == 5, 'add(2, 3) == 5');
+}
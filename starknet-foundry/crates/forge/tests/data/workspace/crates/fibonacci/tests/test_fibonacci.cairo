@@ -0,0 +1,11 @@
+use fibonacci::fib;
+
+#[test]
+fn test_fib_from_tests() {
+    assert(fib(0, 1, 10) == 55, fib(0, 1, 10));
+}
+
+#[test]
+fn test_failing() {
+    assert(fib(0, 1, 10) == 54, 'failing check');
+}
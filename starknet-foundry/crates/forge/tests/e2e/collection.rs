@@ -42,3 +42,141 @@ fn complex_structure() {
         Tests: 16 passed, 0 failed, 0 skipped
         "#});
 }
+
+#[test]
+fn features_and_cfg_items() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/features_test", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    runner()
+        .current_dir(&temp)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"Collected 1 test(s) and 2 test file(s)
+        Running 0 test(s) from src/lib.cairo
+        Running 1 test(s) from tests/test_features.cairo
+        [PASS] test_features::test_features::test_always_compiled
+        Tests: 1 passed, 0 failed, 0 skipped
+        "#});
+
+    runner()
+        .current_dir(&temp)
+        .args(["--features", "mock_oracle", "--cfg", "network=testnet"])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"Collected 3 test(s) and 2 test file(s)
+        Running 0 test(s) from src/lib.cairo
+        Running 3 test(s) from tests/test_features.cairo
+        [PASS] test_features::test_features::test_always_compiled
+        [PASS] test_features::test_features::test_with_mock_oracle
+        [PASS] test_features::test_features::test_on_testnet
+        Tests: 3 passed, 0 failed, 0 skipped
+        "#});
+}
+
+#[test]
+fn compilation_errors() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from(
+        "tests/data/compilation_error_test",
+        &["**/*.cairo", "**/*.toml"],
+    )
+    .unwrap();
+
+    runner()
+        .current_dir(&temp)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"error: [..]
+         --> [..]/tests/test_type_error.cairo:3:[..]
+        [ERROR] Failed to compile tests, found 1 error(s)
+        "#});
+}
+
+#[test]
+fn list_in_json_format() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/discovery_test", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    runner()
+        .current_dir(&temp)
+        .args(["--list", "--format", "json", "--skip", "test_ignored"])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"[
+          {
+            "package": "discovery_test",
+            "path": "test_attributes::test_attributes::test_with_gas",
+            "location": {
+              "file_path": "[..]/tests/test_attributes.cairo",
+              "start_line": 1,
+              "end_line": 5
+            },
+            "attributes": {
+              "available_gas": 100000,
+              "max_steps": null,
+              "timeout": null,
+              "should_panic": null,
+              "ignored": false
+            }
+          },
+          {
+            "package": "discovery_test",
+            "path": "test_attributes::test_attributes::test_should_panic",
+            "location": {
+              "file_path": "[..]/tests/test_attributes.cairo",
+              "start_line": 7,
+              "end_line": 11
+            },
+            "attributes": {
+              "available_gas": null,
+              "max_steps": null,
+              "timeout": null,
+              "should_panic": {
+                "exact": [
+                  "8903707727067478891290643490661"
+                ]
+              },
+              "ignored": false
+            }
+          }
+        ]
+        "#});
+}
+
+#[test]
+fn list_tests_crate_in_json_format() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from(
+        "tests/data/tests_crate_package",
+        &["**/*.cairo", "**/*.toml"],
+    )
+    .unwrap();
+
+    runner()
+        .current_dir(&temp)
+        .args(["--list", "--format", "json", "--skip", "test_mul"])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"[
+          {
+            "package": "tests_crate_package",
+            "path": "tests::test_add::test_add",
+            "location": {
+              "file_path": "[..]/tests/test_add.cairo",
+              "start_line": 4,
+              "end_line": 7
+            },
+            "attributes": {
+              "available_gas": null,
+              "max_steps": null,
+              "timeout": null,
+              "should_panic": null,
+              "ignored": false
+            }
+          }
+        ]
+        "#});
+}
@@ -1,3 +1,4 @@
 mod collection;
 pub(crate) mod common;
 mod running;
+mod workspaces;
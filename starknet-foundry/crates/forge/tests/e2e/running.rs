@@ -14,7 +14,7 @@ fn simple_package() {
     snapbox
         .current_dir(&temp)
         .assert()
-        .success()
+        .failure()
         .stdout_matches(indoc! {r#"Collected 11 test(s) and 5 test file(s)
         Running 1 test(s) from src/lib.cairo
         [PASS] src::test_fib
@@ -144,6 +144,78 @@ fn with_print() {
         "#});
 }
 
+#[test]
+fn with_println() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/println_test", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"Collected 1 test(s) and 2 test file(s)
+        Running 0 test(s) from src/lib.cairo
+        Running 1 test(s) from tests/test_println.cairo
+        felt: 6381921, bool: true, u256: 340282366920938463463374607431768211457, array: [1, 2]
+        Shape::Circle((Point { x: 1, y: -2 }, 3)) Shape::Empty
+        {Option::Some(5)} Result::Err(435711799154)
+        [PASS] test_println::test_println::test_println
+        Tests: 1 passed, 0 failed, 0 skipped
+        "#});
+}
+
+#[test]
+fn with_print_in_contracts() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from(
+        "tests/data/print_in_contract_test",
+        &["**/*.cairo", "**/*.toml"],
+    )
+    .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"Collected 1 test(s) and 2 test file(s)
+        Running 0 test(s) from src/lib.cairo
+        Running 1 test(s) from tests/test_print_in_contract.cairo
+        original value: [123], converted to a string: [{]
+        original value: [124], converted to a string: [|]
+        [PASS] test_print_in_contract::test_print_in_contract::test_print_in_contract
+        Tests: 1 passed, 0 failed, 0 skipped
+        "#});
+}
+
+#[test]
+fn with_dependency_contracts() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from(
+        "tests/data/external_contracts",
+        &["**/*.cairo", "**/*.toml"],
+    )
+    .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"Collected 2 test(s) and 2 test file(s)
+        Running 0 test(s) from src/lib.cairo
+        Running 2 test(s) from tests/test_dependency_contract.cairo
+        [PASS] test_dependency_contract::test_dependency_contract::declare_dependency_contract
+        [PASS] test_dependency_contract::test_dependency_contract::declare_dependency_contract_by_short_name
+        Tests: 2 passed, 0 failed, 0 skipped
+        "#});
+}
+
 #[test]
 fn with_panic_data_decoding() {
     let temp = assert_fs::TempDir::new().unwrap();
@@ -158,7 +230,7 @@ fn with_panic_data_decoding() {
     snapbox
         .current_dir(&temp)
         .assert()
-        .success()
+        .failure()
         .stdout_matches(indoc! {r#"Collected 4 test(s) and 2 test file(s)
         Running 0 test(s) from src/lib.cairo
         Running 4 test(s) from tests/test_panic_decoding.cairo
@@ -215,7 +287,7 @@ fn with_exit_first() {
     snapbox
         .current_dir(&temp)
         .assert()
-        .success()
+        .failure()
         .stdout_matches(indoc! {r#"Collected 11 test(s) and 5 test file(s)
         Running 1 test(s) from src/lib.cairo
         [PASS] src::test_fib
@@ -251,7 +323,7 @@ fn with_exit_first_flag() {
     snapbox
         .current_dir(&temp)
         .assert()
-        .success()
+        .failure()
         .stdout_matches(indoc! {r#"Collected 11 test(s) and 5 test file(s)
         Running 1 test(s) from src/lib.cairo
         [PASS] src::test_fib
@@ -309,7 +381,7 @@ fn exit_first_flag_takes_precedence() {
         .current_dir(&temp)
         .arg("--exit-first")
         .assert()
-        .success()
+        .failure()
         .stdout_matches(indoc! {r#"Collected 11 test(s) and 5 test file(s)
         Running 1 test(s) from src/lib.cairo
         [PASS] src::test_fib
@@ -333,3 +405,338 @@ fn exit_first_flag_takes_precedence() {
         Tests: 8 passed, 1 failed, 2 skipped
         "#});
 }
+
+#[test]
+fn with_failing_scarb_build() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+    let lib_file = temp.child("src/lib.cairo");
+    lib_file
+        .write_str(indoc!(
+            r#"
+            fn broken() -> felt252 {
+                'a' +
+            }
+            "#
+        ))
+        .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"[ERROR] Scarb build did not succeed:
+        ...
+        "#});
+}
+
+#[test]
+fn with_rerun_failed() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    runner().current_dir(&temp).assert().failure();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .arg("--rerun-failed")
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"Collected 2 test(s) and 5 test file(s)
+        Running 0 test(s) from src/lib.cairo
+        Running 0 test(s) from tests/contract.cairo
+        Running 0 test(s) from tests/ext_function_test.cairo
+        Running 2 test(s) from tests/test_simple.cairo
+        [FAIL] test_simple::test_simple::test_failing
+        
+        Failure data:
+            original value: [8111420071579136082810415440747], converted to a string: [failing check]
+        
+        [FAIL] test_simple::test_simple::test_another_failing
+        
+        Failure data:
+            original value: [8111420071579136082810415440747], converted to a string: [failing check]
+        
+        Running 0 test(s) from tests/without_prefix.cairo
+        Tests: 0 passed, 2 failed, 0 skipped
+        "#});
+}
+
+#[test]
+fn with_rerun_failed_without_previous_failures() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .arg("--rerun-failed")
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"Collected 11 test(s) and 5 test file(s)
+        ...
+        Tests: 9 passed, 2 failed, 0 skipped
+        "#});
+}
+
+#[test]
+fn with_multiple_filters_and_skip() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .args(["ext_function_test", "test_two", "--skip", "and"])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"Collected 3 test(s) and 5 test file(s)
+        Running 0 test(s) from src/lib.cairo
+        Running 0 test(s) from tests/contract.cairo
+        Running 2 test(s) from tests/ext_function_test.cairo
+        [PASS] ext_function_test::ext_function_test::test_my_test
+        [PASS] ext_function_test::ext_function_test::test_simple
+        Running 1 test(s) from tests/test_simple.cairo
+        [PASS] test_simple::test_simple::test_two
+        Running 0 test(s) from tests/without_prefix.cairo
+        Tests: 3 passed, 0 failed, 0 skipped
+        "#});
+}
+
+#[test]
+fn with_regex_filter() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .args(["--regex", r"^test_simple::\w+::test_simple\d?$"])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"Collected 2 test(s) and 5 test file(s)
+        Running 0 test(s) from src/lib.cairo
+        Running 0 test(s) from tests/contract.cairo
+        Running 0 test(s) from tests/ext_function_test.cairo
+        Running 2 test(s) from tests/test_simple.cairo
+        [PASS] test_simple::test_simple::test_simple
+        [PASS] test_simple::test_simple::test_simple2
+        Running 0 test(s) from tests/without_prefix.cairo
+        Tests: 2 passed, 0 failed, 0 skipped
+        "#});
+}
+
+#[test]
+fn with_list() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .args(["--list", "--glob", "*::test_simple::*"])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"test_simple::test_simple::test_simple
+        test_simple::test_simple::test_simple2
+        test_simple::test_simple::test_two
+        test_simple::test_simple::test_two_and_two
+        test_simple::test_simple::test_failing
+        test_simple::test_simple::test_another_failing
+        "#});
+}
+
+#[test]
+fn with_coverage() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .args(["--coverage", "call_and_invoke"])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"Collected 1 test(s) and 5 test file(s)
+        Running 0 test(s) from src/lib.cairo
+        Running 1 test(s) from tests/contract.cairo
+        [PASS] contract::contract::call_and_invoke
+        Running 0 test(s) from tests/ext_function_test.cairo
+        Running 0 test(s) from tests/test_simple.cairo
+        Running 0 test(s) from tests/without_prefix.cairo
+        Tests: 1 passed, 0 failed, 0 skipped
+        Coverage report saved to [..]/coverage/lcov.info
+        "#});
+
+    let report = std::fs::read_to_string(temp.child("coverage/lcov.info")).unwrap();
+    assert!(report.contains(
+        "FNDA:1,simple_package::hello_starknet::HelloStarknet::IHelloStarknetImpl::increase_balance"
+    ));
+    assert!(report.contains(
+        "FNDA:2,simple_package::hello_starknet::HelloStarknet::IHelloStarknetImpl::get_balance"
+    ));
+    assert!(report.contains(
+        "FNDA:0,simple_package::hello_starknet::HelloStarknet::IHelloStarknetImpl::do_a_panic"
+    ));
+}
+
+#[test]
+fn with_profiler() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .args(["--profiler", "contract::contract::call_and_invoke"])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"Collected 1 test(s) and 5 test file(s)
+        Running 0 test(s) from src/lib.cairo
+        Running 1 test(s) from tests/contract.cairo
+        [PASS] contract::contract::call_and_invoke
+        Profile of contract::contract::call_and_invoke saved to [..]/target/snforge/profile/contract__contract__call_and_invoke.folded and [..]/target/snforge/profile/contract__contract__call_and_invoke.pb
+        Running 0 test(s) from tests/ext_function_test.cairo
+        Running 0 test(s) from tests/test_simple.cairo
+        Running 0 test(s) from tests/without_prefix.cairo
+        Tests: 1 passed, 0 failed, 0 skipped
+        "#});
+
+    let folded = std::fs::read_to_string(
+        temp.child("target/snforge/profile/contract__contract__call_and_invoke.folded"),
+    )
+    .unwrap();
+    assert!(folded.lines().any(|line| line.contains("get_balance")));
+    assert!(temp
+        .child("target/snforge/profile/contract__contract__call_and_invoke.pb")
+        .path()
+        .exists());
+}
+
+#[test]
+fn with_trace() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/simple_package", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .args(["--trace", "contract::contract::call_and_invoke"])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"Collected 1 test(s) and 5 test file(s)
+        Running 0 test(s) from src/lib.cairo
+        Running 1 test(s) from tests/contract.cairo
+        [PASS] contract::contract::call_and_invoke
+        Trace of contract::contract::call_and_invoke saved to [..]/target/snforge/trace/contract__contract__call_and_invoke.json
+        Running 0 test(s) from tests/ext_function_test.cairo
+        Running 0 test(s) from tests/test_simple.cairo
+        Running 0 test(s) from tests/without_prefix.cairo
+        Tests: 1 passed, 0 failed, 0 skipped
+        "#});
+
+    let trace: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(
+            temp.child("target/snforge/trace/contract__contract__call_and_invoke.json"),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let items = trace["trace"].as_array().unwrap();
+    let count_items = |item_type: &str| {
+        items
+            .iter()
+            .filter(|item| item["type"] == item_type)
+            .count()
+    };
+
+    assert_eq!(trace["test"], "contract::contract::call_and_invoke");
+    assert!(count_items("step") > 0);
+    // deploy and three calls of the contract
+    assert_eq!(
+        count_items("contract_call_start"),
+        count_items("contract_call_end")
+    );
+    assert!(count_items("contract_call_start") >= 4);
+}
+
+#[test]
+fn with_test_limits() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/limits_test", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .arg("--max-steps")
+        .arg("5000")
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"Collected 5 test(s) and 2 test file(s)
+        Running 0 test(s) from src/lib.cairo
+        Running 5 test(s) from tests/test_limits.cairo
+        [PASS] test_limits::test_limits::test_short
+        [INTERRUPTED] test_limits::test_limits::test_step_limit_from_attribute
+
+        Test exceeded the limit of 1000 steps
+
+        [PASS] test_limits::test_limits::test_attribute_overrides_cli_limit
+        [INTERRUPTED] test_limits::test_limits::test_step_limit_from_cli
+
+        Test exceeded the limit of 5000 steps
+
+        [INTERRUPTED] test_limits::test_limits::test_timeout
+
+        Test exceeded the timeout of 1 second(s)
+
+        Tests: 2 passed, 3 failed, 0 skipped
+        "#});
+}
+
+#[test]
+fn with_tests_crate() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from(
+        "tests/data/tests_crate_package",
+        &["**/*.cairo", "**/*.toml"],
+    )
+    .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"Collected 3 test(s) and 3 test file(s)
+        Running 0 test(s) from src/lib.cairo
+        Running 1 test(s) from tests/test_add.cairo
+        [PASS] tests::test_add::test_add
+        Running 2 test(s) from tests/test_mul.cairo
+        [PASS] tests::test_mul::test_mul
+        [PASS] tests::test_mul::test_mul_by_zero
+        Tests: 3 passed, 0 failed, 0 skipped
+        "#});
+}
@@ -0,0 +1,126 @@
+use assert_fs::fixture::PathCopy;
+use indoc::indoc;
+
+use crate::e2e::common::runner::runner;
+
+#[test]
+fn root_workspace_without_flags_runs_all_packages() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/workspace", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"Testing package addition
+        Collected 1 test(s) and 1 test file(s)
+        Running 1 test(s) from src/lib.cairo
+        [PASS] src::test_add
+        Tests: 1 passed, 0 failed, 0 skipped
+        Testing package fibonacci
+        Collected 3 test(s) and 2 test file(s)
+        Running 1 test(s) from src/lib.cairo
+        [PASS] src::test_fib
+        Running 2 test(s) from tests/test_fibonacci.cairo
+        [PASS] test_fibonacci::test_fibonacci::test_fib_from_tests
+        [FAIL] test_fibonacci::test_fibonacci::test_failing
+        
+        Failure data:
+            original value: [8111420071579136082810415440747], converted to a string: [failing check]
+        
+        Tests: 2 passed, 1 failed, 0 skipped
+        Tests summary for all packages
+            addition: 1 passed, 0 failed, 0 skipped
+            fibonacci: 2 passed, 1 failed, 0 skipped
+        Tests: 3 passed, 1 failed, 0 skipped
+        "#});
+}
+
+#[test]
+fn with_package_flag() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/workspace", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .args(["--package", "addition"])
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"Collected 1 test(s) and 1 test file(s)
+        Running 1 test(s) from src/lib.cairo
+        [PASS] src::test_add
+        Tests: 1 passed, 0 failed, 0 skipped
+        "#});
+}
+
+#[test]
+fn with_nonexistent_package_flag() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/workspace", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(&temp)
+        .args(["--package", "nonexistent"])
+        .assert()
+        .failure()
+        .stdout_matches(
+            indoc! {r#"[ERROR] Failed to find package = nonexistent in the workspace members
+            "#},
+        );
+}
+
+#[test]
+fn inside_package_directory_without_flags_runs_all_packages() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/workspace", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(temp.join("crates/addition"))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"Testing package addition
+        ...
+        Testing package fibonacci
+        ...
+        Tests summary for all packages
+            addition: 1 passed, 0 failed, 0 skipped
+            fibonacci: 2 passed, 1 failed, 0 skipped
+        Tests: 3 passed, 1 failed, 0 skipped
+        "#});
+}
+
+#[test]
+fn with_workspace_flag_inside_package_directory() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.copy_from("tests/data/workspace", &["**/*.cairo", "**/*.toml"])
+        .unwrap();
+
+    let snapbox = runner();
+
+    snapbox
+        .current_dir(temp.join("crates/addition"))
+        .arg("--workspace")
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"Testing package addition
+        ...
+        Testing package fibonacci
+        ...
+        Tests summary for all packages
+            addition: 1 passed, 0 failed, 0 skipped
+            fibonacci: 2 passed, 1 failed, 0 skipped
+        Tests: 3 passed, 1 failed, 0 skipped
+        "#});
+}
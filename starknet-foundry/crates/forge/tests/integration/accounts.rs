@@ -0,0 +1,187 @@
+use crate::assert_passed;
+use crate::integration::common::corelib::{corelib, predeployed_contracts};
+use crate::integration::common::runner::Contract;
+use crate::integration::common::runner::TestCase;
+use camino::Utf8PathBuf;
+use forge::run;
+use indoc::indoc;
+use std::path::Path;
+
+const DEPLOY_ACCOUNT: &str = indoc!(
+    r#"
+    use array::ArrayTrait;
+    use option::OptionTrait;
+    use result::ResultTrait;
+    use traits::TryInto;
+    use starknet::account::Call;
+    use cheatcodes::{
+        AccountTransaction, DeployAccountTransaction, DeclareTransaction, InvokeTransaction,
+        PreparedContract, TransactionError
+    };
+
+    const SECRET: felt252 = 'secret';
+    const INCREASE_BALANCE_SELECTOR: felt252 = 0x362398bec32bc0ebb411203221a35a0301193a96f317ebe5e40be9f60d15320;
+    const GET_BALANCE_SELECTOR: felt252 = 0x39e11d48192e4333233c7eb19d10ad67c362bb28580c604d67884c85da39695;
+
+    fn deploy_validating_account() -> felt252 {
+        let class_hash = declare('ValidatingAccount').unwrap();
+        let transaction = AccountTransaction::DeployAccount(
+            DeployAccountTransaction {
+                class_hash: class_hash, contract_address_salt: 1, constructor_calldata: array![SECRET]
+            }
+        );
+        let hash = transaction_hash(@transaction);
+        let result = execute_transaction(transaction, array![hash, SECRET]).unwrap();
+
+        let account_address = *result[0];
+        assert(
+            account_address == deploy_account_address(class_hash, 1, @array![SECRET]),
+            'Wrong account address'
+        );
+        account_address
+    }
+
+    fn deploy_hello_starknet() -> felt252 {
+        let class_hash = declare('HelloStarknet').unwrap();
+        let prepared = PreparedContract {
+            class_hash: class_hash, constructor_calldata: @ArrayTrait::new()
+        };
+        deploy(prepared).unwrap()
+    }
+
+    fn invoke(
+        account_address: felt252, contract_address: felt252, selector: felt252, calldata: Array<felt252>, secret: felt252
+    ) -> Result::<Array<felt252>, TransactionError> {
+        let calls = array![
+            Call { to: contract_address.try_into().unwrap(), selector: selector, calldata: calldata }
+        ];
+        let transaction = AccountTransaction::Invoke(
+            InvokeTransaction { sender_address: account_address, calls: calls }
+        );
+        let hash = transaction_hash(@transaction);
+        execute_transaction(transaction, array![hash, secret])
+    }
+"#
+);
+
+fn contracts() -> Vec<Contract> {
+    vec![
+        Contract::from_code_path(
+            "ValidatingAccount".to_string(),
+            Path::new("tests/data/contracts/validating_account.cairo"),
+        )
+        .unwrap(),
+        Contract::from_code_path(
+            "HelloStarknet".to_string(),
+            Path::new("tests/data/contracts/hello_starknet.cairo"),
+        )
+        .unwrap(),
+    ]
+}
+
+fn run_test_with_accounts(test_code: &str) {
+    let test = TestCase::from(&format!("{DEPLOY_ACCOUNT}{test_code}"), contracts()).unwrap();
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
+
+#[test]
+fn invoke_through_validating_account() {
+    run_test_with_accounts(indoc!(
+        r#"
+        #[test]
+        fn test_invoke() {
+            let account_address = deploy_validating_account();
+            let contract_address = deploy_hello_starknet();
+
+            invoke(account_address, contract_address, INCREASE_BALANCE_SELECTOR, array![42], SECRET).unwrap();
+            let result = invoke(account_address, contract_address, GET_BALANCE_SELECTOR, array![], SECRET).unwrap();
+
+            // Results of the calls serialized by `__execute__`: number of calls, length of the result, balance
+            assert(result.len() == 3, 'Wrong result length');
+            assert(*result[2] == 42, 'Wrong balance');
+            assert(get_nonce(account_address) == 3, 'Wrong nonce');
+        }
+    "#
+    ));
+}
+
+#[test]
+fn declare_through_validating_account() {
+    run_test_with_accounts(indoc!(
+        r#"
+        #[test]
+        fn test_declare() {
+            let account_address = deploy_validating_account();
+
+            let transaction = AccountTransaction::Declare(
+                DeclareTransaction { sender_address: account_address, contract: 'HelloStarknet' }
+            );
+            let hash = transaction_hash(@transaction);
+            let result = execute_transaction(transaction, array![hash, SECRET]).unwrap();
+
+            let prepared = PreparedContract {
+                class_hash: *result[0], constructor_calldata: @ArrayTrait::new()
+            };
+            deploy(prepared).unwrap();
+        }
+    "#
+    ));
+}
+
+#[test]
+fn invalid_signature_fails_validation() {
+    run_test_with_accounts(indoc!(
+        r#"
+        #[test]
+        fn test_invalid_signature() {
+            let account_address = deploy_validating_account();
+            let contract_address = deploy_hello_starknet();
+
+            match invoke(account_address, contract_address, INCREASE_BALANCE_SELECTOR, array![42], 'wrong') {
+                Result::Ok(_) => panic_with_felt252('Should fail validation'),
+                Result::Err(TransactionError::ValidationFailed(x)) => {
+                    assert(*x.panic_data.at(0) == 'INVALID_SIGNATURE', 'Wrong panic data');
+                },
+                Result::Err(_) => panic_with_felt252('Wrong transaction error'),
+            }
+            // Rejected transactions do not change the nonce
+            assert(get_nonce(account_address) == 1, 'Wrong nonce');
+        }
+    "#
+    ));
+}
+
+#[test]
+fn failing_execution_reverts_transaction() {
+    run_test_with_accounts(indoc!(
+        r#"
+        const DO_A_PANIC_SELECTOR: felt252 = 0x3c90fa28d76cca3d1f524541612bd9b88cc064457761e09c93d034edf542da4;
+
+        #[test]
+        fn test_reverted() {
+            let account_address = deploy_validating_account();
+            let contract_address = deploy_hello_starknet();
+
+            match invoke(account_address, contract_address, DO_A_PANIC_SELECTOR, array![], SECRET) {
+                Result::Ok(_) => panic_with_felt252('Should be reverted'),
+                Result::Err(TransactionError::ExecutionReverted(_)) => (),
+                Result::Err(_) => panic_with_felt252('Wrong transaction error'),
+            }
+            // Reverted transactions are included, so the nonce is increased
+            assert(get_nonce(account_address) == 2, 'Wrong nonce');
+        }
+    "#
+    ));
+}
@@ -0,0 +1,119 @@
+use crate::integration::common::corelib::{corelib, predeployed_contracts};
+use crate::integration::common::runner::Contract;
+use crate::{assert_case_output_contains, assert_failed, assert_passed, test_case};
+use camino::Utf8PathBuf;
+use forge::run;
+use indoc::indoc;
+use std::path::Path;
+
+const DEPLOY_CHEATCODE_CALLER: &str = indoc!(
+    r#"
+    use array::ArrayTrait;
+    use result::ResultTrait;
+    use option::OptionTrait;
+    use traits::Into;
+    use traits::TryInto;
+    use starknet::ContractAddress;
+    use starknet::ContractAddressIntoFelt252;
+    use starknet::Felt252TryIntoContractAddress;
+    use cheatcodes::PreparedContract;
+
+    #[starknet::interface]
+    trait ICheatcodeCaller<TContractState> {
+        fn is_contract_deployed(self: @TContractState, address: felt252) -> bool;
+        fn is_contract_deployed_through(
+            self: @TContractState, caller: ContractAddress, address: felt252
+        ) -> bool;
+        fn declare_contract(self: @TContractState, contract: felt252) -> felt252;
+    }
+
+    fn deploy_cheatcode_caller() -> ContractAddress {
+        let class_hash = declare('CheatcodeCaller').unwrap();
+        let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @ArrayTrait::new() };
+        deploy(prepared).unwrap().try_into().unwrap()
+    }
+"#
+);
+
+fn cheatcode_caller() -> Contract {
+    Contract::from_code_path(
+        "CheatcodeCaller".to_string(),
+        Path::new("tests/data/contracts/cheatcode_caller.cairo"),
+    )
+    .unwrap()
+}
+
+#[test]
+fn cheatcodes_in_nested_calls() {
+    let test = test_case!(
+        &format!(
+            "{DEPLOY_CHEATCODE_CALLER}{}",
+            indoc!(
+                r#"
+        #[test]
+        fn test_cheatcodes_in_nested_calls() {
+            let first = deploy_cheatcode_caller();
+            let second = deploy_cheatcode_caller();
+            let dispatcher = ICheatcodeCallerDispatcher { contract_address: first };
+
+            assert(dispatcher.is_contract_deployed(second.into()), 'Should be deployed');
+            assert(!dispatcher.is_contract_deployed(123), 'Should not be deployed');
+            assert(dispatcher.is_contract_deployed_through(second, first.into()), 'Should be deployed');
+            assert(!dispatcher.is_contract_deployed_through(second, 123), 'Should not be deployed');
+        }
+    "#
+            )
+        ),
+        cheatcode_caller()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
+
+#[test]
+fn test_only_cheatcodes_fail_in_contracts() {
+    let test = test_case!(
+        &format!(
+            "{DEPLOY_CHEATCODE_CALLER}{}",
+            indoc!(
+                r#"
+        #[test]
+        fn test_declare_in_contract() {
+            let contract_address = deploy_cheatcode_caller();
+            ICheatcodeCallerDispatcher { contract_address }.declare_contract('CheatcodeCaller');
+        }
+    "#
+            )
+        ),
+        cheatcode_caller()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_failed!(result);
+    assert_case_output_contains!(
+        result,
+        "test_declare_in_contract",
+        "Cheatcode declare can only be used in tests"
+    );
+}
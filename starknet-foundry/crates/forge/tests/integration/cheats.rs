@@ -0,0 +1,292 @@
+use crate::integration::common::corelib::{corelib, predeployed_contracts};
+use crate::integration::common::runner::Contract;
+use crate::{assert_passed, test_case};
+use camino::Utf8PathBuf;
+use forge::run;
+use indoc::indoc;
+use std::path::Path;
+
+const DEPLOY_EXECUTION_INFO_CHECKER: &str = indoc!(
+    r#"
+    use array::ArrayTrait;
+    use result::ResultTrait;
+    use option::OptionTrait;
+    use traits::Into;
+    use traits::TryInto;
+    use starknet::ClassHash;
+    use starknet::ContractAddress;
+    use starknet::ContractAddressIntoFelt252;
+    use starknet::Felt252TryIntoContractAddress;
+    use starknet::Felt252TryIntoClassHash;
+    use cheatcodes::PreparedContract;
+
+    const TEST_ACCOUNT_ADDRESS: felt252 = 0x101;
+
+    #[starknet::interface]
+    trait IExecutionInfoChecker<TContractState> {
+        fn get_caller_address(self: @TContractState) -> felt252;
+        fn get_block_number(self: @TContractState) -> u64;
+        fn get_block_timestamp(self: @TContractState) -> u64;
+        fn get_constructor_caller_address(self: @TContractState) -> felt252;
+        fn get_caller_address_through(self: @TContractState, checker: ContractAddress) -> felt252;
+        fn get_block_number_through(self: @TContractState, checker: ContractAddress) -> u64;
+        fn get_block_timestamp_through(self: @TContractState, checker: ContractAddress) -> u64;
+        fn get_caller_address_through_library_call(
+            self: @TContractState, class_hash: ClassHash
+        ) -> felt252;
+        fn get_balance_through(self: @TContractState, hello_starknet: ContractAddress) -> felt252;
+        fn deploy_checker(self: @TContractState, class_hash: ClassHash) -> ContractAddress;
+    }
+
+    fn deploy_execution_info_checker() -> ContractAddress {
+        let class_hash = declare('ExecutionInfoChecker').unwrap();
+        let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @ArrayTrait::new() };
+        deploy(prepared).unwrap().try_into().unwrap()
+    }
+"#
+);
+
+fn execution_info_checker() -> Contract {
+    Contract::from_code_path(
+        "ExecutionInfoChecker".to_string(),
+        Path::new("tests/data/contracts/execution_info_checker.cairo"),
+    )
+    .unwrap()
+}
+
+#[test]
+fn prank_in_nested_calls() {
+    let test = test_case!(
+        &format!(
+            "{DEPLOY_EXECUTION_INFO_CHECKER}{}",
+            indoc!(
+                r#"
+        #[test]
+        fn test_prank_in_nested_calls() {
+            let first = deploy_execution_info_checker();
+            let second = deploy_execution_info_checker();
+            let dispatcher = IExecutionInfoCheckerDispatcher { contract_address: first };
+
+            start_prank(second.into(), 123);
+            assert(dispatcher.get_caller_address_through(second) == 123, 'Wrong caller address');
+            assert(dispatcher.get_caller_address() == TEST_ACCOUNT_ADDRESS, 'Wrong caller address');
+
+            stop_prank(second.into());
+            assert(dispatcher.get_caller_address_through(second) == first.into(), 'Wrong caller address');
+        }
+    "#
+            )
+        ),
+        execution_info_checker()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
+
+#[test]
+fn prank_in_library_calls_and_constructors() {
+    let test = test_case!(
+        &format!(
+            "{DEPLOY_EXECUTION_INFO_CHECKER}{}",
+            indoc!(
+                r#"
+        #[test]
+        fn test_prank_in_library_calls() {
+            let contract_address = deploy_execution_info_checker();
+            let class_hash = get_class_hash(contract_address.into()).try_into().unwrap();
+            let dispatcher = IExecutionInfoCheckerDispatcher { contract_address };
+
+            start_prank(contract_address.into(), 123);
+            assert(dispatcher.get_caller_address_through_library_call(class_hash) == 123, 'Wrong caller address');
+        }
+
+        #[test]
+        fn test_prank_in_constructor() {
+            let class_hash = declare('ExecutionInfoChecker').unwrap();
+            let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @ArrayTrait::new() };
+            let contract_address = precalculate_address(@prepared);
+
+            start_prank(contract_address, 123);
+            assert(deploy(prepared).unwrap() == contract_address, 'Wrong contract address');
+            let dispatcher = IExecutionInfoCheckerDispatcher { contract_address: contract_address.try_into().unwrap() };
+            assert(dispatcher.get_constructor_caller_address() == 123, 'Wrong caller address');
+        }
+
+        #[test]
+        fn test_prank_contract_deployed_by_contract() {
+            let first = deploy_execution_info_checker();
+            let class_hash = get_class_hash(first.into()).try_into().unwrap();
+            let dispatcher = IExecutionInfoCheckerDispatcher { contract_address: first };
+            let second = dispatcher.deploy_checker(class_hash);
+            let second_dispatcher = IExecutionInfoCheckerDispatcher { contract_address: second };
+            assert(second_dispatcher.get_constructor_caller_address() == first.into(), 'Wrong caller address');
+
+            start_prank(second.into(), 123);
+            assert(dispatcher.get_caller_address_through(second) == 123, 'Wrong caller address');
+        }
+    "#
+            )
+        ),
+        execution_info_checker()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
+
+#[test]
+fn roll_in_nested_calls() {
+    let test = test_case!(
+        &format!(
+            "{DEPLOY_EXECUTION_INFO_CHECKER}{}",
+            indoc!(
+                r#"
+        #[test]
+        fn test_roll_in_nested_calls() {
+            let first = deploy_execution_info_checker();
+            let second = deploy_execution_info_checker();
+            let dispatcher = IExecutionInfoCheckerDispatcher { contract_address: first };
+            let block_number = dispatcher.get_block_number();
+
+            start_roll(second.into(), 234);
+            assert(dispatcher.get_block_number_through(second) == 234, 'Wrong block number');
+            assert(dispatcher.get_block_number() == block_number, 'Wrong block number');
+
+            stop_roll(second.into());
+            assert(dispatcher.get_block_number_through(second) == block_number, 'Wrong block number');
+        }
+    "#
+            )
+        ),
+        execution_info_checker()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
+
+#[test]
+fn warp_in_nested_calls() {
+    let test = test_case!(
+        &format!(
+            "{DEPLOY_EXECUTION_INFO_CHECKER}{}",
+            indoc!(
+                r#"
+        #[test]
+        fn test_warp_in_nested_calls() {
+            let first = deploy_execution_info_checker();
+            let second = deploy_execution_info_checker();
+            let dispatcher = IExecutionInfoCheckerDispatcher { contract_address: first };
+            let block_timestamp = dispatcher.get_block_timestamp();
+
+            start_warp(second.into(), 345);
+            assert(dispatcher.get_block_timestamp_through(second) == 345, 'Wrong block timestamp');
+            assert(dispatcher.get_block_timestamp() == block_timestamp, 'Wrong block timestamp');
+
+            stop_warp(second.into());
+            assert(dispatcher.get_block_timestamp_through(second) == block_timestamp, 'Wrong block timestamp');
+        }
+    "#
+            )
+        ),
+        execution_info_checker()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
+
+#[test]
+fn mock_call_in_nested_calls() {
+    let test = test_case!(
+        &format!(
+            "{DEPLOY_EXECUTION_INFO_CHECKER}{}",
+            indoc!(
+                r#"
+        #[starknet::interface]
+        trait IHelloStarknet<TContractState> {
+            fn increase_balance(ref self: TContractState, amount: felt252);
+            fn get_balance(self: @TContractState) -> felt252;
+        }
+
+        #[test]
+        fn test_mock_call_in_nested_calls() {
+            let checker = deploy_execution_info_checker();
+            let class_hash = declare('HelloStarknet').unwrap();
+            let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @ArrayTrait::new() };
+            let hello_starknet = deploy(prepared).unwrap().try_into().unwrap();
+            let dispatcher = IExecutionInfoCheckerDispatcher { contract_address: checker };
+            IHelloStarknetDispatcher { contract_address: hello_starknet }.increase_balance(5);
+
+            mock_call(hello_starknet.into(), 'get_balance', array![456]);
+            assert(dispatcher.get_balance_through(hello_starknet) == 456, 'Wrong balance');
+            assert(IHelloStarknetDispatcher { contract_address: hello_starknet }.get_balance() == 456, 'Wrong balance');
+
+            stop_mock_call(hello_starknet.into(), 'get_balance');
+            assert(dispatcher.get_balance_through(hello_starknet) == 5, 'Wrong balance');
+        }
+    "#
+            )
+        ),
+        execution_info_checker(),
+        Contract::from_code_path(
+            "HelloStarknet".to_string(),
+            Path::new("tests/data/contracts/hello_starknet.cairo"),
+        )
+        .unwrap()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
@@ -1,6 +1,6 @@
 use crate::integration::common::corelib::{corelib, predeployed_contracts};
 use crate::integration::common::runner::Contract;
-use crate::{assert_case_output_contains, assert_failed, assert_passed, test_case};
+use crate::{assert_failed, assert_passed, test_case};
 use camino::Utf8PathBuf;
 use forge::run;
 use indoc::indoc;
@@ -14,6 +14,7 @@ fn error_handling() {
         use result::ResultTrait;
         use cheatcodes::RevertedTransactionTrait;
         use cheatcodes::PreparedContract;
+        use cheatcodes::DeployError;
         use array::ArrayTrait;
         
         #[test]
@@ -26,10 +27,11 @@ fn error_handling() {
         
             match deploy(prepared_contract) {
                 Result::Ok(_) => panic_with_felt252('Should have panicked'),
-                Result::Err(x) => {
+                Result::Err(DeployError::ConstructorReverted(x)) => {
                     assert(*x.panic_data.at(0_usize) == 'PANIK', *x.panic_data.at(0_usize));
                     assert(*x.panic_data.at(1_usize) == 'DEJTA', *x.panic_data.at(1_usize));
-                }
+                },
+                Result::Err(_) => panic_with_felt252('Wrong deploy error'),
             }
         }
     "#
@@ -236,6 +238,7 @@ fn test_deploy_fails_with_incorrect_class_hash() {
         use result::ResultTrait;
         use cheatcodes::RevertedTransactionTrait;
         use cheatcodes::PreparedContract;
+        use cheatcodes::DeployError;
         use array::ArrayTrait;
 
         #[test]
@@ -243,7 +246,13 @@ fn test_deploy_fails_with_incorrect_class_hash() {
             let mut calldata = ArrayTrait::new();
 
             let prepared = PreparedContract { class_hash: 'made-up-class-hash', constructor_calldata: @calldata };
-            let contract_address = deploy(prepared).unwrap();
+            match deploy(prepared) {
+                Result::Ok(_) => panic_with_felt252('Should have failed'),
+                Result::Err(DeployError::ClassNotDeclared(class_hash)) => {
+                    assert(class_hash == 'made-up-class-hash', 'Wrong class hash');
+                },
+                Result::Err(_) => panic_with_felt252('Wrong deploy error'),
+            }
         }
     "#
         ),
@@ -272,8 +281,7 @@ fn test_deploy_fails_with_incorrect_class_hash() {
     )
     .unwrap();
 
-    assert_case_output_contains!(result, "deploy_non_existing_class_hash", "not declared");
-    assert_failed!(result);
+    assert_passed!(result);
 }
 
 #[test]
@@ -357,3 +365,259 @@ fn test_deploy_invokes_the_constructor() {
     .unwrap();
     assert_passed!(result);
 }
+
+#[test]
+fn deploy_returns_constructor_panic_data_unchanged() {
+    let test = test_case!(
+        indoc!(
+            r#"
+        use result::ResultTrait;
+        use cheatcodes::PreparedContract;
+        use cheatcodes::DeployError;
+        use array::ArrayTrait;
+
+        #[test]
+        fn test_deploy_panic_data() {
+            let class_hash = declare('PanickingConstructor').expect('Could not declare');
+            let prepared_contract = PreparedContract {
+                class_hash: class_hash,
+                constructor_calldata: @ArrayTrait::new()
+            };
+
+            match deploy(prepared_contract) {
+                Result::Ok(_) => panic_with_felt252('Should have panicked'),
+                Result::Err(DeployError::ConstructorReverted(x)) => {
+                    assert(x.panic_data.len() == 3, 'Wrong panic_data len');
+                    assert(*x.panic_data.at(0) == 1234, *x.panic_data.at(0));
+                    assert(*x.panic_data.at(1) == 'a, b', *x.panic_data.at(1));
+                    assert(*x.panic_data.at(2) == 0, *x.panic_data.at(2));
+                },
+                Result::Err(_) => panic_with_felt252('Wrong deploy error'),
+            }
+        }
+    "#
+        ),
+        Contract::new(
+            "PanickingConstructor",
+            indoc!(
+                r#"
+                #[starknet::contract]
+                mod PanickingConstructor {
+                    use array::ArrayTrait;
+
+                    #[storage]
+                    struct Storage {}
+
+                    #[constructor]
+                    fn constructor(ref self: ContractState) {
+                        let mut panic_data = ArrayTrait::new();
+                        panic_data.append(1234);
+                        panic_data.append('a, b');
+                        panic_data.append(0);
+                        panic(panic_data);
+                    }
+                }
+    "#
+            )
+        )
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
+
+#[test]
+fn deploy_fails_when_address_is_already_deployed() {
+    let test = test_case!(
+        indoc!(
+            r#"
+        use result::ResultTrait;
+        use cheatcodes::PreparedContract;
+        use cheatcodes::DeployError;
+        use array::ArrayTrait;
+
+        #[test]
+        fn test_deploy_twice() {
+            let class_hash = declare('HelloStarknet').unwrap();
+            let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @ArrayTrait::new() };
+            let contract_address = deploy(prepared.clone()).unwrap();
+
+            match deploy(prepared) {
+                Result::Ok(_) => panic_with_felt252('Should have failed'),
+                Result::Err(DeployError::AlreadyDeployed(address)) => {
+                    assert(address == contract_address, 'Wrong contract address');
+                },
+                Result::Err(_) => panic_with_felt252('Wrong deploy error'),
+            }
+        }
+    "#
+        ),
+        Contract::from_code_path(
+            "HelloStarknet".to_string(),
+            Path::new("tests/data/contracts/hello_starknet.cairo"),
+        )
+        .unwrap()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
+
+#[test]
+fn deploy_at_given_address() {
+    let test = test_case!(
+        indoc!(
+            r#"
+        use result::ResultTrait;
+        use option::OptionTrait;
+        use traits::TryInto;
+        use array::ArrayTrait;
+        use starknet::ContractAddress;
+        use starknet::Felt252TryIntoContractAddress;
+        use cheatcodes::PreparedContract;
+        use cheatcodes::DeployError;
+
+        #[starknet::interface]
+        trait IHelloStarknet<TContractState> {
+            fn increase_balance(ref self: TContractState, amount: felt252);
+            fn get_balance(self: @TContractState) -> felt252;
+        }
+
+        #[test]
+        fn test_deploy_at() {
+            let class_hash = declare('HelloStarknet').unwrap();
+            let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @ArrayTrait::new() };
+            let contract_address = deploy_at(prepared.clone(), 0x1234).unwrap();
+            assert(contract_address == 0x1234, 'Wrong contract address');
+
+            let contract_address: ContractAddress = contract_address.try_into().unwrap();
+            let dispatcher = IHelloStarknetDispatcher { contract_address };
+            dispatcher.increase_balance(100);
+            assert(dispatcher.get_balance() == 100, 'balance == 100');
+
+            match deploy_at(prepared, 0x1234) {
+                Result::Ok(_) => panic_with_felt252('Should have failed'),
+                Result::Err(DeployError::AlreadyDeployed(address)) => {
+                    assert(address == 0x1234, 'Wrong contract address');
+                },
+                Result::Err(_) => panic_with_felt252('Wrong deploy error'),
+            }
+        }
+    "#
+        ),
+        Contract::from_code_path(
+            "HelloStarknet".to_string(),
+            Path::new("tests/data/contracts/hello_starknet.cairo"),
+        )
+        .unwrap()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
+
+#[test]
+fn deploy_from_given_deployer() {
+    let test = test_case!(
+        indoc!(
+            r#"
+        use result::ResultTrait;
+        use option::OptionTrait;
+        use traits::{Into, TryInto};
+        use array::ArrayTrait;
+        use starknet::{ContractAddress, ContractAddressIntoFelt252};
+        use starknet::Felt252TryIntoContractAddress;
+        use cheatcodes::PreparedContract;
+
+        #[starknet::interface]
+        trait IDeployerGetter<TContractState> {
+            fn get_deployer(self: @TContractState) -> ContractAddress;
+        }
+
+        #[test]
+        fn test_deploy_from() {
+            let class_hash = declare('DeployerGetter').unwrap();
+            let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @ArrayTrait::new() };
+            let contract_address = deploy_from(prepared.clone(), 0x5678).unwrap();
+            let other_address = deploy(prepared).unwrap();
+            assert(contract_address != other_address, 'Addresses should differ');
+
+            let contract_address: ContractAddress = contract_address.try_into().unwrap();
+            let dispatcher = IDeployerGetterDispatcher { contract_address };
+            let deployer: felt252 = dispatcher.get_deployer().into();
+            assert(deployer == 0x5678, 'Wrong deployer');
+        }
+    "#
+        ),
+        Contract::new(
+            "DeployerGetter",
+            indoc!(
+                r#"
+                #[starknet::contract]
+                mod DeployerGetter {
+                    use starknet::ContractAddress;
+                    use starknet::get_caller_address;
+
+                    #[storage]
+                    struct Storage {
+                        deployer: ContractAddress,
+                    }
+
+                    #[constructor]
+                    fn constructor(ref self: ContractState) {
+                        self.deployer.write(get_caller_address());
+                    }
+
+                    #[external(v0)]
+                    fn get_deployer(self: @ContractState) -> ContractAddress {
+                        self.deployer.read()
+                    }
+                }
+    "#
+            )
+        )
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
@@ -1,11 +1,12 @@
 use crate::integration::common::runner::Contract;
-use crate::{assert_passed, test_case};
+use crate::{assert_case_output_contains, assert_passed, test_case};
 use camino::Utf8PathBuf;
 use std::path::Path;
 use std::string::ToString;
 
 use crate::integration::common::corelib::{corelib, predeployed_contracts};
 use forge::run;
+use forge::test_case_summary::TestCaseSummary;
 use indoc::indoc;
 
 #[test]
@@ -230,3 +231,132 @@ fn handling_errors() {
 
     assert_passed!(result);
 }
+
+#[test]
+fn call_to_not_deployed_contract_fails_the_test() {
+    let test = test_case!(indoc!(
+        r#"
+        use starknet::ContractAddress;
+        use starknet::Felt252TryIntoContractAddress;
+        use traits::TryInto;
+        use option::OptionTrait;
+
+        #[starknet::interface]
+        trait IHelloStarknet<TContractState> {
+            fn get_balance(self: @TContractState) -> felt252;
+        }
+
+        #[test]
+        fn call_not_deployed() {
+            let contract_address: ContractAddress = 0x1234.try_into().unwrap();
+            let dispatcher = IHelloStarknetDispatcher { contract_address };
+            dispatcher.get_balance();
+        }
+
+        #[test]
+        fn passing_after_failure() {
+            assert(1 == 1, 'simple check');
+        }
+    "#
+    ));
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_case_output_contains!(result, "call_not_deployed", "Transaction execution error");
+    let summaries = &result[0].test_case_summaries;
+    assert_eq!(summaries.len(), 2);
+    assert!(matches!(summaries[1], TestCaseSummary::Passed { .. }));
+}
+
+#[test]
+fn exceeding_recursion_depth_fails_the_test() {
+    let test = test_case!(
+        indoc!(
+            r#"
+        use array::ArrayTrait;
+        use result::ResultTrait;
+        use option::OptionTrait;
+        use traits::TryInto;
+        use starknet::ContractAddress;
+        use starknet::Felt252TryIntoContractAddress;
+        use cheatcodes::PreparedContract;
+
+        #[starknet::interface]
+        trait IRecursive<TContractState> {
+            fn recurse(self: @TContractState, depth: felt252);
+        }
+
+        fn deploy_recursive() -> IRecursiveDispatcher {
+            let class_hash = declare('Recursive').unwrap();
+            let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @ArrayTrait::new() };
+            let contract_address: ContractAddress = deploy(prepared).unwrap().try_into().unwrap();
+            IRecursiveDispatcher { contract_address }
+        }
+
+        #[test]
+        fn recursion_within_limit() {
+            deploy_recursive().recurse(10);
+        }
+
+        #[test]
+        fn recursion_over_limit() {
+            deploy_recursive().recurse(100);
+        }
+    "#
+        ),
+        Contract::new(
+            "Recursive",
+            indoc!(
+                r#"
+                #[starknet::interface]
+                trait IRecursive<TContractState> {
+                    fn recurse(self: @TContractState, depth: felt252);
+                }
+
+                #[starknet::contract]
+                mod Recursive {
+                    use super::{IRecursiveDispatcher, IRecursiveDispatcherTrait};
+
+                    #[storage]
+                    struct Storage {}
+
+                    #[external(v0)]
+                    impl RecursiveImpl of super::IRecursive<ContractState> {
+                        fn recurse(self: @ContractState, depth: felt252) {
+                            if depth != 0 {
+                                IRecursiveDispatcher { contract_address: starknet::get_contract_address() }
+                                    .recurse(depth - 1);
+                            }
+                        }
+                    }
+                }
+    "#
+            )
+        )
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_case_output_contains!(result, "recursion_over_limit", "Recursion depth exceeded");
+    let summaries = &result[0].test_case_summaries;
+    assert_eq!(summaries.len(), 2);
+    assert!(matches!(summaries[0], TestCaseSummary::Passed { .. }));
+}
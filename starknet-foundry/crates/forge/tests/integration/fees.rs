@@ -0,0 +1,153 @@
+use crate::integration::common::corelib::{corelib, predeployed_contracts};
+use crate::integration::common::runner::Contract;
+use crate::{assert_case_output_contains, assert_failed, assert_passed, test_case};
+use camino::Utf8PathBuf;
+use forge::test_case_summary::TestCaseSummary;
+use forge::{run, RunnerConfig};
+use indoc::indoc;
+use std::path::Path;
+
+const BALANCE_OF: &str = indoc!(
+    r#"
+    use array::ArrayTrait;
+    use array::SpanTrait;
+    use option::OptionTrait;
+    use result::ResultTrait;
+    use traits::TryInto;
+    use starknet::SyscallResultTrait;
+    use starknet::contract_address_const;
+    use cheatcodes::PreparedContract;
+
+    const TEST_ACCOUNT_ADDRESS: felt252 = 0x101;
+    const BALANCE_OF_SELECTOR: felt252 = 0x2e4263afad30923c891518314c3c95dbe830a16874e8abc5777a9a20b54c76e;
+
+    fn balance_of(address: felt252) -> u256 {
+        let result = starknet::call_contract_syscall(
+            contract_address_const::<0x1001>(), BALANCE_OF_SELECTOR, array![address].span()
+        ).unwrap_syscall();
+        u256 { low: (*result[0]).try_into().unwrap(), high: (*result[1]).try_into().unwrap() }
+    }
+"#
+);
+
+#[test]
+fn deal_sets_fee_token_balance() {
+    let test = test_case!(&format!(
+        "{BALANCE_OF}{}",
+        indoc!(
+            r#"
+        #[test]
+        fn test_deal() {
+            deal(0x123, u256 { low: 100, high: 2 });
+            assert(balance_of(0x123) == u256 { low: 100, high: 2 }, 'Wrong balance');
+
+            deal(TEST_ACCOUNT_ADDRESS, u256 { low: 5, high: 0 });
+            assert(balance_of(TEST_ACCOUNT_ADDRESS) == u256 { low: 5, high: 0 }, 'Wrong account balance');
+        }
+    "#
+        )
+    ));
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+    assert!(matches!(
+        result[0].test_case_summaries[0],
+        TestCaseSummary::Passed { fee: None, .. }
+    ));
+}
+
+#[test]
+fn fees_are_charged_and_reported() {
+    let test = test_case!(
+        &format!(
+            "{BALANCE_OF}{}",
+            indoc!(
+                r#"
+        #[test]
+        fn test_fees() {
+            let balance_before = balance_of(TEST_ACCOUNT_ADDRESS);
+
+            let class_hash = declare('HelloStarknet').unwrap();
+            let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @ArrayTrait::new() };
+            deploy(prepared).unwrap();
+
+            assert(balance_of(TEST_ACCOUNT_ADDRESS) < balance_before, 'Fee should be charged');
+        }
+    "#
+            )
+        ),
+        Contract::from_code_path(
+            "HelloStarknet".to_string(),
+            Path::new("tests/data/contracts/hello_starknet.cairo"),
+        )
+        .unwrap()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &RunnerConfig::default().with_charge_fees(true),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+    assert!(matches!(
+        result[0].test_case_summaries[0],
+        TestCaseSummary::Passed { fee: Some(fee), .. } if fee > 0
+    ));
+}
+
+#[test]
+fn insufficient_balance_fails_the_test() {
+    let test = test_case!(
+        &format!(
+            "{BALANCE_OF}{}",
+            indoc!(
+                r#"
+        #[test]
+        fn test_insufficient_balance() {
+            deal(TEST_ACCOUNT_ADDRESS, u256 { low: 0, high: 0 });
+            declare('HelloStarknet').unwrap();
+        }
+    "#
+            )
+        ),
+        Contract::from_code_path(
+            "HelloStarknet".to_string(),
+            Path::new("tests/data/contracts/hello_starknet.cairo"),
+        )
+        .unwrap()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &RunnerConfig::default().with_charge_fees(true),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_failed!(result);
+    assert_case_output_contains!(
+        result,
+        "test_insufficient_balance",
+        "Insufficient fee token balance of the test account"
+    );
+}
@@ -0,0 +1,57 @@
+use crate::integration::common::corelib::{corelib, predeployed_contracts};
+use crate::integration::common::runner::Contract;
+use crate::{assert_passed, test_case};
+use camino::Utf8PathBuf;
+use forge::run;
+use indoc::indoc;
+use std::path::Path;
+
+#[test]
+fn contract_introspection() {
+    let test = test_case!(
+        indoc!(
+            r#"
+        use result::ResultTrait;
+        use array::ArrayTrait;
+        use cheatcodes::PreparedContract;
+
+        const TEST_ACCOUNT_ADDRESS: felt252 = 0x101;
+
+        #[test]
+        fn test_introspection() {
+            assert(!is_deployed(0x1234), 'Should not be deployed');
+            assert(get_class_hash(0x1234) == 0, 'Class hash should be 0');
+            let nonce_before_declare = get_nonce(TEST_ACCOUNT_ADDRESS);
+
+            let class_hash = declare('HelloStarknet').unwrap();
+            assert(get_nonce(TEST_ACCOUNT_ADDRESS) == nonce_before_declare + 1, 'Nonce should increase');
+
+            let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @ArrayTrait::new() };
+            let contract_address = deploy_at(prepared, 0x1234).unwrap();
+
+            assert(is_deployed(contract_address), 'Should be deployed');
+            assert(get_class_hash(contract_address) == class_hash, 'Wrong class hash');
+            assert(get_nonce(contract_address) == 0, 'Wrong nonce');
+        }
+    "#
+        ),
+        Contract::from_code_path(
+            "HelloStarknet".to_string(),
+            Path::new("tests/data/contracts/hello_starknet.cairo"),
+        )
+        .unwrap()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
@@ -1,4 +1,12 @@
+mod accounts;
+mod cheatcodes_in_contracts;
+mod cheats;
 pub(crate) mod common;
 mod declare;
 mod deploy;
 mod dispatchers;
+mod fees;
+mod introspection;
+mod replace_bytecode;
+mod reverts;
+mod signing;
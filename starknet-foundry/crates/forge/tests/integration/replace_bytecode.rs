@@ -0,0 +1,147 @@
+use crate::integration::common::corelib::{corelib, predeployed_contracts};
+use crate::integration::common::runner::Contract;
+use crate::{assert_passed, test_case};
+use camino::Utf8PathBuf;
+use forge::run;
+use indoc::indoc;
+use std::path::Path;
+
+#[test]
+fn replace_bytecode() {
+    let test = test_case!(
+        indoc!(
+            r#"
+        use result::ResultTrait;
+        use option::OptionTrait;
+        use traits::TryInto;
+        use array::ArrayTrait;
+        use starknet::ContractAddress;
+        use starknet::Felt252TryIntoContractAddress;
+        use cheatcodes::PreparedContract;
+
+        #[starknet::interface]
+        trait IHelloStarknet<TContractState> {
+            fn increase_balance(ref self: TContractState, amount: felt252);
+            fn get_balance(self: @TContractState) -> felt252;
+        }
+
+        #[test]
+        fn test_replace_bytecode() {
+            let class_hash = declare('HelloStarknet').unwrap();
+            let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @ArrayTrait::new() };
+            let contract_address = deploy(prepared).unwrap();
+            let new_class_hash = declare('DoubledBalance').unwrap();
+
+            let dispatcher = IHelloStarknetDispatcher { contract_address: contract_address.try_into().unwrap() };
+            dispatcher.increase_balance(100);
+            assert(dispatcher.get_balance() == 100, 'balance == 100');
+
+            replace_bytecode(contract_address, new_class_hash).unwrap();
+
+            // Storage is kept, only the code is replaced
+            assert(dispatcher.get_balance() == 200, 'balance == 200');
+        }
+    "#
+        ),
+        Contract::from_code_path(
+            "HelloStarknet".to_string(),
+            Path::new("tests/data/contracts/hello_starknet.cairo"),
+        )
+        .unwrap(),
+        Contract::new(
+            "DoubledBalance",
+            indoc!(
+                r#"
+                #[starknet::contract]
+                mod DoubledBalance {
+                    #[storage]
+                    struct Storage {
+                        balance: felt252,
+                    }
+
+                    #[external(v0)]
+                    fn increase_balance(ref self: ContractState, amount: felt252) {
+                        self.balance.write(self.balance.read() + amount);
+                    }
+
+                    #[external(v0)]
+                    fn get_balance(self: @ContractState) -> felt252 {
+                        self.balance.read() * 2
+                    }
+                }
+    "#
+            )
+        )
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
+
+#[test]
+fn replace_bytecode_errors() {
+    let test = test_case!(
+        indoc!(
+            r#"
+        use result::ResultTrait;
+        use array::ArrayTrait;
+        use cheatcodes::PreparedContract;
+        use cheatcodes::ReplaceBytecodeError;
+
+        #[test]
+        fn test_undeclared_class_hash() {
+            let class_hash = declare('HelloStarknet').unwrap();
+            let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @ArrayTrait::new() };
+            let contract_address = deploy(prepared).unwrap();
+
+            match replace_bytecode(contract_address, 'made-up-class-hash') {
+                Result::Ok(_) => panic_with_felt252('Should have failed'),
+                Result::Err(error) => {
+                    assert(error == ReplaceBytecodeError::UndeclaredClassHash(()), 'Wrong error');
+                },
+            }
+        }
+
+        #[test]
+        fn test_contract_not_deployed() {
+            let class_hash = declare('HelloStarknet').unwrap();
+
+            match replace_bytecode(0x1234, class_hash) {
+                Result::Ok(_) => panic_with_felt252('Should have failed'),
+                Result::Err(error) => {
+                    assert(error == ReplaceBytecodeError::ContractNotDeployed(()), 'Wrong error');
+                },
+            }
+        }
+    "#
+        ),
+        Contract::from_code_path(
+            "HelloStarknet".to_string(),
+            Path::new("tests/data/contracts/hello_starknet.cairo"),
+        )
+        .unwrap()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
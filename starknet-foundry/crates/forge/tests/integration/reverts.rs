@@ -0,0 +1,137 @@
+use crate::integration::common::corelib::{corelib, predeployed_contracts};
+use crate::integration::common::runner::Contract;
+use crate::{assert_passed, test_case};
+use camino::Utf8PathBuf;
+use forge::run;
+use indoc::indoc;
+use std::path::Path;
+
+const DEPLOY_PANICKING_WRITER: &str = indoc!(
+    r#"
+    use array::ArrayTrait;
+    use result::ResultTrait;
+    use option::OptionTrait;
+    use traits::Into;
+    use traits::TryInto;
+    use starknet::ContractAddress;
+    use starknet::ContractAddressIntoFelt252;
+    use starknet::Felt252TryIntoContractAddress;
+    use cheatcodes::PreparedContract;
+
+    const WRITE_AND_PANIC_SELECTOR: felt252 = 0x1e624ee5fecdc79e7b2337f4e82b8099e96634716503b0d8e419a77ef2c4519;
+
+    #[starknet::interface]
+    trait IPanickingWriter<TContractState> {
+        fn get_value(self: @TContractState) -> felt252;
+        fn write_and_panic(ref self: TContractState, value: felt252);
+        fn write_and_call(
+            ref self: TContractState,
+            value: felt252,
+            writer: ContractAddress,
+            selector: felt252,
+            calldata: Array<felt252>
+        ) -> bool;
+    }
+
+    fn deploy_panicking_writer() -> ContractAddress {
+        let class_hash = declare('PanickingWriter').unwrap();
+        let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @array![1, 0] };
+        deploy(prepared).unwrap().try_into().unwrap()
+    }
+"#
+);
+
+fn panicking_writer() -> Contract {
+    Contract::from_code_path(
+        "PanickingWriter".to_string(),
+        Path::new("tests/data/contracts/panicking_writer.cairo"),
+    )
+    .unwrap()
+}
+
+#[test]
+fn storage_writes_of_failed_calls_are_reverted() {
+    let test = test_case!(
+        &format!(
+            "{DEPLOY_PANICKING_WRITER}{}",
+            indoc!(
+                r#"
+        #[test]
+        fn test_failed_call_is_reverted() {
+            let writer = deploy_panicking_writer();
+
+            let result = starknet::call_contract_syscall(writer, WRITE_AND_PANIC_SELECTOR, array![5].span());
+            assert(result.is_err(), 'Call should have failed');
+
+            let dispatcher = IPanickingWriterDispatcher { contract_address: writer };
+            assert(dispatcher.get_value() == 1, 'Write was not reverted');
+        }
+
+        #[test]
+        fn test_failed_nested_call_is_reverted() {
+            let caller = deploy_panicking_writer();
+            let writer = deploy_panicking_writer();
+            let dispatcher = IPanickingWriterDispatcher { contract_address: caller };
+
+            let succeeded = dispatcher.write_and_call(3, writer, WRITE_AND_PANIC_SELECTOR, array![5]);
+            assert(!succeeded, 'Call should have failed');
+
+            assert(dispatcher.get_value() == 3, 'Caller write was reverted');
+            let writer_dispatcher = IPanickingWriterDispatcher { contract_address: writer };
+            assert(writer_dispatcher.get_value() == 1, 'Write was not reverted');
+        }
+    "#
+            )
+        ),
+        panicking_writer()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
+
+#[test]
+fn failed_deployments_are_reverted() {
+    let test = test_case!(
+        &format!(
+            "{DEPLOY_PANICKING_WRITER}{}",
+            indoc!(
+                r#"
+        #[test]
+        fn test_failed_deployment_is_reverted() {
+            let class_hash = declare('PanickingWriter').unwrap();
+            let prepared = PreparedContract { class_hash: class_hash, constructor_calldata: @array![1, 1] };
+            let contract_address = precalculate_address(@prepared);
+
+            assert(deploy(prepared).is_err(), 'Deployment should have failed');
+            assert(!is_deployed(contract_address), 'Deployment was not reverted');
+        }
+    "#
+            )
+        ),
+        panicking_writer()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
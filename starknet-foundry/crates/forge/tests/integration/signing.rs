@@ -0,0 +1,132 @@
+use crate::integration::common::corelib::{corelib, predeployed_contracts};
+use crate::integration::common::runner::Contract;
+use crate::{assert_passed, test_case};
+use camino::Utf8PathBuf;
+use forge::run;
+use indoc::indoc;
+use std::path::Path;
+
+#[test]
+fn signature_is_verified_in_cairo() {
+    let test = test_case!(indoc!(
+        r#"
+        #[test]
+        fn test_signing() {
+            let keys = derive_stark_keys(123);
+            let same_keys = derive_stark_keys(123);
+            assert(keys.private_key == same_keys.private_key, 'Keys should be deterministic');
+            assert(keys.public_key == same_keys.public_key, 'Keys should be deterministic');
+
+            let message_hash = 0x1234;
+            let (r, s) = sign_message_hash(keys.private_key, message_hash);
+            assert(ecdsa::check_ecdsa_signature(message_hash, keys.public_key, r, s), 'Invalid signature');
+
+            let other_keys = generate_stark_keys();
+            assert(other_keys.public_key != keys.public_key, 'Keys should be random');
+            assert(!ecdsa::check_ecdsa_signature(message_hash, other_keys.public_key, r, s), 'Signature should not match');
+        }
+    "#
+    ));
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
+
+#[test]
+fn transactions_signed_for_ecdsa_account() {
+    let test = test_case!(
+        indoc!(
+            r#"
+        use array::ArrayTrait;
+        use option::OptionTrait;
+        use result::ResultTrait;
+        use traits::TryInto;
+        use starknet::account::Call;
+        use cheatcodes::{
+            AccountTransaction, DeployAccountTransaction, InvokeTransaction, PreparedContract,
+            TransactionError
+        };
+
+        const INCREASE_BALANCE_SELECTOR: felt252 = 0x362398bec32bc0ebb411203221a35a0301193a96f317ebe5e40be9f60d15320;
+
+        fn sign(private_key: felt252, transaction: @AccountTransaction) -> Array<felt252> {
+            let (r, s) = sign_message_hash(private_key, transaction_hash(transaction));
+            array![r, s]
+        }
+
+        fn increase_balance(account_address: felt252, contract_address: felt252) -> AccountTransaction {
+            let calls = array![
+                Call { to: contract_address.try_into().unwrap(), selector: INCREASE_BALANCE_SELECTOR, calldata: array![42] }
+            ];
+            AccountTransaction::Invoke(InvokeTransaction { sender_address: account_address, calls: calls })
+        }
+
+        #[test]
+        fn test_ecdsa_account() {
+            let keys = derive_stark_keys('seed');
+            let class_hash = declare('EcdsaAccount').unwrap();
+            let transaction = AccountTransaction::DeployAccount(
+                DeployAccountTransaction {
+                    class_hash: class_hash, contract_address_salt: 1, constructor_calldata: array![keys.public_key]
+                }
+            );
+            let signature = sign(keys.private_key, @transaction);
+            let account_address = *execute_transaction(transaction, signature).unwrap()[0];
+
+            let prepared = PreparedContract {
+                class_hash: declare('HelloStarknet').unwrap(), constructor_calldata: @ArrayTrait::new()
+            };
+            let contract_address = deploy(prepared).unwrap();
+
+            let transaction = increase_balance(account_address, contract_address);
+            let signature = sign(generate_stark_keys().private_key, @transaction);
+            match execute_transaction(transaction, signature) {
+                Result::Ok(_) => panic_with_felt252('Should fail validation'),
+                Result::Err(TransactionError::ValidationFailed(x)) => {
+                    assert(*x.panic_data.at(0) == 'INVALID_SIGNATURE', 'Wrong panic data');
+                },
+                Result::Err(_) => panic_with_felt252('Wrong transaction error'),
+            }
+
+            let transaction = increase_balance(account_address, contract_address);
+            let signature = sign(keys.private_key, @transaction);
+            execute_transaction(transaction, signature).unwrap();
+            assert(get_nonce(account_address) == 2, 'Wrong nonce');
+        }
+    "#
+        ),
+        Contract::from_code_path(
+            "EcdsaAccount".to_string(),
+            Path::new("tests/data/contracts/ecdsa_account.cairo"),
+        )
+        .unwrap(),
+        Contract::from_code_path(
+            "HelloStarknet".to_string(),
+            Path::new("tests/data/contracts/hello_starknet.cairo"),
+        )
+        .unwrap()
+    );
+
+    let result = run(
+        &test.path().unwrap(),
+        &test.path().unwrap().join("src/lib.cairo"),
+        &Some(test.linked_libraries()),
+        &Default::default(),
+        Some(&Utf8PathBuf::from_path_buf(corelib().to_path_buf()).unwrap()),
+        &test.contracts(corelib().path()).unwrap(),
+        &Utf8PathBuf::from_path_buf(predeployed_contracts().to_path_buf()).unwrap(),
+    )
+    .unwrap();
+
+    assert_passed!(result);
+}
@@ -0,0 +1,181 @@
+use std::fmt;
+
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_defs::ids::ModuleId;
+use cairo_lang_diagnostics::{DiagnosticEntry, Diagnostics, ToOption};
+use cairo_lang_filesystem::db::FilesGroup;
+use cairo_lang_filesystem::ids::{FileId, FileLongId};
+use cairo_lang_filesystem::span::TextSpan;
+use cairo_lang_lowering::db::LoweringGroup;
+use cairo_lang_parser::db::ParserGroup;
+use cairo_lang_semantic::db::SemanticGroup;
+use cairo_lang_utils::Upcast;
+use serde::Serialize;
+
+/// Severity of a compiler diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// Position in a source file, lines and columns are numbered from 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Range of a source file the diagnostic points to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// Diagnostic reported by the compiler when compiling tests
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CompilerDiagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// Path of the file the diagnostic points to, files generated by plugins are reported by name
+    pub file_path: Option<String>,
+    /// Missing if the position is not known, e.g. the file can not be read
+    pub span: Option<Span>,
+}
+
+impl fmt::Display for CompilerDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)?;
+        match (&self.file_path, &self.span) {
+            (Some(file_path), Some(span)) => write!(
+                f,
+                "\n --> {file_path}:{}:{}",
+                span.start.line, span.start.column
+            ),
+            (Some(file_path), None) => write!(f, "\n --> {file_path}"),
+            (None, _) => Ok(()),
+        }
+    }
+}
+
+/// Error returned when tests do not compile, contains all errors found by the compiler
+#[derive(Debug)]
+pub struct CompilationError {
+    pub diagnostics: Vec<CompilerDiagnostic>,
+}
+
+impl fmt::Display for CompilationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors = self
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .count();
+        write!(f, "Failed to compile tests, found {errors} error(s)")
+    }
+}
+
+impl std::error::Error for CompilationError {}
+
+/// Collects diagnostics of all crates in the database, like `DiagnosticsReporter::check` does
+#[must_use]
+pub fn collect_diagnostics(db: &RootDatabase) -> Vec<CompilerDiagnostic> {
+    let mut diagnostics = vec![];
+    for crate_id in db.crates() {
+        let Ok(main_file) = db.module_main_file(ModuleId::CrateRoot(crate_id)) else {
+            diagnostics.push(CompilerDiagnostic {
+                severity: Severity::Error,
+                message: "Failed to get the main file of a crate".to_string(),
+                file_path: None,
+                span: None,
+            });
+            continue;
+        };
+        if db.file_content(main_file).is_none() {
+            diagnostics.push(CompilerDiagnostic {
+                severity: Severity::Error,
+                message: "Failed to read the file".to_string(),
+                file_path: Some(file_path(db, main_file)),
+                span: None,
+            });
+        }
+
+        for module_id in db.crate_modules(crate_id).iter() {
+            for file_id in db.module_files(*module_id).unwrap_or_default().iter() {
+                let files_db: &dyn FilesGroup = db;
+                diagnostics.extend(compiler_diagnostics(
+                    files_db,
+                    &db.file_syntax_diagnostics(*file_id),
+                ));
+            }
+            if let Some(semantic_diagnostics) =
+                db.module_semantic_diagnostics(*module_id).to_option()
+            {
+                let semantic_db: &dyn SemanticGroup = db;
+                diagnostics.extend(compiler_diagnostics(semantic_db, &semantic_diagnostics));
+            }
+            if let Some(lowering_diagnostics) =
+                db.module_lowering_diagnostics(*module_id).to_option()
+            {
+                let lowering_db: &dyn LoweringGroup = db;
+                diagnostics.extend(compiler_diagnostics(lowering_db, &lowering_diagnostics));
+            }
+        }
+    }
+    diagnostics
+}
+
+fn compiler_diagnostics<TEntry: DiagnosticEntry>(
+    db: &TEntry::DbType,
+    diagnostics: &Diagnostics<TEntry>,
+) -> Vec<CompilerDiagnostic> {
+    let files_db: &dyn FilesGroup = db.upcast();
+    diagnostics
+        .get_all()
+        .iter()
+        .map(|entry| {
+            let location = entry.location(db);
+            CompilerDiagnostic {
+                // Cairo compiler reports every diagnostic as an error
+                severity: Severity::Error,
+                message: entry.format(db),
+                file_path: Some(file_path(files_db, location.file_id)),
+                span: span(files_db, location.file_id, location.span),
+            }
+        })
+        .collect()
+}
+
+fn file_path(db: &dyn FilesGroup, file_id: FileId) -> String {
+    match db.lookup_intern_file(file_id) {
+        FileLongId::OnDisk(path) => path.to_string_lossy().to_string(),
+        FileLongId::Virtual(_) => file_id.file_name(db),
+    }
+}
+
+fn span(db: &dyn FilesGroup, file_id: FileId, span: TextSpan) -> Option<Span> {
+    let start = span.start.position_in_file(db, file_id)?;
+    let end = span.end.position_in_file(db, file_id)?;
+    Some(Span {
+        start: Position {
+            line: start.line + 1,
+            column: start.col + 1,
+        },
+        end: Position {
+            line: end.line + 1,
+            column: end.col + 1,
+        },
+    })
+}
@@ -0,0 +1,111 @@
+use anyhow::Result;
+use cairo_lang_defs::ids::TopLevelLanguageElementId;
+use cairo_lang_filesystem::cfg::Cfg;
+use rayon::prelude::{IntoParallelIterator, ParallelExtend, ParallelIterator};
+use salsa::ParallelDatabase;
+use serde::Serialize;
+
+use crate::{
+    find_all_tests, function_location, group_roots_by_crate_name, setup_tests_database,
+    FunctionLocation, LinkedLibrary, PanicExpectation, SingleTestConfig, TestExpectation,
+    TestsDatabase,
+};
+
+/// Panic expected by a test marked with `#[should_panic]`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpectedPanic {
+    /// Any panic data is accepted
+    Any,
+    /// Panic data has to be equal to these felts, written as decimal numbers
+    Exact(Vec<String>),
+}
+
+/// Attributes of a test changing how it is run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TestAttributes {
+    pub available_gas: Option<usize>,
+    pub max_steps: Option<usize>,
+    /// Timeout in seconds
+    pub timeout: Option<u64>,
+    pub should_panic: Option<ExpectedPanic>,
+    pub ignored: bool,
+}
+
+impl From<SingleTestConfig> for TestAttributes {
+    fn from(config: SingleTestConfig) -> Self {
+        let should_panic = match config.expectation {
+            TestExpectation::Success => None,
+            TestExpectation::Panics(PanicExpectation::Any) => Some(ExpectedPanic::Any),
+            TestExpectation::Panics(PanicExpectation::Exact(values)) => Some(ExpectedPanic::Exact(
+                values.iter().map(ToString::to_string).collect(),
+            )),
+        };
+        Self {
+            available_gas: config.available_gas,
+            max_steps: config.max_steps,
+            timeout: config.timeout,
+            should_panic,
+            ignored: config.ignored,
+        }
+    }
+}
+
+/// Test found in the crates compiled from a test root
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiscoveredTest {
+    /// Fully qualified path of the test function, e.g. `tests::erc20::test_transfer`
+    pub path: String,
+    /// Missing for tests generated by plugins
+    pub location: Option<FunctionLocation>,
+    pub attributes: TestAttributes,
+}
+
+/// Finds tests in the crates compiled from the test roots without generating Sierra for them,
+/// roots share compilation databases like in `collect_tests_from_roots` and are searched in parallel.
+/// Compilation errors are returned as `CompilationError`.
+pub fn discover_tests(
+    input_paths: &[&str],
+    linked_libraries: Option<Vec<LinkedLibrary>>,
+    corelib_path: Option<&str>,
+    cfg_items: &[Cfg],
+) -> Result<Vec<DiscoveredTest>> {
+    let mut discovered_tests: Vec<(usize, Vec<DiscoveredTest>)> = vec![];
+    for roots in group_roots_by_crate_name(input_paths) {
+        let paths: Vec<&str> = roots.iter().map(|(_, input_path)| *input_path).collect();
+        let TestsDatabase {
+            db, main_crate_ids, ..
+        } = setup_tests_database(&paths, linked_libraries.clone(), corelib_path, cfg_items)?;
+
+        let snapshots: Vec<_> = roots
+            .iter()
+            .zip(main_crate_ids)
+            .map(|((index, _), main_crate_ids)| (*index, main_crate_ids, db.snapshot()))
+            .collect();
+        discovered_tests.par_extend(snapshots.into_par_iter().map(
+            |(index, main_crate_ids, db)| {
+                let tests = find_all_tests(&*db, main_crate_ids)
+                    .into_iter()
+                    .map(|(func_id, config)| DiscoveredTest {
+                        path: strip_crate_path(&func_id.full_path(&*db)),
+                        location: function_location(&*db, &func_id),
+                        attributes: config.into(),
+                    })
+                    .collect();
+                (index, tests)
+            },
+        ));
+    }
+
+    discovered_tests.sort_by_key(|(index, _)| *index);
+    Ok(discovered_tests
+        .into_iter()
+        .flat_map(|(_, tests)| tests)
+        .collect())
+}
+
+/// Paths of functions from `lib.cairo` crates are prefixed with the crate directory,
+/// which is not a part of the module path
+fn strip_crate_path(path: &str) -> String {
+    path.rsplit('/').next().unwrap_or(path).to_string()
+}
@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use cairo_lang_defs::plugin::{
+    DynGeneratedFileAuxData, MacroPlugin, PluginDiagnostic, PluginGeneratedFile, PluginResult,
+};
+use cairo_lang_semantic::plugin::{AsDynMacroPlugin, SemanticPlugin, TrivialPluginAuxData};
+use cairo_lang_syntax::attribute::structured::{
+    AttributeArg, AttributeArgVariant, AttributeListStructurize,
+};
+use cairo_lang_syntax::node::db::SyntaxGroup;
+use cairo_lang_syntax::node::ids::SyntaxStablePtrId;
+use cairo_lang_syntax::node::{ast, Terminal, TypedSyntaxNode};
+use smol_str::SmolStr;
+
+/// Cairo short strings hold at most 31 characters
+const MAX_SHORT_STRING_LENGTH: usize = 31;
+
+/// Implements `forge_print::Format` for structs and enums with `#[derive(Format)]`
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct FormatDerivePlugin;
+
+impl MacroPlugin for FormatDerivePlugin {
+    fn generate_code(&self, db: &dyn SyntaxGroup, item_ast: ast::Item) -> PluginResult {
+        let (attributes, name, generic_params) = match &item_ast {
+            ast::Item::Struct(struct_ast) => (
+                struct_ast.attributes(db),
+                struct_ast.name(db),
+                struct_ast.generic_params(db),
+            ),
+            ast::Item::Enum(enum_ast) => (
+                enum_ast.attributes(db),
+                enum_ast.name(db),
+                enum_ast.generic_params(db),
+            ),
+            _ => return PluginResult::default(),
+        };
+        if !derives_format(db, &attributes) {
+            return PluginResult::default();
+        }
+
+        let mut diagnostics = vec![];
+        if !matches!(generic_params, ast::OptionWrappedGenericParamList::Empty(_)) {
+            diagnostics.push(PluginDiagnostic {
+                stable_ptr: generic_params.stable_ptr().untyped(),
+                message: "Format can not be derived for generic types.".into(),
+            });
+        }
+        let name = short_string(db, &name, &mut diagnostics);
+
+        let body = match &item_ast {
+            ast::Item::Struct(struct_ast) => {
+                let fields: Vec<SmolStr> = struct_ast
+                    .members(db)
+                    .elements(db)
+                    .iter()
+                    .map(|member| short_string(db, &member.name(db), &mut diagnostics))
+                    .collect();
+                struct_fmt_body(&name, &fields)
+            }
+            ast::Item::Enum(enum_ast) => {
+                let variants: Vec<SmolStr> = enum_ast
+                    .variants(db)
+                    .elements(db)
+                    .iter()
+                    .map(|variant| short_string(db, &variant.name(db), &mut diagnostics))
+                    .collect();
+                enum_fmt_body(&name, &variants)
+            }
+            _ => unreachable!(),
+        };
+
+        if !diagnostics.is_empty() {
+            return PluginResult {
+                code: None,
+                diagnostics,
+                remove_original_item: false,
+            };
+        }
+        PluginResult {
+            code: Some(PluginGeneratedFile {
+                name: "format_impl".into(),
+                content: format!(
+                    "impl {name}Format of forge_print::Format<{name}> {{\n    fn fmt(self: @{name}, \
+                     ref output: Array<felt252>) {{\n{body}    }}\n}}\n"
+                ),
+                aux_data: DynGeneratedFileAuxData(Arc::new(TrivialPluginAuxData {})),
+            }),
+            diagnostics,
+            remove_original_item: false,
+        }
+    }
+}
+
+impl AsDynMacroPlugin for FormatDerivePlugin {
+    fn as_dyn_macro_plugin<'a>(self: Arc<Self>) -> Arc<dyn MacroPlugin + 'a>
+    where
+        Self: 'a,
+    {
+        self
+    }
+}
+impl SemanticPlugin for FormatDerivePlugin {}
+
+fn derives_format(db: &dyn SyntaxGroup, attributes: &ast::AttributeList) -> bool {
+    attributes
+        .structurize(db)
+        .iter()
+        .filter(|attr| attr.id.as_str() == "derive")
+        .flat_map(|attr| attr.args.iter())
+        .any(|arg| {
+            matches!(
+                arg,
+                AttributeArg {
+                    variant: AttributeArgVariant::Unnamed {
+                        value: ast::Expr::Path(path),
+                        ..
+                    },
+                    ..
+                } if path.as_syntax_node().get_text_without_trivia(db) == "Format"
+            )
+        })
+}
+
+/// Names are passed to the formatter as short strings
+fn short_string(
+    db: &dyn SyntaxGroup,
+    identifier: &ast::TerminalIdentifier,
+    diagnostics: &mut Vec<PluginDiagnostic>,
+) -> SmolStr {
+    let text = identifier.text(db);
+    if text.len() > MAX_SHORT_STRING_LENGTH {
+        let stable_ptr: SyntaxStablePtrId = identifier.stable_ptr().untyped();
+        diagnostics.push(PluginDiagnostic {
+            stable_ptr,
+            message: format!(
+                "Format can not be derived for names longer than {MAX_SHORT_STRING_LENGTH} \
+                 characters."
+            ),
+        });
+    }
+    text
+}
+
+fn struct_fmt_body(name: &str, fields: &[SmolStr]) -> String {
+    let mut body = format!(
+        "        array::ArrayTrait::append(ref output, forge_print::STRUCT);\n        \
+         array::ArrayTrait::append(ref output, '{name}');\n        \
+         array::ArrayTrait::append(ref output, {});\n",
+        fields.len()
+    );
+    for field in fields {
+        body.push_str(&format!(
+            "        array::ArrayTrait::append(ref output, '{field}');\n        \
+             forge_print::Format::fmt(self.{field}, ref output);\n"
+        ));
+    }
+    body
+}
+
+fn enum_fmt_body(name: &str, variants: &[SmolStr]) -> String {
+    let mut body = format!(
+        "        array::ArrayTrait::append(ref output, forge_print::ENUM);\n        \
+         array::ArrayTrait::append(ref output, '{name}');\n        match self {{\n"
+    );
+    for variant in variants {
+        body.push_str(&format!(
+            "            {name}::{variant}(value) => {{\n                \
+             array::ArrayTrait::append(ref output, '{variant}');\n                \
+             forge_print::Format::fmt(value, ref output);\n            }},\n"
+        ));
+    }
+    body.push_str("        }\n");
+    body
+}
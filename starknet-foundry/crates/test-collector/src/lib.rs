@@ -1,21 +1,25 @@
+use crate::diagnostics::{collect_diagnostics, CompilationError};
+use crate::format_derive_plugin::FormatDerivePlugin;
 use crate::sierra_casm_generator::SierraCasmGenerator;
 use anyhow::{anyhow, Context, Result};
 use cairo_felt::Felt252;
 use cairo_lang_compiler::db::RootDatabase;
-use cairo_lang_compiler::diagnostics::DiagnosticsReporter;
 use cairo_lang_compiler::project::setup_project;
 use cairo_lang_compiler::project::{
     get_main_crate_ids_from_project, setup_single_file_project,
     update_crate_roots_from_project_config, ProjectError,
 };
 use cairo_lang_debug::DebugWithDb;
-use cairo_lang_defs::ids::{FreeFunctionId, FunctionWithBodyId, ModuleItemId};
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_defs::ids::{
+    FreeFunctionId, FunctionWithBodyId, LanguageElementId, ModuleItemId, TopLevelLanguageElementId,
+};
 use cairo_lang_defs::plugin::PluginDiagnostic;
 use cairo_lang_diagnostics::ToOption;
 use cairo_lang_filesystem::cfg::{Cfg, CfgSet};
 use cairo_lang_filesystem::db::init_dev_corelib;
 use cairo_lang_filesystem::detect::detect_corelib;
-use cairo_lang_filesystem::ids::CrateId;
+use cairo_lang_filesystem::ids::{CrateId, FileLongId};
 use cairo_lang_lowering::ids::ConcreteFunctionWithBodyId;
 use cairo_lang_project::{DeserializationError, ProjectConfig, ProjectConfigContent};
 use cairo_lang_semantic::db::SemanticGroup;
@@ -35,11 +39,18 @@ use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
 use cairo_lang_utils::OptionHelper;
 use itertools::Itertools;
 use num_traits::ToPrimitive;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use salsa::ParallelDatabase;
+use serde::Serialize;
 use smol_str::SmolStr;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+pub mod diagnostics;
+pub mod discovery;
+pub mod format_derive_plugin;
 pub mod sierra_casm_generator;
 
 pub fn build_project_config(
@@ -100,6 +111,10 @@ pub enum TestExpectation {
 pub struct SingleTestConfig {
     /// The amount of gas the test requested.
     pub available_gas: Option<usize>,
+    /// The maximum number of steps the test can execute.
+    pub max_steps: Option<usize>,
+    /// The maximum time in seconds the test can run for.
+    pub timeout: Option<u64>,
     /// The expected result of the run.
     pub expectation: TestExpectation,
     /// Should the test be ignored.
@@ -142,6 +157,8 @@ pub fn try_extract_test_config(
         .iter()
         .find(|attr| attr.id.as_str() == "available_gas");
     let should_panic_attr = attrs.iter().find(|attr| attr.id.as_str() == "should_panic");
+    let max_steps_attr = attrs.iter().find(|attr| attr.id.as_str() == "max_steps");
+    let timeout_attr = attrs.iter().find(|attr| attr.id.as_str() == "timeout");
     let mut diagnostics = vec![];
     if let Some(attr) = test_attr {
         if !attr.args.is_empty() {
@@ -151,9 +168,15 @@ pub fn try_extract_test_config(
             });
         }
     } else {
-        for attr in [ignore_attr, available_gas_attr, should_panic_attr]
-            .into_iter()
-            .flatten()
+        for attr in [
+            ignore_attr,
+            available_gas_attr,
+            should_panic_attr,
+            max_steps_attr,
+            timeout_attr,
+        ]
+        .into_iter()
+        .flatten()
         {
             diagnostics.push(PluginDiagnostic {
                 stable_ptr: attr.id_stable_ptr.untyped(),
@@ -172,27 +195,13 @@ pub fn try_extract_test_config(
     } else {
         false
     };
-    let available_gas = if let Some(attr) = available_gas_attr {
-        if let [AttributeArg {
-            variant:
-                AttributeArgVariant::Unnamed {
-                    value: ast::Expr::Literal(literal),
-                    ..
-                },
-            ..
-        }] = &attr.args[..]
-        {
-            literal.numeric_value(db).unwrap_or_default().to_usize()
-        } else {
-            diagnostics.push(PluginDiagnostic {
-                stable_ptr: attr.id_stable_ptr.untyped(),
-                message: "Attribute should have a single value argument.".into(),
-            });
-            None
-        }
-    } else {
-        None
-    };
+    let available_gas =
+        available_gas_attr.and_then(|attr| extract_numeric_value(db, attr, &mut diagnostics));
+    let max_steps =
+        max_steps_attr.and_then(|attr| extract_numeric_value(db, attr, &mut diagnostics));
+    let timeout = timeout_attr
+        .and_then(|attr| extract_numeric_value(db, attr, &mut diagnostics))
+        .and_then(|timeout| u64::try_from(timeout).ok());
     let (should_panic, expected_panic_value) = if let Some(attr) = should_panic_attr {
         if attr.args.is_empty() {
             (true, None)
@@ -220,6 +229,8 @@ pub fn try_extract_test_config(
     } else {
         Some(SingleTestConfig {
             available_gas,
+            max_steps,
+            timeout,
             expectation: if should_panic {
                 TestExpectation::Panics(if let Some(values) = expected_panic_value {
                     PanicExpectation::Exact(values)
@@ -234,6 +245,31 @@ pub fn try_extract_test_config(
     })
 }
 
+/// Extracts the value of an attribute with a single numeric argument, e.g. `#[available_gas(100)]`.
+fn extract_numeric_value(
+    db: &dyn SyntaxGroup,
+    attr: &Attribute,
+    diagnostics: &mut Vec<PluginDiagnostic>,
+) -> Option<usize> {
+    if let [AttributeArg {
+        variant:
+            AttributeArgVariant::Unnamed {
+                value: ast::Expr::Literal(literal),
+                ..
+            },
+        ..
+    }] = &attr.args[..]
+    {
+        literal.numeric_value(db).unwrap_or_default().to_usize()
+    } else {
+        diagnostics.push(PluginDiagnostic {
+            stable_ptr: attr.id_stable_ptr.untyped(),
+            message: "Attribute should have a single value argument.".into(),
+        });
+        None
+    }
+}
+
 /// Tries to extract the relevant expected panic values.
 fn extract_panic_values(db: &dyn SyntaxGroup, attr: &Attribute) -> Option<Vec<Felt252>> {
     let [
@@ -275,53 +311,272 @@ pub struct LinkedLibrary {
 pub struct TestCase {
     pub name: String,
     pub available_gas: Option<usize>,
+    pub max_steps: Option<usize>,
+    /// Timeout in seconds
+    pub timeout: Option<u64>,
 }
 
-// returns tuple[sierra if no output_path, list[test_name, test_config]]
-pub fn collect_tests(
-    input_path: &str,
-    output_path: Option<&str>,
+/// Location of a function body in a source file, lines are numbered from 1
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct FunctionLocation {
+    pub file_path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// Locations of the functions defined on disk, keyed by the full path of a function
+pub type FunctionLocations = HashMap<String, FunctionLocation>;
+
+/// Finds locations of all free and impl functions defined in the files of the given crates
+pub fn find_function_locations(db: &dyn SemanticGroup, crates: &[CrateId]) -> FunctionLocations {
+    let mut locations = HashMap::new();
+    for crate_id in crates {
+        let modules = db.crate_modules(*crate_id);
+        for module_id in modules.iter() {
+            if let Ok(free_functions) = db.module_free_functions_ids(*module_id) {
+                for func_id in free_functions.iter() {
+                    if let Some(location) = function_location(db, func_id) {
+                        locations.insert(func_id.full_path(db.upcast()), location);
+                    }
+                }
+            }
+            let Ok(impls) = db.module_impls_ids(*module_id) else {
+                continue;
+            };
+            for impl_def_id in impls.iter() {
+                let Ok(impl_functions) = db.impl_functions(*impl_def_id) else {
+                    continue;
+                };
+                for func_id in impl_functions.values() {
+                    if let Some(location) = function_location(db, func_id) {
+                        locations.insert(func_id.full_path(db.upcast()), location);
+                    }
+                }
+            }
+        }
+    }
+    locations
+}
+
+pub(crate) fn function_location(
+    db: &dyn SemanticGroup,
+    func_id: &impl LanguageElementId,
+) -> Option<FunctionLocation> {
+    let defs_db: &dyn DefsGroup = db.upcast();
+    let file_id = func_id.module_file_id(defs_db).file_id(defs_db).ok()?;
+    // Functions generated by plugins live in virtual files, they have no lines to report
+    let FileLongId::OnDisk(file_path) = db.lookup_intern_file(file_id) else {
+        return None;
+    };
+    let span = func_id
+        .untyped_stable_ptr(defs_db)
+        .lookup(db.upcast())
+        .span_without_trivia(db.upcast());
+    let start = span.start.position_in_file(db.upcast(), file_id)?;
+    let end = span.end.position_in_file(db.upcast(), file_id)?;
+
+    Some(FunctionLocation {
+        file_path: file_path.to_string_lossy().to_string(),
+        start_line: start.line + 1,
+        end_line: end.line + 1,
+    })
+}
+
+/// Cfg set the tests are compiled with, `test` is always enabled
+#[must_use]
+pub fn test_cfg_set(cfg_items: &[Cfg]) -> CfgSet {
+    CfgSet::from_iter(
+        [Cfg::name("test")]
+            .into_iter()
+            .chain(cfg_items.iter().cloned()),
+    )
+}
+
+/// Database with the test roots and linked libraries set up, checked for compilation errors.
+/// Crates are compiled once and shared by all test roots.
+pub(crate) struct TestsDatabase {
+    pub db: RootDatabase,
+    /// Crates compiled from every test root, in the order of the roots
+    pub main_crate_ids: Vec<Vec<CrateId>>,
+    /// Crates of the linked libraries
+    pub library_crate_ids: Vec<CrateId>,
+}
+
+pub(crate) fn setup_tests_database(
+    input_paths: &[&str],
     linked_libraries: Option<Vec<LinkedLibrary>>,
-    builtins: Option<Vec<&str>>,
     corelib_path: Option<&str>,
-) -> Result<(Program, Vec<TestCase>)> {
+    cfg_items: &[Cfg],
+) -> Result<TestsDatabase> {
     // code taken from crates/cairo-lang-test-runner/src/lib.rs
-    let db = &mut {
+    let mut db = {
         let mut b = RootDatabase::builder();
-        b.with_cfg(CfgSet::from_iter([Cfg::name("test")]));
+        b.with_cfg(test_cfg_set(cfg_items));
         b.with_semantic_plugin(Arc::new(TestPlugin::default()));
         b.with_semantic_plugin(Arc::new(StarkNetPlugin::default()));
+        b.with_semantic_plugin(Arc::new(FormatDerivePlugin::default()));
         b.build()?
     };
 
     init_dev_corelib(
-        db,
+        &mut db,
         corelib_path.map_or_else(
             || detect_corelib().ok_or_else(|| anyhow!("Failed to load development corelib")),
             |corelib_path| Ok(corelib_path.into()),
         )?,
     );
 
-    let main_crate_ids = setup_project(db, Path::new(&input_path))
-        .with_context(|| format!("Failed to setup project for path({input_path})"))?;
+    let main_crate_ids = input_paths
+        .iter()
+        .map(|input_path| {
+            setup_project(&mut db, Path::new(input_path))
+                .with_context(|| format!("Failed to setup project for path({input_path})"))
+        })
+        .collect::<Result<Vec<_>>>()?;
 
+    let mut library_crate_ids = vec![];
     if let Some(linked_libraries) = linked_libraries {
         for linked_library in linked_libraries {
-            setup_project_without_cairo_project_toml(
-                db,
-                &linked_library.path,
-                &linked_library.name,
-            )
-            .with_context(|| format!("Failed to add linked library ({})", linked_library.name))?;
+            library_crate_ids.extend(
+                setup_project_without_cairo_project_toml(
+                    &mut db,
+                    &linked_library.path,
+                    &linked_library.name,
+                )
+                .with_context(|| {
+                    format!("Failed to add linked library ({})", linked_library.name)
+                })?,
+            );
+        }
+    }
+
+    let diagnostics = collect_diagnostics(&db);
+    if !diagnostics.is_empty() {
+        return Err(CompilationError { diagnostics }.into());
+    }
+    Ok(TestsDatabase {
+        db,
+        main_crate_ids,
+        library_crate_ids,
+    })
+}
+
+/// Name of the crate `setup_project` creates for the test root file
+fn root_crate_name(input_path: &str) -> Option<String> {
+    let path = Path::new(input_path);
+    let file_stem = path.file_stem()?.to_str()?;
+    if file_stem == "lib" {
+        let canonical_path = path.canonicalize().ok()?;
+        Some(canonical_path.parent()?.file_name()?.to_str()?.to_string())
+    } else {
+        Some(file_stem.to_string())
+    }
+}
+
+/// Splits test roots into groups compiled in one database each. Crates are identified by name,
+/// so roots creating crates with the same name are put in different groups.
+pub(crate) fn group_roots_by_crate_name<'a>(input_paths: &[&'a str]) -> Vec<Vec<(usize, &'a str)>> {
+    let mut groups: Vec<(HashSet<String>, Vec<(usize, &'a str)>)> = vec![];
+    for (index, input_path) in input_paths.iter().enumerate() {
+        let Some(crate_name) = root_crate_name(input_path) else {
+            groups.push((HashSet::new(), vec![(index, *input_path)]));
+            continue;
+        };
+        match groups
+            .iter_mut()
+            .find(|(crate_names, _)| !crate_names.is_empty() && !crate_names.contains(&crate_name))
+        {
+            Some((crate_names, roots)) => {
+                crate_names.insert(crate_name);
+                roots.push((index, *input_path));
+            }
+            None => groups.push((HashSet::from([crate_name]), vec![(index, *input_path)])),
         }
     }
+    groups.into_iter().map(|(_, roots)| roots).collect()
+}
+
+/// Compiled tests of a single test root
+pub type CollectedTests = (Program, Vec<TestCase>, FunctionLocations);
+
+/// Compiles tests of all test roots, returning them in the order of the roots.
+/// Roots share a compilation database, so the corelib and linked libraries are compiled only once.
+pub fn collect_tests_from_roots(
+    input_paths: &[&str],
+    linked_libraries: Option<Vec<LinkedLibrary>>,
+    builtins: Option<Vec<&str>>,
+    corelib_path: Option<&str>,
+    cfg_items: &[Cfg],
+) -> Result<Vec<CollectedTests>> {
+    let builtins = builtins.map_or_else(Vec::new, |builtins| {
+        builtins.iter().map(|s| (*s).to_string()).collect()
+    });
+
+    let mut collected_tests: Vec<(usize, CollectedTests)> = vec![];
+    for roots in group_roots_by_crate_name(input_paths) {
+        let paths: Vec<&str> = roots.iter().map(|(_, input_path)| *input_path).collect();
+        let TestsDatabase {
+            db,
+            main_crate_ids,
+            library_crate_ids,
+        } = setup_tests_database(&paths, linked_libraries.clone(), corelib_path, cfg_items)?;
+
+        // Snapshots share the compiled crates, so the roots are compiled in parallel
+        let snapshots: Vec<_> = roots
+            .iter()
+            .zip(main_crate_ids)
+            .map(|((index, _), main_crate_ids)| (*index, main_crate_ids, db.snapshot()))
+            .collect();
+        let tests: Result<Vec<(usize, CollectedTests)>> = snapshots
+            .into_par_iter()
+            .map(|(index, main_crate_ids, db)| {
+                let tests = compile_tests(&db, main_crate_ids, &library_crate_ids, &builtins)?;
+                Ok((index, tests))
+            })
+            .collect();
+        collected_tests.extend(tests?);
+    }
+
+    collected_tests.sort_by_key(|(index, _)| *index);
+    Ok(collected_tests
+        .into_iter()
+        .map(|(_, tests)| tests)
+        .collect())
+}
+
+// returns tuple[sierra if no output_path, list[test_name, test_config], function locations]
+pub fn collect_tests(
+    input_path: &str,
+    output_path: Option<&str>,
+    linked_libraries: Option<Vec<LinkedLibrary>>,
+    builtins: Option<Vec<&str>>,
+    corelib_path: Option<&str>,
+    cfg_items: &[Cfg],
+) -> Result<CollectedTests> {
+    let (sierra_program, collected_tests, function_locations) = collect_tests_from_roots(
+        &[input_path],
+        linked_libraries,
+        builtins,
+        corelib_path,
+        cfg_items,
+    )?
+    .pop()
+    .context("Failed to collect tests")?;
 
-    if DiagnosticsReporter::stderr().check(db) {
-        return Err(anyhow!(
-            "Failed to add linked library, for a detailed information, please go through the logs \
-             above"
-        ));
+    if let Some(path) = output_path {
+        fs::write(path, sierra_program.to_string()).context("Failed to write output")?;
     }
+    Ok((sierra_program, collected_tests, function_locations))
+}
+
+fn compile_tests(
+    db: &RootDatabase,
+    main_crate_ids: Vec<CrateId>,
+    library_crate_ids: &[CrateId],
+    builtins: &[String],
+) -> Result<CollectedTests> {
+    let mut crates_with_sources = main_crate_ids.clone();
+    crates_with_sources.extend_from_slice(library_crate_ids);
     let all_tests = find_all_tests(db, main_crate_ids);
 
     let z: Vec<ConcreteFunctionWithBodyId> = all_tests
@@ -359,21 +614,18 @@ pub fn collect_tests(
         .map(|(test_name, config)| TestCase {
             name: test_name,
             available_gas: config.available_gas,
+            max_steps: config.max_steps,
+            timeout: config.timeout,
         })
         .collect();
 
     let sierra_program = replace_sierra_ids_in_program(db, &sierra_program);
 
-    let builtins = builtins.map_or_else(Vec::new, |builtins| {
-        builtins.iter().map(|s| (*s).to_string()).collect()
-    });
+    validate_tests(sierra_program.clone(), &collected_tests, builtins)?;
 
-    validate_tests(sierra_program.clone(), &collected_tests, &builtins)?;
+    let function_locations = find_function_locations(db, &crates_with_sources);
 
-    if let Some(path) = output_path {
-        fs::write(path, sierra_program.to_string()).context("Failed to write output")?;
-    }
-    Ok((sierra_program, collected_tests))
+    Ok((sierra_program, collected_tests, function_locations))
 }
 
 fn validate_tests(